@@ -4,6 +4,7 @@ use super::{bites, bites_str, equity, error};
 
 use std::str::FromStr;
 
+#[derive(Clone)]
 struct Tile {
     label: bites_str::BitesStr,
     blank_label: bites_str::BitesStr,
@@ -15,7 +16,7 @@ struct Tile {
     alias_blank_labels: Vec<bites_str::BitesStr>,
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct StaticAlphabet {
     tiles: Vec<Tile>,
     widest_label_len: usize, // in codepoints for now (graphemes is too complex)
@@ -25,6 +26,7 @@ pub struct StaticAlphabet {
     tiles_by_descending_scores: Box<[u8]>,
 }
 
+#[derive(Clone)]
 pub enum Alphabet {
     Static(StaticAlphabet),
 }
@@ -122,6 +124,32 @@ impl Alphabet {
         Ok(Self::new_static(tiles))
     }
 
+    // the inverse of new_static_from_text.
+    pub fn to_static_text(&self) -> String {
+        let mut s = String::new();
+        for idx in 0..self.len() {
+            let tile = self.get(idx);
+            s.push_str(&format!(
+                "{} {} {} {} {} {}",
+                &*tile.label,
+                &*tile.blank_label,
+                tile.freq,
+                tile.score,
+                tile.is_vowel as u8,
+                tile.alias_labels.len(),
+            ));
+            for label in tile.alias_labels.iter() {
+                s.push_str(&format!(" {}", &**label));
+            }
+            s.push_str(&format!(" {}", tile.alias_blank_labels.len()));
+            for label in tile.alias_blank_labels.iter() {
+                s.push_str(&format!(" {}", &**label));
+            }
+            s.push('\n');
+        }
+        s
+    }
+
     #[inline(always)]
     pub fn len(&self) -> u8 {
         match self {
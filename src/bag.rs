@@ -88,6 +88,65 @@ impl Bag {
         }
     }
 
+    // Like replenish, but skips over excluded tiles, leaving them where they were.
+    pub fn replenish_excluding(
+        &mut self,
+        rack: &mut Vec<u8>,
+        rack_size: usize,
+        player_index: usize,
+        excluded: &[u8],
+    ) {
+        let from_back = player_index.is_multiple_of(2);
+        let mut skipped = Vec::new();
+        while rack.len() < rack_size {
+            let Some(tile) = (if from_back {
+                self.pop_back()
+            } else {
+                self.pop_front()
+            }) else {
+                break;
+            };
+            if excluded.contains(&tile) {
+                skipped.push(tile);
+            } else {
+                rack.push(tile);
+            }
+        }
+        if from_back {
+            for &tile in skipped.iter().rev() {
+                self.unpop_back(tile);
+            }
+        } else {
+            for &tile in skipped.iter().rev() {
+                self.unpop_front(tile);
+            }
+        }
+    }
+
+    // Exact inverse of pop_back, for undo.
+    pub fn unpop_back(&mut self, tile: u8) {
+        self.tiles.push(tile);
+    }
+
+    // Exact inverse of pop_front, for undo.
+    pub fn unpop_front(&mut self, tile: u8) {
+        self.fc -= 1;
+        self.tiles[self.fc] = tile;
+    }
+
+    // Inverse of replenish, drawn is what it pushed onto the rack.
+    pub fn unreplenish(&mut self, drawn: &[u8], player_index: usize) {
+        if player_index.is_multiple_of(2) {
+            for &tile in drawn.iter().rev() {
+                self.unpop_back(tile);
+            }
+        } else {
+            for &tile in drawn.iter().rev() {
+                self.unpop_front(tile);
+            }
+        }
+    }
+
     pub fn return_tile(&mut self, tile: u8) {
         if self.fc > 0 {
             self.fc -= 1;
@@ -109,6 +168,20 @@ impl Bag {
         self.tiles.extend(iter);
     }
 
+    // The exact layout including dead space, since put_back cannot be inverted.
+    pub fn layout(&self) -> BagLayout {
+        BagLayout {
+            tiles: self.tiles[..].into(),
+            fc: self.fc,
+        }
+    }
+
+    pub fn set_layout(&mut self, layout: &BagLayout) {
+        self.tiles.clear();
+        self.tiles.extend_from_slice(&layout.tiles);
+        self.fc = layout.fc;
+    }
+
     pub fn as_slice(&self) -> &[u8] {
         &self.tiles[self.fc..]
     }
@@ -269,6 +342,12 @@ impl Bag {
     }
 }
 
+#[derive(Clone, PartialEq)]
+pub struct BagLayout {
+    tiles: Box<[u8]>,
+    fc: usize,
+}
+
 impl Clone for Bag {
     #[inline(always)]
     fn clone(&self) -> Self {
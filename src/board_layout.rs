@@ -1,6 +1,6 @@
 // Copyright (C) 2020-2026 Andy Kurnia.
 
-use super::matrix;
+use super::{error, matrix};
 
 #[derive(Clone)]
 pub struct Premium {
@@ -73,7 +73,7 @@ fn del() -> Premium {
     }
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct StaticBoardLayout {
     premiums: Box<[Premium]>,
     dim: matrix::Dim,
@@ -85,6 +85,7 @@ pub struct StaticBoardLayout {
     is_symmetric: bool,
 }
 
+#[derive(Clone)]
 pub enum BoardLayout {
     Static(StaticBoardLayout),
 }
@@ -219,6 +220,90 @@ impl BoardLayout {
     }
 }
 
+// Build a layout from a text grid, one line per row and one character per
+// square. Blank lines and surrounding whitespace are ignored.
+//   .  plain square          #  punctured square (no tile may be played)
+//   d  double letter (DLS)   D  double word (DWS)
+//   t  triple letter (TLS)   T  triple word (TWS)
+//   q  quadruple letter      Q  quadruple word
+//   *  start square, on a double word (as on the standard board)
+//   +  start square, plain
+// There must be exactly one start square. Transposed premiums, the danger
+// star squares and symmetry are derived as for the built-in layouts.
+pub fn make_board_layout_from_text(text: &str) -> error::Returns<BoardLayout> {
+    let mut premiums = Vec::new();
+    let mut star = None;
+    let mut rows = 0usize;
+    let mut cols = None;
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut num_cols = 0usize;
+        for c in line.chars() {
+            premiums.push(match c {
+                '.' => fvs(),
+                '#' => del(),
+                'd' => dls(),
+                't' => tls(),
+                'q' => qls(),
+                'D' => dws(),
+                'T' => tws(),
+                'Q' => qws(),
+                '*' | '+' => {
+                    if star.is_some() {
+                        return_error!(format!(
+                            "row {rows} col {num_cols} (0-based): second start square"
+                        ));
+                    }
+                    star = Some((rows, num_cols));
+                    if c == '*' { dws() } else { fvs() }
+                }
+                _ => {
+                    return_error!(format!(
+                        "row {rows} col {num_cols} (0-based): invalid square {c:?}"
+                    ));
+                }
+            });
+            num_cols += 1;
+        }
+        match cols {
+            None => cols = Some(num_cols),
+            Some(cols) if cols != num_cols => {
+                return_error!(format!(
+                    "row {rows} (0-based): need {cols} cols, found {num_cols} cols"
+                ));
+            }
+            Some(_) => {}
+        }
+        rows += 1;
+    }
+    let Some(cols) = cols else {
+        return_error!("empty board layout".into());
+    };
+    if rows > i8::MAX as usize || cols > i8::MAX as usize {
+        return_error!(format!(
+            "board layout is {rows}x{cols}, at most {}x{} is supported",
+            i8::MAX,
+            i8::MAX
+        ));
+    }
+    let Some((star_row, star_col)) = star else {
+        return_error!("board layout has no start square".into());
+    };
+    Ok(BoardLayout::new_static(StaticBoardLayout {
+        premiums: premiums.into_boxed_slice(),
+        dim: matrix::Dim {
+            rows: rows as i8,
+            cols: cols as i8,
+        },
+        star_row: star_row as i8,
+        star_col: star_col as i8,
+        ..Default::default()
+    }))
+}
+
 // https://en.wikipedia.org/wiki/Scrabble
 pub fn make_standard_board_layout() -> BoardLayout {
     BoardLayout::new_static(StaticBoardLayout {
@@ -1145,3 +1230,83 @@ pub fn make_super_board_layout() -> BoardLayout {
         ..Default::default()
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn same_premiums(a: &[Premium], b: &[Premium]) -> bool {
+        a.len() == b.len()
+            && a.iter().zip(b).all(|(p, q)| {
+                p.word_multiplier == q.word_multiplier && p.tile_multiplier == q.tile_multiplier
+            })
+    }
+
+    // the standard board drawn as text matches the hand-built table, including
+    // everything new_static derives from it.
+    #[test]
+    fn text_layout_matches_standard() {
+        let parsed = make_board_layout_from_text(
+            "
+            T..d...T...d..T
+            .D...t...t...D.
+            ..D...d.d...D..
+            d..D...d...D..d
+            ....D.....D....
+            .t...t...t...t.
+            ..d...d.d...d..
+            T..d...*...d..T
+            ..d...d.d...d..
+            .t...t...t...t.
+            ....D.....D....
+            d..D...d...D..d
+            ..D...d.d...D..
+            .D...t...t...D.
+            T..d...T...d..T
+            ",
+        )
+        .unwrap();
+        let standard = make_standard_board_layout();
+        assert_eq!(parsed.dim().rows, 15);
+        assert_eq!(parsed.dim().cols, 15);
+        assert_eq!(parsed.star_row(), standard.star_row());
+        assert_eq!(parsed.star_col(), standard.star_col());
+        assert!(same_premiums(parsed.premiums(), standard.premiums()));
+        assert!(same_premiums(
+            parsed.transposed_premiums(),
+            standard.transposed_premiums()
+        ));
+        for i in 0..15 {
+            assert_eq!(parsed.danger_star_across(i), standard.danger_star_across(i));
+            assert_eq!(parsed.danger_star_down(i), standard.danger_star_down(i));
+        }
+        assert!(parsed.is_symmetric());
+    }
+
+    // a non-square club board with an off-center plain start square.
+    #[test]
+    fn text_layout_non_square() {
+        let parsed = make_board_layout_from_text("T.#\n.+d\nQ.q\n.t.\n").unwrap();
+        assert_eq!(parsed.dim().rows, 4);
+        assert_eq!(parsed.dim().cols, 3);
+        assert_eq!((parsed.star_row(), parsed.star_col()), (1, 1));
+        assert_eq!(parsed.premiums()[2].tile_multiplier, 0);
+        assert_eq!(parsed.premiums()[6].word_multiplier, 4);
+        // transposed (col 2, row 1) is the d at (row 1, col 2).
+        assert_eq!(parsed.transposed_premiums()[2 * 4 + 1].tile_multiplier, 2);
+        assert!(!parsed.is_symmetric());
+        // danger squares are the premiums in the rows/cols next to the star.
+        assert!(parsed.danger_star_across(0)); // T above
+        assert!(!parsed.danger_star_across(1));
+        assert!(parsed.danger_star_across(2)); // q below
+        assert!(parsed.danger_star_down(1)); // d to the right
+        assert!(!parsed.danger_star_down(3)); // the t is in the star's column
+    }
+
+    #[test]
+    fn text_layout_rejects_bad_grids() {
+        for bad in ["", "...\n..", ".*.\n.+.", "...", ".x*"] {
+            assert!(make_board_layout_from_text(bad).is_err(), "{bad:?}");
+        }
+    }
+}
@@ -1,6 +1,6 @@
 // Copyright (C) 2020-2026 Andy Kurnia.
 
-use super::{bites, error, fash};
+use super::{bites, error, fash, kwg};
 
 // Unconfirmed entries.
 // Memory wastage notes:
@@ -183,11 +183,14 @@ pub fn make_alphagrams(machine_words: &[bites::Bites]) -> Box<[bites::Bites]> {
 
 // build formats
 
+#[derive(Clone, Copy)]
 pub enum BuildContent {
     DawgOnly,
     Gaddawg,
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum BuildLayout {
     Legacy,       // tiny, slow to movegen, leaf-first order. used to be the default.
     Magpie, // big, fast to movegen, BFS order, no tail dedup, easy to read. https://github.com/jvc56/MAGPIE/
@@ -691,16 +694,32 @@ fn do_build<const VARIANT: u8>(
         ),
     };
 
+    lay_out::<VARIANT>(
+        build_content,
+        build_layout,
+        &states,
+        dawg_start_state,
+        gaddag_start_state,
+    )
+}
+
+fn lay_out<const VARIANT: u8>(
+    build_content: BuildContent,
+    build_layout: BuildLayout,
+    states: &[State],
+    dawg_start_state: u32,
+    gaddag_start_state: u32,
+) -> error::Returns<bites::Bites> {
     let mut states_defragger = StatesDefragger {
-        states: &states,
+        states,
         head_indexes: &match build_layout {
             BuildLayout::Legacy
             | BuildLayout::MagpieMerged
             | BuildLayout::Experimental
-            | BuildLayout::Wolges => gen_head_indexes(&states),
+            | BuildLayout::Wolges => gen_head_indexes(states),
             BuildLayout::Magpie => Vec::new(),
         },
-        to_end_lens: &gen_to_end_lens(&states),
+        to_end_lens: &gen_to_end_lens(states),
         destination: &mut vec![0u32; states.len()],
         num_written: match build_content {
             BuildContent::DawgOnly => 1,
@@ -713,21 +732,11 @@ fn do_build<const VARIANT: u8>(
         BuildLayout::Magpie => states_defragger.defrag_magpie(dawg_start_state),
         BuildLayout::MagpieMerged => states_defragger.defrag_magpie_merged(dawg_start_state),
         BuildLayout::Experimental => states_defragger.build_experimental(
-            &gen_num_ways(
-                &states,
-                &build_content,
-                dawg_start_state,
-                gaddag_start_state,
-            ),
-            &gen_top_indexes(&states, states_defragger.head_indexes),
+            &gen_num_ways(states, &build_content, dawg_start_state, gaddag_start_state),
+            &gen_top_indexes(states, states_defragger.head_indexes),
         ),
         BuildLayout::Wolges => states_defragger.build_wolges(
-            &gen_num_ways(
-                &states,
-                &build_content,
-                dawg_start_state,
-                gaddag_start_state,
-            ),
+            &gen_num_ways(states, &build_content, dawg_start_state, gaddag_start_state),
             &build_content,
             dawg_start_state,
         ),
@@ -781,10 +790,294 @@ pub fn build_big(
     do_build::<2>(build_content, build_layout, machine_words)
 }
 
+// The words to add to and remove from a lexicon, as a trie. In the gaddag
+// trie, a separator child (tile 0) stands for the gaddag["AC@"] of a prefix
+// "CA" of an edited word, whose destination may have changed.
+struct EditNode {
+    children: Vec<(u8, u32)>, // sorted by tile, indexes edit nodes.
+    accepts: Option<bool>,    // None if unchanged.
+    dawg_node: u32,           // separator only: the dawg edit node of the prefix.
+    arc_index: u32,           // dawg only: the state made for its children.
+}
+
+impl EditNode {
+    fn new() -> Self {
+        Self {
+            children: Vec::new(),
+            accepts: None,
+            dawg_node: 0,
+            arc_index: 0,
+        }
+    }
+}
+
+fn edit_trie_insert<I: Iterator<Item = u8>>(edit_nodes: &mut Vec<EditNode>, tiles: I) -> u32 {
+    let mut p = 0;
+    for tile in tiles {
+        let children = &edit_nodes[p as usize].children;
+        p = match children.binary_search_by_key(&tile, |&(t, _)| t) {
+            Ok(i) => children[i].1,
+            Err(i) => {
+                let new_p = edit_nodes.len() as u32;
+                edit_nodes[p as usize].children.insert(i, (tile, new_p));
+                edit_nodes.push(EditNode::new());
+                new_p
+            }
+        };
+    }
+    p
+}
+
+// Makes states for an existing kwg with edits applied, in the same order
+// make_dawg makes them for the whole edited word list: each sibling list after
+// the lists of its children. Lists without edits are made once per kwg index,
+// as make_dawg would find every later copy already made.
+struct StateEditor<'a, 'b, N: kwg::Node> {
+    state_maker: StateMaker<'a>,
+    kwg: &'b kwg::Kwg<'b, N>,
+    made: Vec<u32>, // by kwg index, !0 if not yet made.
+}
+
+impl<N: kwg::Node> StateEditor<'_, '_, N> {
+    fn make_transitions(&mut self, node_transitions: Vec<Transition>) -> u32 {
+        self.state_maker.make_state(&node_transitions)
+    }
+
+    // the state for the unedited sibling list at kwg index p.
+    fn make_unedited(&mut self, p: i32) -> u32 {
+        if p <= 0 {
+            return 0;
+        }
+        if self.made[p as usize] != !0 {
+            return self.made[p as usize];
+        }
+        let mut node_transitions = Vec::new();
+        let mut q = p;
+        loop {
+            let node = self.kwg[q];
+            let arc_index = if node.tile() == 0 {
+                // gaddag separators point back to dawg lists, already made.
+                self.made[node.arc_index() as usize]
+            } else {
+                self.make_unedited(node.arc_index())
+            };
+            node_transitions.push(Transition {
+                tile: node.tile(),
+                accepts: node.accepts(),
+                arc_index,
+            });
+            if node.is_end() {
+                break;
+            }
+            q += 1;
+        }
+        let ret = self.make_transitions(node_transitions);
+        self.made[p as usize] = ret;
+        ret
+    }
+
+    // the state for the sibling list at kwg index p (0 if none) merged with
+    // the children of edit_nodes[e]. dawg_edit_nodes resolves separators.
+    fn make_edited(
+        &mut self,
+        p: i32,
+        edit_nodes: &mut [EditNode],
+        e: u32,
+        dawg_edit_nodes: &[EditNode],
+    ) -> u32 {
+        if edit_nodes[e as usize].children.is_empty() {
+            return self.make_unedited(p);
+        }
+        let mut node_transitions = Vec::new();
+        let mut q = if p > 0 { p } else { 0 };
+        let mut child_idx = 0;
+        loop {
+            let old_tile = if q > 0 {
+                self.kwg[q].tile() as u16
+            } else {
+                0x100
+            };
+            let edit_tile = match edit_nodes[e as usize].children.get(child_idx) {
+                Some(&(tile, _)) => tile as u16,
+                None => 0x100,
+            };
+            let tile = old_tile.min(edit_tile);
+            if tile == 0x100 {
+                break;
+            }
+            let old_node = (old_tile == tile).then(|| self.kwg[q]);
+            let edit_child = (edit_tile == tile).then(|| {
+                child_idx += 1;
+                edit_nodes[e as usize].children[child_idx - 1].1
+            });
+            let (accepts, arc_index) = match edit_child {
+                Some(child) => {
+                    let accepts = edit_nodes[child as usize]
+                        .accepts
+                        .unwrap_or_else(|| old_node.is_some_and(|node| node.accepts()));
+                    let arc_index = if tile == 0 {
+                        dawg_edit_nodes[edit_nodes[child as usize].dawg_node as usize].arc_index
+                    } else {
+                        let old_arc_index = old_node.map_or(0, |node| node.arc_index());
+                        self.make_edited(old_arc_index, edit_nodes, child, dawg_edit_nodes)
+                    };
+                    edit_nodes[child as usize].arc_index = arc_index;
+                    (accepts, arc_index)
+                }
+                None => {
+                    let node = old_node.unwrap();
+                    let arc_index = if tile == 0 {
+                        self.made[node.arc_index() as usize]
+                    } else {
+                        self.make_unedited(node.arc_index())
+                    };
+                    (node.accepts(), arc_index)
+                }
+            };
+            // removals may leave nothing below a transition.
+            if accepts || arc_index != 0 {
+                node_transitions.push(Transition {
+                    tile: tile as u8,
+                    accepts,
+                    arc_index,
+                });
+            }
+            if let Some(old_node) = old_node {
+                q = if old_node.is_end() { 0 } else { q + 1 };
+            }
+        }
+        self.make_transitions(node_transitions)
+    }
+}
+
+fn do_edit<const VARIANT: u8, N: kwg::Node>(
+    build_content: BuildContent,
+    build_layout: BuildLayout,
+    kwg: &kwg::Kwg<N>,
+    add_machine_words: &[bites::Bites],
+    remove_machine_words: &[bites::Bites],
+) -> error::Returns<bites::Bites> {
+    if kwg.0.len()
+        < match build_content {
+            BuildContent::DawgOnly => 1,
+            BuildContent::Gaddawg => 2,
+        }
+    {
+        return_error!("kwg is too short for this content".into());
+    }
+    // removing first lets a word in both lists stay.
+    let mut edited_words = Vec::with_capacity(remove_machine_words.len() + add_machine_words.len());
+    edited_words.extend(remove_machine_words.iter().map(|word| (word, false)));
+    edited_words.extend(add_machine_words.iter().map(|word| (word, true)));
+    let mut dawg_edit_nodes = vec![EditNode::new()];
+    for &(word, accepts) in edited_words.iter() {
+        if word.is_empty() || word.contains(&0) {
+            return_error!(format!("cannot edit in {word:?}"));
+        }
+        let p = edit_trie_insert(&mut dawg_edit_nodes, word.iter().copied());
+        dawg_edit_nodes[p as usize].accepts = Some(accepts);
+    }
+    let mut gaddag_edit_nodes = vec![EditNode::new()];
+    if let BuildContent::Gaddawg = build_content {
+        for &(word, accepts) in edited_words.iter() {
+            // CARE = ERAC, RAC@, AC@, C@ (see gen_machine_drowwords).
+            let p = edit_trie_insert(&mut gaddag_edit_nodes, word.iter().rev().copied());
+            gaddag_edit_nodes[p as usize].accepts = Some(accepts);
+            for prefix_len in 1..word.len() {
+                let p = edit_trie_insert(
+                    &mut gaddag_edit_nodes,
+                    word[..prefix_len]
+                        .iter()
+                        .rev()
+                        .copied()
+                        .chain(std::iter::once(0)),
+                );
+                gaddag_edit_nodes[p as usize].dawg_node =
+                    edit_trie_insert(&mut dawg_edit_nodes, word[..prefix_len].iter().copied());
+            }
+        }
+    }
+
+    let mut states = vec![State {
+        tile: 0,
+        accepts: false,
+        arc_index: 0,
+        next_index: 0,
+    }];
+    let mut states_finder = fash::MyHashMap::default();
+    states_finder.insert(states[0].clone(), 0);
+    let mut state_editor = StateEditor {
+        state_maker: StateMaker {
+            states: &mut states,
+            states_finder: &mut states_finder,
+        },
+        kwg,
+        made: vec![!0; kwg.0.len()],
+    };
+    let dawg_start_state =
+        state_editor.make_edited(kwg[0].arc_index(), &mut dawg_edit_nodes, 0, &[]);
+    let gaddag_start_state = match build_content {
+        BuildContent::DawgOnly => 0,
+        BuildContent::Gaddawg => state_editor.make_edited(
+            kwg[1].arc_index(),
+            &mut gaddag_edit_nodes,
+            0,
+            &dawg_edit_nodes,
+        ),
+    };
+
+    lay_out::<VARIANT>(
+        build_content,
+        build_layout,
+        &states,
+        dawg_start_state,
+        gaddag_start_state,
+    )
+}
+
+// Like build, but from a kwg built with the same build_content, plus and minus
+// some machine words (which need not be sorted). Only the nodes on the paths
+// of the edited words are rebuilt from their words; the rest is carried over
+// from the kwg. The result is the same as building the edited word list.
+#[inline(always)]
+pub fn edit<N: kwg::Node>(
+    build_content: BuildContent,
+    build_layout: BuildLayout,
+    kwg: &kwg::Kwg<N>,
+    add_machine_words: &[bites::Bites],
+    remove_machine_words: &[bites::Bites],
+) -> error::Returns<bites::Bites> {
+    do_edit::<1, N>(
+        build_content,
+        build_layout,
+        kwg,
+        add_machine_words,
+        remove_machine_words,
+    )
+}
+
+#[inline(always)]
+pub fn edit_big<N: kwg::Node>(
+    build_content: BuildContent,
+    build_layout: BuildLayout,
+    kwg: &kwg::Kwg<N>,
+    add_machine_words: &[bites::Bites],
+    remove_machine_words: &[bites::Bites],
+) -> error::Returns<bites::Bites> {
+    do_edit::<2, N>(
+        build_content,
+        build_layout,
+        kwg,
+        add_machine_words,
+        remove_machine_words,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::kwg::{self, Node};
+    use rand::prelude::*;
 
     fn collect_dawg_words<N: kwg::Node>(
         kwg: &kwg::Kwg<N>,
@@ -853,4 +1146,83 @@ mod tests {
     fn round_trip_single() {
         round_trip_dawg(BuildLayout::Wolges, &["HELLO"]);
     }
+
+    fn machine_words(words: &[&str]) -> Vec<bites::Bites> {
+        let mut ret = words
+            .iter()
+            .map(|w| w.bytes().map(|b| b - b'A' + 1).collect::<Vec<u8>>()[..].into())
+            .collect::<Vec<bites::Bites>>();
+        ret.sort_unstable();
+        ret.dedup();
+        ret
+    }
+
+    // editing gives the same bytes as building the edited list.
+    fn check_edit(words: &[&str], add: &[&str], remove: &[&str]) {
+        let mut edited = words
+            .iter()
+            .filter(|w| !remove.contains(w) || add.contains(w))
+            .chain(add.iter())
+            .copied()
+            .collect::<Vec<_>>();
+        edited.sort_unstable();
+        let (words, edited) = (machine_words(words), machine_words(&edited));
+        let (add, remove) = (machine_words(add), machine_words(remove));
+        for content in [BuildContent::DawgOnly, BuildContent::Gaddawg] {
+            for layout in [
+                BuildLayout::Legacy,
+                BuildLayout::Magpie,
+                BuildLayout::MagpieMerged,
+                BuildLayout::Experimental,
+                BuildLayout::Wolges,
+            ] {
+                let kwg = kwg::Kwg::<kwg::Node22>::from_bytes_alloc(
+                    &build(content, layout, &words).unwrap(),
+                );
+                assert_eq!(
+                    edit(content, layout, &kwg, &add, &remove).unwrap(),
+                    build(content, layout, &edited).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn edit_matches_full_build() {
+        check_edit(WORD_LIST, &[], &[]);
+        check_edit(WORD_LIST, &["AAHS", "CABS", "ZA", "QI"], &[]);
+        // removing the only word with a prefix drops the prefix too.
+        check_edit(WORD_LIST, &[], &["AAHED", "ABAC", "ZOO", "CATS", "NOPE"]);
+        check_edit(
+            WORD_LIST,
+            &["ABACA", "CART", "AAH"],
+            &["ABA", "CAT", "ZAPS"],
+        );
+        check_edit(WORD_LIST, &[], WORD_LIST);
+        check_edit(&[], WORD_LIST, &[]);
+        check_edit(&["HELLO"], &["HELL", "HELLOS"], &["HELLO"]);
+
+        // many shared prefixes and suffixes over a few letters.
+        let mut rng = rand::rngs::ChaCha20Rng::seed_from_u64(3);
+        let random_words = |rng: &mut rand::rngs::ChaCha20Rng, n| {
+            (0..n)
+                .map(|_| {
+                    let len = rng.random_range(1..7);
+                    (0..len)
+                        .map(|_| ["A", "B", "C", "D"][rng.random_range(0..4)])
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+        };
+        for _ in 0..20 {
+            let words = random_words(&mut rng, 300);
+            let add = random_words(&mut rng, 30);
+            let mut remove = random_words(&mut rng, 10);
+            remove.extend(words.iter().take(30).cloned());
+            fn as_strs(v: &[String]) -> Vec<&str> {
+                v.iter().map(|w| w.as_str()).collect()
+            }
+            check_edit(&as_strs(&words), &as_strs(&add), &as_strs(&remove));
+        }
+    }
 }
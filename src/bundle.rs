@@ -0,0 +1,250 @@
+// Copyright (C) 2020-2026 Andy Kurnia.
+
+// a self-describing lexicon file: a kwg, an optional klv, and what they were
+// built for. a bare .kwg does not say its alphabet, layout, content or node
+// width, so a wrong combination goes unnoticed. the layout is
+//
+//   "WOLGESLB" u32 version u32 num_sections
+//   num_sections * (4-byte tag, u32 len, payload padded to 4 bytes)
+//   u64 fnv-1a checksum of everything before it
+//
+// with all integers little endian. payloads stay 4-byte aligned, so a bundle
+// read into aligned memory can be borrowed in place (see kwg::Kwg::from_bytes).
+
+use super::{alphabet, build, error, game_config};
+
+const MAGIC: &[u8; 8] = b"WOLGESLB";
+const VERSION: u32 = 1;
+const TAG_META: &[u8; 4] = b"META";
+const TAG_ALPHABET: &[u8; 4] = b"ALPH";
+const TAG_KWG: &[u8; 4] = b"KWG\0";
+const TAG_KLV: &[u8; 4] = b"KLV\0";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Content {
+    DawgOnly,
+    Gaddawg,
+    // a dawg of alphagrams, for jumbled games.
+    AlphaDawg,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeWidth {
+    Node22,
+    Node24,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Metadata {
+    pub lexicon_name: String,
+    pub build_date: String,
+    pub layout: build::BuildLayout,
+    pub content: Content,
+    pub node_width: NodeWidth,
+}
+
+pub struct Bundle<'a> {
+    pub metadata: Metadata,
+    // in the alphabet::Alphabet::new_static_from_text format.
+    pub alphabet_text: &'a str,
+    pub kwg: &'a [u8],
+    pub klv: Option<&'a [u8]>,
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut h = 0xcbf29ce484222325u64;
+    for &b in bytes {
+        h = (h ^ b as u64).wrapping_mul(0x100000001b3);
+    }
+    h
+}
+
+#[inline(always)]
+pub fn is_bundle(buf: &[u8]) -> bool {
+    buf.starts_with(MAGIC)
+}
+
+impl<'a> Bundle<'a> {
+    pub fn to_bytes(&self) -> error::Returns<Vec<u8>> {
+        let meta = serde_json::to_vec(&self.metadata)?;
+        let mut sections = vec![
+            (TAG_META, &meta[..]),
+            (TAG_ALPHABET, self.alphabet_text.as_bytes()),
+            (TAG_KWG, self.kwg),
+        ];
+        if let Some(klv) = self.klv {
+            sections.push((TAG_KLV, klv));
+        }
+        let mut ret = Vec::new();
+        ret.extend_from_slice(MAGIC);
+        ret.extend_from_slice(&VERSION.to_le_bytes());
+        ret.extend_from_slice(&(sections.len() as u32).to_le_bytes());
+        for (tag, payload) in sections {
+            if payload.len() > u32::MAX as usize {
+                return_error!(format!("{} section too big", String::from_utf8_lossy(tag)));
+            }
+            ret.extend_from_slice(tag);
+            ret.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+            ret.extend_from_slice(payload);
+            ret.resize(ret.len().next_multiple_of(4), 0);
+        }
+        let checksum = fnv1a(&ret);
+        ret.extend_from_slice(&checksum.to_le_bytes());
+        Ok(ret)
+    }
+
+    pub fn from_bytes(buf: &'a [u8]) -> error::Returns<Self> {
+        if !is_bundle(buf) {
+            return_error!("not a lexicon bundle".into());
+        }
+        if buf.len() < 24 {
+            return_error!("truncated lexicon bundle".into());
+        }
+        let (body, checksum) = buf.split_at(buf.len() - 8);
+        if fnv1a(body) != u64::from_le_bytes(checksum.try_into().unwrap()) {
+            return_error!("lexicon bundle checksum mismatch".into());
+        }
+        let read_u32 = |r: usize| u32::from_le_bytes(body[r..r + 4].try_into().unwrap());
+        let version = read_u32(8);
+        if version != VERSION {
+            return_error!(format!("unsupported lexicon bundle version {version}"));
+        }
+        let num_sections = read_u32(12);
+        let mut meta = None;
+        let mut alphabet_text = None;
+        let mut kwg = None;
+        let mut klv = None;
+        let mut r = 16;
+        for _ in 0..num_sections {
+            if r + 8 > body.len() {
+                return_error!("truncated lexicon bundle".into());
+            }
+            let tag = &body[r..r + 4];
+            let len = read_u32(r + 4) as usize;
+            r += 8;
+            let Some(payload) = body.get(r..r + len) else {
+                return_error!("truncated lexicon bundle".into());
+            };
+            r = (r + len).next_multiple_of(4);
+            match tag.try_into().unwrap() {
+                TAG_META => meta = Some(payload),
+                TAG_ALPHABET => alphabet_text = Some(std::str::from_utf8(payload)?),
+                TAG_KWG => kwg = Some(payload),
+                TAG_KLV => klv = Some(payload),
+                // sections from later writers are skipped.
+                _ => {}
+            }
+        }
+        let (Some(meta), Some(alphabet_text), Some(kwg)) = (meta, alphabet_text, kwg) else {
+            return_error!("lexicon bundle lacks a META, ALPH or KWG section".into());
+        };
+        Ok(Self {
+            metadata: serde_json::from_slice(meta)?,
+            alphabet_text,
+            kwg,
+            klv,
+        })
+    }
+
+    pub fn alphabet(&self) -> error::Returns<alphabet::Alphabet> {
+        alphabet::Alphabet::new_static_from_text(self.alphabet_text)
+    }
+
+    // whether the kwg can be played with this game_config: the same alphabet,
+    // and a gaddawg for classic games or an alphagram dawg for jumbled games.
+    pub fn check_game_config(&self, game_config: &game_config::GameConfig) -> error::Returns<()> {
+        if self.alphabet()?.to_static_text() != game_config.alphabet().to_static_text() {
+            return_error!(format!(
+                "{} was built for a different alphabet",
+                self.metadata.lexicon_name
+            ));
+        }
+        let wanted = match game_config.game_rules() {
+            game_config::GameRules::Classic => Content::Gaddawg,
+            game_config::GameRules::Jumbled => Content::AlphaDawg,
+        };
+        if self.metadata.content != wanted {
+            return_error!(format!(
+                "{} holds {:?} content, but the game needs {:?}",
+                self.metadata.lexicon_name, self.metadata.content, wanted
+            ));
+        }
+        Ok(())
+    }
+}
+
+// the bundle in buf, checked against game_config, or None if buf is not a
+// bundle and should be read as a raw kwg.
+pub fn detect<'a>(
+    buf: &'a [u8],
+    game_config: &game_config::GameConfig,
+) -> error::Returns<Option<Bundle<'a>>> {
+    if !is_bundle(buf) {
+        return Ok(None);
+    }
+    let bundle = Bundle::from_bytes(buf)?;
+    bundle.check_game_config(game_config)?;
+    Ok(Some(bundle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::make_tiny_kwg_bytes;
+    use crate::{klv, kwg};
+
+    fn make_bundle_bytes(content: Content, klv: Option<&[u8]>) -> Vec<u8> {
+        let kwg = make_tiny_kwg_bytes(&["CAT", "TA"]);
+        Bundle {
+            metadata: Metadata {
+                lexicon_name: "TINY".into(),
+                build_date: "2026-10-17".into(),
+                layout: build::BuildLayout::Wolges,
+                content,
+                node_width: NodeWidth::Node22,
+            },
+            alphabet_text: &alphabet::make_english_alphabet().to_static_text(),
+            kwg: &kwg,
+            klv,
+        }
+        .to_bytes()
+        .unwrap()
+    }
+
+    // a bundle reads back as written, checks its game config, and any damage
+    // is caught by the checksum. raw files are left to the caller.
+    #[test]
+    fn bundle_round_trips_and_validates() {
+        let english = game_config::make_english_game_config();
+        let bytes = make_bundle_bytes(Content::Gaddawg, Some(klv::EMPTY_KLV_BYTES));
+        assert!(bytes.len().is_multiple_of(4));
+        let bundle = detect(&bytes, &english).unwrap().unwrap();
+        assert_eq!(bundle.metadata.lexicon_name, "TINY");
+        assert_eq!(bundle.metadata.layout, build::BuildLayout::Wolges);
+        assert_eq!(bundle.klv, Some(klv::EMPTY_KLV_BYTES));
+        let kwg = kwg::Kwg::<kwg::Node22>::from_bytes(bundle.kwg);
+        assert!(crate::lexicon::is_word(&kwg, &[3, 1, 20]));
+        assert!(!crate::lexicon::is_word(&kwg, &[1, 20]));
+
+        // the alphabet, or the rules, do not match.
+        assert!(detect(&bytes, &game_config::make_french_game_config()).is_err());
+        assert!(detect(&bytes, &game_config::make_jumbled_english_game_config()).is_err());
+        let bytes = make_bundle_bytes(Content::AlphaDawg, None);
+        assert!(detect(&bytes, &english).is_err());
+        assert!(
+            detect(&bytes, &game_config::make_jumbled_english_game_config())
+                .unwrap()
+                .unwrap()
+                .klv
+                .is_none()
+        );
+
+        let mut damaged = bytes.clone();
+        damaged[40] ^= 1;
+        assert!(detect(&damaged, &english).is_err());
+        assert!(detect(kwg::EMPTY_KWG_BYTES, &english).unwrap().is_none());
+    }
+}
@@ -393,6 +393,9 @@ impl std::fmt::Display for GameStatePrinter<'_> {
                 if adjustment != 0 {
                     write!(f, " ({adjustment})")?;
                 }
+                if self.game_config.is_time_forfeit(clock_ms) {
+                    write!(f, " (forfeit)")?;
+                }
                 if game_timers.turn as usize == i {
                     // may differ from game_state.turn if timer is paused
                     write!(f, " (timer running)")?;
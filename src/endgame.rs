@@ -3,7 +3,9 @@
 // note: this module is very slow and may need a lot of space
 // and it still has many bugs
 
-use super::{display, fash, game_config, klv, kwg, move_picker, movegen};
+use super::{
+    bites, build, display, error, fash, game_config, game_state, klv, kwg, move_picker, movegen,
+};
 
 // move one tile at a time from rack
 #[derive(Clone, Eq, Hash, PartialEq)]
@@ -151,13 +153,14 @@ pub struct FoundPlay<'a> {
 }
 
 // EndgameSolver is the main two-player endgame solver.
+// (Games with more players go to multi_endgame::MultiEndgameSolver.)
 // EndgameSolver can only be reused for the same game_config and kwg.
 // (Refer to note at WorkBuffer.)
 // This is not enforced.
 pub struct EndgameSolver<'a, N: kwg::Node, L: kwg::Node> {
     game_config: &'a game_config::GameConfig,
-    kwg: &'a kwg::Kwg<N>,
-    klv: Box<klv::Klv<L>>,
+    kwg: &'a kwg::Kwg<'a, N>,
+    klv: Box<klv::Klv<'static, L>>,
     board_tiles: Vec<u8>,
     racks: [Vec<u8>; 2],
     rack_scores: [i32; 2],
@@ -165,7 +168,7 @@ pub struct EndgameSolver<'a, N: kwg::Node, L: kwg::Node> {
 }
 
 impl<'a, N: kwg::Node, L: kwg::Node> EndgameSolver<'a, N, L> {
-    pub fn new(game_config: &'a game_config::GameConfig, kwg: &'a kwg::Kwg<N>) -> Self {
+    pub fn new(game_config: &'a game_config::GameConfig, kwg: &'a kwg::Kwg<'a, N>) -> Self {
         if game_config.num_players() != 2 {
             panic!("cannot solve non-2-player endgames");
         }
@@ -1286,6 +1289,29 @@ impl<'a, N: kwg::Node, L: kwg::Node> EndgameSolver<'a, N, L> {
         mover_rack: &[u8],
         unseen_tally: &[u8],
         score_diff: f32,
+    ) -> Result<PegResult, PegUnsupported> {
+        self.solve_peg_one_in_bag_known(
+            mover,
+            board,
+            mover_rack,
+            unseen_tally,
+            &game_state::KnownTiles::default(),
+            score_diff,
+        )
+    }
+
+    // solve_peg_one_in_bag, when some of the opponent's tiles are known. A tile
+    // known to be on the opponent's rack cannot be the one in the bag, so each
+    // hypothesis T is weighted by the copies of T that are not known, and a
+    // tile known to be absent from the opponent's rack must be the bag tile.
+    pub fn solve_peg_one_in_bag_known(
+        &mut self,
+        mover: u8,
+        board: &[u8],
+        mover_rack: &[u8],
+        unseen_tally: &[u8],
+        opp_known: &game_state::KnownTiles,
+        score_diff: f32,
     ) -> Result<PegResult, PegUnsupported> {
         // When an exchange is legal against the one-tile bag (Spanish), solving
         // requires the exchange-aware search, and that search only terminates when
@@ -1297,6 +1323,25 @@ impl<'a, N: kwg::Node, L: kwg::Node> EndgameSolver<'a, N, L> {
         if one_in_bag_exchange_legal(gc) && !one_in_bag_exchange_solvable(gc) {
             return Err(PegUnsupported::ExchangeWithoutForcedEnd);
         }
+        let bag_tiles: Vec<(u8, u32)> = unseen_tally
+            .iter()
+            .enumerate()
+            .filter_map(|(t, &c)| {
+                let num_known = opp_known
+                    .present
+                    .iter()
+                    .filter(|&&u| u as usize == t)
+                    .count();
+                let fits_absent = opp_known.absent.iter().all(|&u| {
+                    unseen_tally.get(u as usize).copied().unwrap_or(0) == (u as usize == t) as u8
+                });
+                (c as usize > num_known && fits_absent)
+                    .then_some((t as u8, (c as usize - num_known) as u32))
+            })
+            .collect();
+        if bag_tiles.is_empty() {
+            return Err(PegUnsupported::InconsistentKnownTiles);
+        }
         // In the real game the mover does NOT see the bag tile, so it must commit
         // ONE first move that fares best AVERAGED over every possible bag tile
         // (argmax_M E_T value(M,T)) -- the move that wins the most endgames. The
@@ -1304,7 +1349,14 @@ impl<'a, N: kwg::Node, L: kwg::Node> EndgameSolver<'a, N, L> {
         // so an exchange-legal config falls back to the clairvoyant E_T max_M
         // aggregate (an optimistic bound); every other config is solved committed.
         if one_in_bag_exchange_legal(gc) {
-            Ok(self.peg_clairvoyant_aggregate(mover, board, mover_rack, unseen_tally, score_diff))
+            Ok(self.peg_clairvoyant_aggregate(
+                mover,
+                board,
+                mover_rack,
+                unseen_tally,
+                &bag_tiles,
+                score_diff,
+            ))
         } else {
             let unseen: Vec<(u8, u32)> = unseen_tally
                 .iter()
@@ -1312,7 +1364,9 @@ impl<'a, N: kwg::Node, L: kwg::Node> EndgameSolver<'a, N, L> {
                 .filter(|&(_, &c)| c != 0)
                 .map(|(t, &c)| (t as u8, c as u32))
                 .collect();
-            Ok(self.peg_committed_no_exchange(mover, board, mover_rack, &unseen, score_diff))
+            Ok(self.peg_committed_no_exchange(
+                mover, board, mover_rack, &unseen, &bag_tiles, score_diff,
+            ))
         }
     }
 
@@ -1322,22 +1376,21 @@ impl<'a, N: kwg::Node, L: kwg::Node> EndgameSolver<'a, N, L> {
     // optimistic bound on the mover's win rate, not the in-game value. Retained
     // for the exchange-legal path (whose committed handling is not built yet) and
     // as the reference bound in tests. Reports no single committed move.
+    // bag_tiles lists each possible bag tile with its weight.
     fn peg_clairvoyant_aggregate(
         &mut self,
         mover: u8,
         board: &[u8],
         mover_rack: &[u8],
         unseen_tally: &[u8],
+        bag_tiles: &[(u8, u32)],
         score_diff: f32,
     ) -> PegResult {
         let mut hypotheses: Vec<(u8, u32, f32)> = Vec::new();
         // opp_rack is rebuilt in place for each hypothesis (one allocation).
         let mut opp_rack: Vec<u8> = Vec::new();
-        for (t, &count) in unseen_tally.iter().enumerate() {
-            if count == 0 {
-                continue;
-            }
-            let bag_tile = t as u8;
+        for &(bag_tile, weight) in bag_tiles {
+            let t = bag_tile as usize;
             // opp_rack = the unseen multiset with exactly one copy of bag_tile
             // removed (that copy is the one in the bag).
             opp_rack.clear();
@@ -1350,7 +1403,7 @@ impl<'a, N: kwg::Node, L: kwg::Node> EndgameSolver<'a, N, L> {
             racks[(mover ^ 1) as usize] = &opp_rack;
             self.init(board, racks);
             let v = self.solve_one_in_bag(mover, bag_tile) + score_diff;
-            hypotheses.push((bag_tile, count as u32, v));
+            hypotheses.push((bag_tile, weight, v));
         }
         let weighted: Vec<(u32, f32)> = hypotheses.iter().map(|&(_, w, v)| (w, v)).collect();
         let (win_pct, expected_margin) = peg_aggregate(&weighted);
@@ -1385,19 +1438,23 @@ impl<'a, N: kwg::Node, L: kwg::Node> EndgameSolver<'a, N, L> {
     // pass value is a lower bound and pass never spuriously wins the argmax -- and
     // pass is dominated whenever the mover has a scoring play. Fully committed pass
     // (and exchange, and 2+ tiles in the bag) are the retower follow-ups.
+    //
+    // unseen is the unseen multiset as (tile, count), and bag_tiles the possible
+    // bag tiles as (tile, weight).
     fn peg_committed_no_exchange(
         &self,
         mover: u8,
         board: &[u8],
         mover_rack: &[u8],
         unseen: &[(u8, u32)],
+        bag_tiles: &[(u8, u32)],
         score_diff: f32,
     ) -> PegResult {
         let gc = self.game_config;
         let kwg = self.kwg;
         let klv = klv::Klv::<L>::from_bytes_alloc(klv::EMPTY_KLV_BYTES);
         let opp = mover ^ 1;
-        let total = unseen.iter().map(|&(_, w)| w).sum::<u32>() as f32;
+        let total = bag_tiles.iter().map(|&(_, w)| w).sum::<u32>() as f32;
 
         // Candidate first moves are generated once from the pre-draw rack (bag-tile
         // independent). Snapshot the place plays out of the shared generator so the
@@ -1434,8 +1491,8 @@ impl<'a, N: kwg::Node, L: kwg::Node> EndgameSolver<'a, N, L> {
         for cand in &candidates {
             let mut win = 0.0f32;
             let mut marg = 0.0f32;
-            let mut hyps: Vec<(u8, u32, f32)> = Vec::with_capacity(unseen.len());
-            for &(t, w) in unseen {
+            let mut hyps: Vec<(u8, u32, f32)> = Vec::with_capacity(bag_tiles.len());
+            for &(t, w) in bag_tiles {
                 // a drawn blank lands on the rack as an undesignated blank (0).
                 let drawn = t & !((t as i8) >> 7) as u8;
                 // opp rack = the unseen multiset minus one copy of this bag tile.
@@ -1571,6 +1628,9 @@ pub enum PegUnsupported {
     // turns can never force the game to end, so the exchange search need not
     // terminate. The position is not exactly solvable by this enumeration.
     ExchangeWithoutForcedEnd,
+    // No tile can be the one in the bag without contradicting what is known
+    // of the opponent's rack.
+    InconsistentKnownTiles,
 }
 
 impl std::fmt::Display for PegUnsupported {
@@ -1580,6 +1640,9 @@ impl std::fmt::Display for PegUnsupported {
                 "exchange is legal with one tile in the bag but scoreless turns \
                  never force the game to end, so this position is not solvable",
             ),
+            PegUnsupported::InconsistentKnownTiles => {
+                f.write_str("the known tiles do not fit the unseen tiles")
+            }
         }
     }
 }
@@ -1654,6 +1717,49 @@ pub fn peg_aggregate(hypotheses: &[(u32, f32)]) -> (f32, f32) {
     }
 }
 
+// Word-prune a board for the solver: keep only the words still playable on it,
+// rebuilt in the shape the config's rules need -- a gaddawg for classic play, or
+// an alphagram dawg for jumbled play. The pruned set is always tiny, so Node22
+// suffices regardless of how big the source graph was. Reuses the caller's move
+// generator, which is left ready for another kwg.
+pub fn build_word_pruned_kwg<N: kwg::Node>(
+    move_generator: &mut movegen::KurniaMoveGenerator,
+    game_config: &game_config::GameConfig,
+    kwg: &kwg::Kwg<N>,
+    board_tiles: &[u8],
+) -> error::Returns<kwg::Kwg<'static, kwg::Node22>> {
+    let mut set_of_words = fash::MyHashSet::<bites::Bites>::default();
+    move_generator.gen_remaining_words(
+        &movegen::BoardSnapshot {
+            board_tiles,
+            game_config,
+            kwg,
+            klv: &klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES),
+        },
+        |word: &[u8]| {
+            set_of_words.insert(word.into());
+        },
+    );
+    move_generator.reset_for_another_kwg();
+    let mut vec_of_words = set_of_words.into_iter().collect::<Vec<_>>();
+    vec_of_words.sort_unstable();
+    let smaller_kwg_bytes = match game_config.game_rules() {
+        game_config::GameRules::Classic => build::build(
+            build::BuildContent::Gaddawg,
+            build::BuildLayout::Wolges,
+            &vec_of_words,
+        )?,
+        game_config::GameRules::Jumbled => build::build(
+            build::BuildContent::DawgOnly,
+            build::BuildLayout::Wolges,
+            &build::make_alphagrams(&vec_of_words),
+        )?,
+    };
+    Ok(kwg::Kwg::<kwg::Node22>::from_bytes_alloc(
+        &smaller_kwg_bytes,
+    ))
+}
+
 // Reference-check tests for the endgame solver. A small self-contained gaddawg
 // and a set of hand-built and fixed-seed positions drive an obviously-correct
 // plain-negamax reference; the fast solver is asserted to agree with it, to
@@ -1663,7 +1769,7 @@ pub fn peg_aggregate(hypotheses: &[(u32, f32)]) -> (f32, f32) {
 #[cfg(test)]
 mod tests {
     use super::{EndgameSolver, PegMove};
-    use crate::{alphabet, bites, build, game_config, klv, kwg, movegen};
+    use crate::{alphabet, bites, build, game_config, game_state, klv, kwg, movegen};
 
     // ---- tiny gaddawg over a short English word list --------------------------
     // Two- to four-letter words drawn from the letters A, B, T, H so the racks
@@ -2286,6 +2392,50 @@ mod tests {
         assert_eq!(result.expected_margin, 2500.0);
     }
 
+    // Same position, knowing the opponent holds the T (or has no H): only the
+    // H can be in the bag, so only the losing hypothesis is left. Knowing both
+    // H and T are on the opponent's one-tile rack contradicts the unseen tiles.
+    #[test]
+    fn peg_one_in_bag_known_tiles_narrow_the_hypotheses() {
+        let gc = game_config::make_english_game_config();
+        let kwg_bytes = tiny_kwg_bytes();
+        let kwg = kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&kwg_bytes);
+
+        let board = empty_board();
+        let mover_rack = [2u8]; // B
+        let mut unseen_tally = vec![0u8; gc.alphabet().len() as usize];
+        unseen_tally[8] = 1; // H
+        unseen_tally[20] = 1; // T
+
+        let mut egs = EndgameSolver::<kwg::Node22, kwg::Node22>::new(&gc, &kwg);
+        for opp_known in [
+            game_state::KnownTiles {
+                present: vec![20],
+                absent: vec![],
+            },
+            game_state::KnownTiles {
+                present: vec![],
+                absent: vec![8],
+            },
+        ] {
+            let result = egs
+                .solve_peg_one_in_bag_known(0, &board, &mover_rack, &unseen_tally, &opp_known, 0.0)
+                .expect("english one-in-bag is always solvable");
+            assert_eq!(result.hypotheses, vec![(8, 1, -2000.0)]);
+            assert_eq!(result.win_pct, 0.0);
+            assert_eq!(result.expected_margin, -2000.0);
+        }
+
+        let opp_known = game_state::KnownTiles {
+            present: vec![8, 20],
+            absent: vec![],
+        };
+        assert!(matches!(
+            egs.solve_peg_one_in_bag_known(0, &board, &mover_rack, &unseen_tally, &opp_known, 0.0),
+            Err(super::PegUnsupported::InconsistentKnownTiles)
+        ));
+    }
+
     // ---- PV-playout invariant -------------------------------------------------
     // Replay solve()'s principal variation and check the realized final value
     // equals solve()'s returned value. This is a self-consistency check, so the
@@ -2876,8 +3026,14 @@ mod tests {
                             committed(board, mrack, &unseen, &mut mg);
                         // clairvoyant bound (E_T max_M).
                         let mut egs2 = EndgameSolver::<kwg::Node22, kwg::Node22>::new(&gc, &kwg);
-                        let clair =
-                            egs2.peg_clairvoyant_aggregate(0, board, mrack, &unseen_tally, 0.0);
+                        let clair = egs2.peg_clairvoyant_aggregate(
+                            0,
+                            board,
+                            mrack,
+                            &unseen_tally,
+                            &unseen,
+                            0.0,
+                        );
 
                         assert!(got.committed, "english path must be committed");
                         assert!(got.best_move.is_some(), "committed result must name a move");
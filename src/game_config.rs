@@ -1,12 +1,40 @@
 // Copyright (C) 2020-2026 Andy Kurnia.
 
-use super::{alphabet, board_layout};
+use super::{alphabet, board_layout, error};
 
+#[derive(Clone)]
 pub enum GameRules {
     Classic,
     Jumbled,
 }
 
+// what happens when a play is challenged. Void refuses an invalid play before
+// anyone can challenge it. Otherwise a challenged phony is withdrawn and the
+// turn lost, and a valid play challenged stands with the challenger paying:
+// nothing under Single, their next turn under Double, or the given points (to
+// the challenged player) per challenge or per word formed. Under Double only
+// the next player may challenge, since their turn is the one lost; with more
+// than two players, the others cannot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChallengeRule {
+    Void,
+    Single,
+    Double,
+    Points(i16),
+    PointsPerWord(i16),
+}
+
+// how a game clock runs down. Increment adds its ms to a player's clock after
+// each of their turns, unless they are already over time. Delay lets each turn
+// use up to its ms before the clock starts running.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClockMode {
+    SuddenDeath,
+    Increment(i64),
+    Delay(i64),
+}
+
+#[derive(Clone)]
 pub struct StaticGameConfig {
     game_rules: GameRules,
     alphabet: alphabet::Alphabet,
@@ -15,13 +43,27 @@ pub struct StaticGameConfig {
     num_players: u8,
     num_passes_to_end: u8,
     challenges_are_passes: bool, // count challenge as pass turn or as zero turn
+    challenge_rule: ChallengeRule,
     num_zeros_to_end: u8,
     zeros_can_end_empty_board: bool,
     exchanges_are_zeros: bool,
     exchanges_allowed_per_player: i16,
     exchange_tile_limit: i16, // >= 1
+    // bonus by number of tiles played, 0..=rack_size.
+    num_played_bonuses: Box<[i16]>,
+    clock_mode: ClockMode,
+    overtime_penalty_per_minute: i16, // per started minute over time
+    overtime_forfeit_minutes: i16,    // i16::MAX means never forfeit
 }
 
+// the usual table: only playing the whole rack earns a bonus.
+fn make_bingo_only_bonuses(rack_size: u8, bingo_bonus: i16) -> Box<[i16]> {
+    let mut num_played_bonuses = vec![0; rack_size as usize + 1];
+    num_played_bonuses[rack_size as usize] = bingo_bonus;
+    num_played_bonuses.into_boxed_slice()
+}
+
+#[derive(Clone)]
 pub enum GameConfig {
     Static(StaticGameConfig),
 }
@@ -34,6 +76,13 @@ impl GameConfig {
         }
     }
 
+    // for callers that only need the alphabet, such as when building.
+    pub fn into_alphabet(self) -> alphabet::Alphabet {
+        match self {
+            GameConfig::Static(x) => x.alphabet,
+        }
+    }
+
     #[inline(always)]
     pub fn board_layout(&self) -> &board_layout::BoardLayout {
         match self {
@@ -69,6 +118,13 @@ impl GameConfig {
         }
     }
 
+    #[inline(always)]
+    pub fn challenge_rule(&self) -> ChallengeRule {
+        match self {
+            GameConfig::Static(x) => x.challenge_rule,
+        }
+    }
+
     #[inline(always)]
     pub fn num_zeros_to_end(&self) -> u8 {
         match self {
@@ -104,13 +160,40 @@ impl GameConfig {
         }
     }
 
+    #[inline(always)]
+    pub fn num_played_bonuses(&self) -> &[i16] {
+        match self {
+            GameConfig::Static(x) => &x.num_played_bonuses,
+        }
+    }
+
+    // playing more than rack_size tiles (only possible with an oversized
+    // rack) earns the whole-rack bonus, as it always has.
     #[inline(always)]
     pub fn num_played_bonus(&self, num_played: u8) -> i16 {
         match self {
-            GameConfig::Static(x) => {
-                // branchless
-                50 & -((num_played >= x.rack_size) as i16)
-            }
+            GameConfig::Static(x) => x.num_played_bonuses[num_played.min(x.rack_size) as usize],
+        }
+    }
+
+    #[inline(always)]
+    pub fn clock_mode(&self) -> ClockMode {
+        match self {
+            GameConfig::Static(x) => x.clock_mode,
+        }
+    }
+
+    #[inline(always)]
+    pub fn overtime_penalty_per_minute(&self) -> i16 {
+        match self {
+            GameConfig::Static(x) => x.overtime_penalty_per_minute,
+        }
+    }
+
+    #[inline(always)]
+    pub fn overtime_forfeit_minutes(&self) -> i16 {
+        match self {
+            GameConfig::Static(x) => x.overtime_forfeit_minutes,
         }
     }
 
@@ -118,13 +201,22 @@ impl GameConfig {
     #[inline(always)]
     pub fn time_adjustment(&self, clock_ms: i64) -> i16 {
         match self {
-            GameConfig::Static(..) => {
+            GameConfig::Static(x) => {
                 // branchless
-                (-(((!clock_ms / 60000) + 1) * 10) as i16) & -((clock_ms < 0) as i16)
+                (-(((!clock_ms / 60000) + 1) * x.overtime_penalty_per_minute as i64) as i16)
+                    & -((clock_ms < 0) as i16)
             }
         }
     }
 
+    // more than overtime_forfeit_minutes over time loses the game outright.
+    #[inline(always)]
+    pub fn is_time_forfeit(&self, clock_ms: i64) -> bool {
+        match self {
+            GameConfig::Static(x) => clock_ms < x.overtime_forfeit_minutes as i64 * -60000,
+        }
+    }
+
     #[inline(always)]
     pub fn game_rules(&self) -> &GameRules {
         match self {
@@ -142,11 +234,16 @@ pub fn make_catalan_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 0,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -159,11 +256,16 @@ pub fn make_jumbled_catalan_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 0,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -176,11 +278,16 @@ pub fn make_super_catalan_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 0,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -193,11 +300,16 @@ pub fn make_jumbled_super_catalan_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 0,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -210,11 +322,16 @@ pub fn make_dutch_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 0,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -227,11 +344,16 @@ pub fn make_jumbled_dutch_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 0,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -244,11 +366,16 @@ pub fn make_english_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 0,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -267,11 +394,16 @@ pub fn make_exchange_test_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 2,
         challenges_are_passes: true,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 3,
         zeros_can_end_empty_board: false,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 1,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -289,11 +421,16 @@ pub fn make_exchange_unsolvable_test_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 2,
         challenges_are_passes: true,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 3,
         zeros_can_end_empty_board: false,
         exchanges_are_zeros: false,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 1,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -306,11 +443,16 @@ pub fn make_jumbled_english_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 0,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -323,11 +465,16 @@ pub fn make_punctured_english_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 0,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -340,11 +487,16 @@ pub fn make_jumbled_punctured_english_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 0,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -357,11 +509,16 @@ pub fn make_hong_kong_english_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 0,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 9,
+        num_played_bonuses: make_bingo_only_bonuses(9, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -374,11 +531,16 @@ pub fn make_super_english_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 0,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -391,11 +553,16 @@ pub fn make_jumbled_super_english_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 0,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -408,11 +575,16 @@ pub fn make_french_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 0,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -425,11 +597,16 @@ pub fn make_jumbled_french_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 0,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -442,11 +619,16 @@ pub fn make_german_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 4,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 0,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -459,11 +641,16 @@ pub fn make_jumbled_german_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 4,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 0,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -476,11 +663,16 @@ pub fn make_norwegian_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 0,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -493,11 +685,16 @@ pub fn make_jumbled_norwegian_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 0,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -512,11 +709,16 @@ pub fn make_polish_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 4,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: false,
         exchanges_allowed_per_player: 3,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -529,11 +731,16 @@ pub fn make_jumbled_polish_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 4,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: false,
         exchanges_allowed_per_player: 3,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -546,11 +753,16 @@ pub fn make_slovene_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 0,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -563,11 +775,16 @@ pub fn make_jumbled_slovene_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 0,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -581,11 +798,16 @@ pub fn make_spanish_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 4,
         challenges_are_passes: true,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 12,
         zeros_can_end_empty_board: false,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 1,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -598,11 +820,16 @@ pub fn make_jumbled_spanish_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 4,
         challenges_are_passes: true,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 12,
         zeros_can_end_empty_board: false,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 1,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -615,11 +842,16 @@ pub fn make_swedish_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 0,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
     })
 }
 
@@ -632,10 +864,514 @@ pub fn make_jumbled_swedish_game_config() -> GameConfig {
         num_players: 2,
         num_passes_to_end: 0,
         challenges_are_passes: false,
+        challenge_rule: ChallengeRule::Double,
         num_zeros_to_end: 6,
         zeros_can_end_empty_board: true,
         exchanges_are_zeros: true,
         exchanges_allowed_per_player: i16::MAX,
         exchange_tile_limit: 7,
+        num_played_bonuses: make_bingo_only_bonuses(7, 50),
+        clock_mode: ClockMode::SuddenDeath,
+        overtime_penalty_per_minute: 10,
+        overtime_forfeit_minutes: i16::MAX,
+    })
+}
+
+// the alphabet, board layout, and tile set for a config name -- language-
+// named, not lexicon-named, since a config is reusable across any word list
+// built for it (a config has no notion of which words are valid). The
+// jumbled- prefix selects the anagram variant (any letters in any order form
+// a word), the super- prefix selects the 21x21 Super board, and they combine
+// as jumbled-super-. A jumbled config expects a .kad (alphagram dawg) word
+// graph rather than a .kwg gaddawg; the endgame word-prune rebuilds in the
+// matching shape automatically.
+pub fn make_game_config_by_name(name: &str) -> Option<GameConfig> {
+    Some(match name {
+        "english" => make_english_game_config(),
+        "jumbled-english" => make_jumbled_english_game_config(),
+        "super-english" => make_super_english_game_config(),
+        "jumbled-super-english" => make_jumbled_super_english_game_config(),
+        "catalan" => make_catalan_game_config(),
+        "jumbled-catalan" => make_jumbled_catalan_game_config(),
+        "super-catalan" => make_super_catalan_game_config(),
+        "jumbled-super-catalan" => make_jumbled_super_catalan_game_config(),
+        "dutch" => make_dutch_game_config(),
+        "jumbled-dutch" => make_jumbled_dutch_game_config(),
+        "french" => make_french_game_config(),
+        "jumbled-french" => make_jumbled_french_game_config(),
+        "german" => make_german_game_config(),
+        "jumbled-german" => make_jumbled_german_game_config(),
+        "norwegian" => make_norwegian_game_config(),
+        "jumbled-norwegian" => make_jumbled_norwegian_game_config(),
+        "polish" => make_polish_game_config(),
+        "jumbled-polish" => make_jumbled_polish_game_config(),
+        "slovene" => make_slovene_game_config(),
+        "jumbled-slovene" => make_jumbled_slovene_game_config(),
+        "spanish" => make_spanish_game_config(),
+        "jumbled-spanish" => make_jumbled_spanish_game_config(),
+        "swedish" => make_swedish_game_config(),
+        "jumbled-swedish" => make_jumbled_swedish_game_config(),
+        _ => return None,
+    })
+}
+
+// a club variant described by a rules file instead of a recompile. Every field
+// is optional and overrides the base config (default english). The alphabet is
+// a built-in alphabet name, or inline text in the new_static_from_text format,
+// or (only when loading from a file) a file of that text, relative to the rules
+// file. The board layout likewise is standard, punctured, or super, or a text
+// grid (see board_layout::make_board_layout_from_text). The play bonus is the
+// base's bingo bonus for playing the whole rack, unless num_played_bonuses
+// gives the bonus for each number of tiles played (0, 1, 2, ...; missing
+// entries are 0); bingo_bonus then overrides the whole-rack entry. An
+// example, for six-tile racks, three passes to end, and a 30-point bingo with
+// 10 points for playing five tiles:
+//
+//   { "base": "english", "rack_size": 6, "exchange_tile_limit": 6,
+//     "num_passes_to_end": 3, "num_played_bonuses": [0, 0, 0, 0, 0, 10],
+//     "bingo_bonus": 30 }
+//
+// The clock is the base's (sudden death for the built-in configs) unless
+// increment_ms or delay_ms is given (not both). Going over time costs
+// overtime_penalty_per_minute per started minute, and more than
+// overtime_forfeit_minutes over forfeits the game (null for never); both
+// default to the base's (10, and never).
+//
+// challenge_rule is void, single, double (the default), points, or
+// points_per_word; the last two pay challenge_points (default 5). Under double
+// only the next player may challenge (see ChallengeRule).
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    base: Option<String>,
+    jumbled: Option<bool>,
+    alphabet: Option<String>,
+    alphabet_text: Option<String>,
+    alphabet_file: Option<String>,
+    board_layout: Option<String>,
+    board_layout_text: Option<String>,
+    board_layout_file: Option<String>,
+    rack_size: Option<u8>,
+    num_players: Option<u8>,
+    num_passes_to_end: Option<u8>,
+    challenges_are_passes: Option<bool>,
+    challenge_rule: Option<String>,
+    challenge_points: Option<i16>,
+    num_zeros_to_end: Option<u8>,
+    zeros_can_end_empty_board: Option<bool>,
+    exchanges_are_zeros: Option<bool>,
+    // absent keeps the base's limit, null means unlimited.
+    #[serde(default, deserialize_with = "deserialize_some")]
+    exchanges_allowed_per_player: Option<Option<i16>>,
+    exchange_tile_limit: Option<i16>,
+    num_played_bonuses: Option<Vec<i16>>,
+    bingo_bonus: Option<i16>,
+    increment_ms: Option<i64>,
+    delay_ms: Option<i64>,
+    overtime_penalty_per_minute: Option<i16>,
+    // absent keeps the base's, null means never.
+    #[serde(default, deserialize_with = "deserialize_some")]
+    overtime_forfeit_minutes: Option<Option<i16>>,
+}
+
+// tells a field given as null (Some(None)) from an absent one (None).
+fn deserialize_some<'de, T: serde::Deserialize<'de>, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<T>, D::Error> {
+    T::deserialize(deserializer).map(Some)
+}
+
+fn make_alphabet_by_name(name: &str) -> Option<alphabet::Alphabet> {
+    Some(match name {
+        "catalan" => alphabet::make_catalan_alphabet(),
+        "super-catalan" => alphabet::make_super_catalan_alphabet(),
+        "decimal" => alphabet::make_decimal_alphabet(),
+        "dutch" => alphabet::make_dutch_alphabet(),
+        "english" => alphabet::make_english_alphabet(),
+        "french" => alphabet::make_french_alphabet(),
+        "hex" => alphabet::make_hex_alphabet(),
+        "hong-kong-english" => alphabet::make_hong_kong_english_alphabet(),
+        "super-english" => alphabet::make_super_english_alphabet(),
+        "german" => alphabet::make_german_alphabet(),
+        "norwegian" => alphabet::make_norwegian_alphabet(),
+        "polish" => alphabet::make_polish_alphabet(),
+        "slovene" => alphabet::make_slovene_alphabet(),
+        "spanish" => alphabet::make_spanish_alphabet(),
+        "swedish" => alphabet::make_swedish_alphabet(),
+        _ => return None,
+    })
+}
+
+fn make_board_layout_by_name(name: &str) -> Option<board_layout::BoardLayout> {
+    Some(match name {
+        "standard" => board_layout::make_standard_board_layout(),
+        "punctured" => board_layout::make_punctured_board_layout(),
+        "super" => board_layout::make_super_board_layout(),
+        _ => return None,
     })
 }
+
+// read_file resolves "alphabet_file" and "board_layout_file" to their text.
+fn make_game_config_from_rules_impl(
+    text: &str,
+    read_file: &dyn Fn(&str) -> error::Returns<String>,
+) -> error::Returns<GameConfig> {
+    let rules = serde_json::from_str::<RulesFile>(text)?;
+    let base_name = rules.base.as_deref().unwrap_or("english");
+    let Some(GameConfig::Static(mut x)) = make_game_config_by_name(base_name) else {
+        return_error!(format!("invalid base config {base_name:?}"));
+    };
+    if let Some(jumbled) = rules.jumbled {
+        x.game_rules = if jumbled {
+            GameRules::Jumbled
+        } else {
+            GameRules::Classic
+        };
+    }
+    match (&rules.alphabet, &rules.alphabet_text, &rules.alphabet_file) {
+        (None, None, None) => {}
+        (Some(name), None, None) => {
+            let Some(alphabet) = make_alphabet_by_name(name) else {
+                return_error!(format!("invalid alphabet {name:?}"));
+            };
+            x.alphabet = alphabet;
+        }
+        (None, Some(alphabet_text), None) => {
+            x.alphabet = alphabet::Alphabet::new_static_from_text(alphabet_text)?;
+        }
+        (None, None, Some(alphabet_file)) => {
+            x.alphabet = alphabet::Alphabet::new_static_from_text(&read_file(alphabet_file)?)?;
+        }
+        _ => {
+            return_error!(
+                "at most one of alphabet, alphabet_text, alphabet_file may be given".into()
+            );
+        }
+    }
+    match (
+        &rules.board_layout,
+        &rules.board_layout_text,
+        &rules.board_layout_file,
+    ) {
+        (None, None, None) => {}
+        (Some(name), None, None) => {
+            let Some(board_layout) = make_board_layout_by_name(name) else {
+                return_error!(format!("invalid board layout {name:?}"));
+            };
+            x.board_layout = board_layout;
+        }
+        (None, Some(board_layout_text), None) => {
+            x.board_layout = board_layout::make_board_layout_from_text(board_layout_text)?;
+        }
+        (None, None, Some(board_layout_file)) => {
+            x.board_layout =
+                board_layout::make_board_layout_from_text(&read_file(board_layout_file)?)?;
+        }
+        _ => {
+            return_error!(
+                "at most one of board_layout, board_layout_text, board_layout_file may be given"
+                    .into()
+            );
+        }
+    }
+    let base_bingo_bonus = x.num_played_bonuses[x.rack_size as usize];
+    x.rack_size = rules.rack_size.unwrap_or(x.rack_size);
+    x.num_players = rules.num_players.unwrap_or(x.num_players);
+    x.num_passes_to_end = rules.num_passes_to_end.unwrap_or(x.num_passes_to_end);
+    x.challenges_are_passes = rules
+        .challenges_are_passes
+        .unwrap_or(x.challenges_are_passes);
+    x.challenge_rule = match (rules.challenge_rule.as_deref(), rules.challenge_points) {
+        (None, None) => x.challenge_rule,
+        (Some("void"), None) => ChallengeRule::Void,
+        (Some("single"), None) => ChallengeRule::Single,
+        (Some("double"), None) => ChallengeRule::Double,
+        (Some("points"), challenge_points) => ChallengeRule::Points(challenge_points.unwrap_or(5)),
+        (Some("points_per_word"), challenge_points) => {
+            ChallengeRule::PointsPerWord(challenge_points.unwrap_or(5))
+        }
+        (challenge_rule, challenge_points) => {
+            return_error!(format!(
+                "invalid challenge_rule {challenge_rule:?} with challenge_points {challenge_points:?}"
+            ));
+        }
+    };
+    x.num_zeros_to_end = rules.num_zeros_to_end.unwrap_or(x.num_zeros_to_end);
+    x.zeros_can_end_empty_board = rules
+        .zeros_can_end_empty_board
+        .unwrap_or(x.zeros_can_end_empty_board);
+    x.exchanges_are_zeros = rules.exchanges_are_zeros.unwrap_or(x.exchanges_are_zeros);
+    x.exchanges_allowed_per_player = match rules.exchanges_allowed_per_player {
+        None => x.exchanges_allowed_per_player,
+        Some(exchanges_allowed_per_player) => exchanges_allowed_per_player.unwrap_or(i16::MAX),
+    };
+    x.exchange_tile_limit = rules.exchange_tile_limit.unwrap_or(x.exchange_tile_limit);
+    if x.rack_size == 0 || x.rack_size as u16 > x.alphabet.num_tiles() {
+        return_error!(format!("invalid rack_size {}", x.rack_size));
+    }
+    x.num_played_bonuses = match rules.num_played_bonuses {
+        Some(num_played_bonuses) => {
+            if num_played_bonuses.len() > x.rack_size as usize + 1 {
+                return_error!(format!(
+                    "num_played_bonuses has {} entries, at most {} allowed",
+                    num_played_bonuses.len(),
+                    x.rack_size as usize + 1
+                ));
+            }
+            let mut v = num_played_bonuses;
+            v.resize(x.rack_size as usize + 1, 0);
+            v.into_boxed_slice()
+        }
+        None => make_bingo_only_bonuses(x.rack_size, base_bingo_bonus),
+    };
+    if let Some(bingo_bonus) = rules.bingo_bonus {
+        x.num_played_bonuses[x.rack_size as usize] = bingo_bonus;
+    }
+    x.clock_mode = match (rules.increment_ms, rules.delay_ms) {
+        (None, None) => x.clock_mode,
+        (Some(increment_ms), None) if increment_ms >= 0 => ClockMode::Increment(increment_ms),
+        (None, Some(delay_ms)) if delay_ms >= 0 => ClockMode::Delay(delay_ms),
+        _ => {
+            return_error!(
+                "at most one of increment_ms, delay_ms may be given, and not negative".into()
+            );
+        }
+    };
+    x.overtime_penalty_per_minute = rules
+        .overtime_penalty_per_minute
+        .unwrap_or(x.overtime_penalty_per_minute);
+    x.overtime_forfeit_minutes = match rules.overtime_forfeit_minutes {
+        None => x.overtime_forfeit_minutes,
+        Some(overtime_forfeit_minutes) => overtime_forfeit_minutes.unwrap_or(i16::MAX),
+    };
+    if x.num_players == 0 {
+        return_error!("num_players must be at least 1".into());
+    }
+    if x.exchange_tile_limit < 1 {
+        return_error!(format!(
+            "invalid exchange_tile_limit {}",
+            x.exchange_tile_limit
+        ));
+    }
+    if let ChallengeRule::Points(n) | ChallengeRule::PointsPerWord(n) = x.challenge_rule
+        && n < 0
+    {
+        return_error!(format!("invalid challenge_points {n}"));
+    }
+    if x.overtime_penalty_per_minute < 0 {
+        return_error!(format!(
+            "invalid overtime_penalty_per_minute {}",
+            x.overtime_penalty_per_minute
+        ));
+    }
+    if x.overtime_forfeit_minutes < 0 {
+        return_error!(format!(
+            "invalid overtime_forfeit_minutes {}",
+            x.overtime_forfeit_minutes
+        ));
+    }
+    if x.exchanges_allowed_per_player < 0 {
+        return_error!(format!(
+            "invalid exchanges_allowed_per_player {}",
+            x.exchanges_allowed_per_player
+        ));
+    }
+    Ok(GameConfig::Static(x))
+}
+
+// the text of a rules file (see RulesFile). alphabet_file and
+// board_layout_file are not supported here, as there is no file to resolve
+// them against.
+pub fn make_game_config_from_rules(text: &str) -> error::Returns<GameConfig> {
+    make_game_config_from_rules_impl(text, &|file| {
+        Err(error::new(format!("{file:?} needs a rules file path")).into())
+    })
+}
+
+pub fn load_game_config_from_rules_file(path: &str) -> error::Returns<GameConfig> {
+    let text = std::fs::read_to_string(path)?;
+    let dir = std::path::Path::new(path)
+        .parent()
+        .unwrap_or(std::path::Path::new(""));
+    make_game_config_from_rules_impl(&text, &|file| Ok(std::fs::read_to_string(dir.join(file))?))
+}
+
+// what the binaries accept as a config: a built-in config name (see
+// make_game_config_by_name), or the path of a .json rules file.
+pub fn load_game_config(name_or_path: &str) -> error::Returns<GameConfig> {
+    if name_or_path.ends_with(".json") {
+        load_game_config_from_rules_file(name_or_path)
+    } else {
+        match make_game_config_by_name(name_or_path) {
+            Some(game_config) => Ok(game_config),
+            None => Err(error::new(format!("invalid config {name_or_path:?}")).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // unknown config is rejected with None so the CLI can print usage.
+    #[test]
+    fn game_config_for_name_rejects_unknown() {
+        assert!(make_game_config_by_name("english").is_some());
+        assert!(make_game_config_by_name("BOGUS").is_none());
+    }
+
+    // the jumbled- and super- prefixes resolve, and their game_rules match
+    // (jumbled -> Jumbled, so the endgame word-prune picks the alphagram dawg).
+    #[test]
+    fn game_config_for_name_handles_jumbled_and_super() {
+        assert!(matches!(
+            make_game_config_by_name("english").unwrap().game_rules(),
+            GameRules::Classic
+        ));
+        assert!(matches!(
+            make_game_config_by_name("jumbled-english")
+                .unwrap()
+                .game_rules(),
+            GameRules::Jumbled
+        ));
+        assert!(matches!(
+            make_game_config_by_name("jumbled-super-english")
+                .unwrap()
+                .game_rules(),
+            GameRules::Jumbled
+        ));
+        // super- alone stays classic (only the board grows to 21x21).
+        let super_english = make_game_config_by_name("super-english").unwrap();
+        assert!(matches!(super_english.game_rules(), GameRules::Classic));
+        assert_eq!(super_english.board_layout().dim().rows, 21);
+        // the -big node-width suffix is not part of the config name itself.
+        assert!(make_game_config_by_name("english-big").is_none());
+    }
+
+    // rules override the base config, defaults keep it, and bad values are
+    // rejected rather than producing a config the engine cannot play.
+    #[test]
+    fn rules_file_overrides_base() {
+        let game_config = make_game_config_from_rules(
+            r#"{ "rack_size": 6, "num_passes_to_end": 3, "exchange_tile_limit": 6,
+                 "exchanges_allowed_per_player": 2, "bingo_bonus": 30 }"#,
+        )
+        .unwrap();
+        assert_eq!(game_config.rack_size(), 6);
+        assert_eq!(game_config.num_passes_to_end(), 3);
+        assert_eq!(game_config.exchanges_allowed_per_player(), 2);
+        assert_eq!(game_config.num_played_bonuses(), [0, 0, 0, 0, 0, 0, 30]);
+        assert_eq!(game_config.num_zeros_to_end(), 6);
+        assert_eq!(game_config.alphabet().len(), 27);
+
+        let game_config = make_game_config_from_rules(
+            r#"{ "base": "super-english", "jumbled": true, "alphabet": "english" }"#,
+        )
+        .unwrap();
+        assert!(matches!(game_config.game_rules(), GameRules::Jumbled));
+        assert_eq!(game_config.board_layout().dim().rows, 21);
+        assert_eq!(game_config.alphabet().num_tiles(), 100);
+        assert_eq!(game_config.exchanges_allowed_per_player(), i16::MAX);
+
+        // a limited base keeps its limit unless lifted with null.
+        let game_config = make_game_config_from_rules(r#"{ "base": "polish" }"#).unwrap();
+        assert_eq!(game_config.exchanges_allowed_per_player(), 3);
+        let game_config = make_game_config_from_rules(
+            r#"{ "base": "polish", "exchanges_allowed_per_player": null }"#,
+        )
+        .unwrap();
+        assert_eq!(game_config.exchanges_allowed_per_player(), i16::MAX);
+
+        let game_config =
+            make_game_config_from_rules(r#"{ "alphabet_text": "? ? 2 0 0 0 0\nA a 9 1 1 0 0" }"#)
+                .unwrap();
+        assert_eq!(game_config.alphabet().len(), 2);
+
+        let game_config = make_game_config_from_rules(
+            r#"{ "board_layout_text": "T.T\n.*.\nT.T", "rack_size": 3 }"#,
+        )
+        .unwrap();
+        assert_eq!(game_config.board_layout().dim().rows, 3);
+        assert_eq!(game_config.board_layout().star_row(), 1);
+        assert_eq!(game_config.num_played_bonuses(), [0, 0, 0, 50]);
+
+        let game_config = make_game_config_from_rules(
+            r#"{ "rack_size": 8, "num_played_bonuses": [0, 0, 0, 0, 0, 0, 0, 20, 35] }"#,
+        )
+        .unwrap();
+        assert_eq!(game_config.num_played_bonus(7), 20);
+        assert_eq!(game_config.num_played_bonus(8), 35);
+        assert_eq!(game_config.challenge_rule(), ChallengeRule::Double);
+
+        let game_config = make_game_config_from_rules(
+            r#"{ "challenge_rule": "points_per_word", "challenge_points": 10 }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            game_config.challenge_rule(),
+            ChallengeRule::PointsPerWord(10)
+        );
+        let game_config = make_game_config_from_rules(r#"{ "challenge_rule": "points" }"#).unwrap();
+        assert_eq!(game_config.challenge_rule(), ChallengeRule::Points(5));
+
+        for bad in [
+            r#"{ "rack_size": 0 }"#,
+            r#"{ "exchange_tile_limit": 0 }"#,
+            r#"{ "base": "bogus" }"#,
+            r#"{ "alphabet": "bogus" }"#,
+            r#"{ "board_layout": "bogus" }"#,
+            r#"{ "alphabet": "english", "alphabet_text": "" }"#,
+            r#"{ "alphabet_file": "english.txt" }"#,
+            r#"{ "board_layout": "super", "board_layout_text": "*" }"#,
+            r#"{ "board_layout_text": "..." }"#,
+            r#"{ "rack_sise": 6 }"#,
+            r#"{ "num_played_bonuses": [0, 0, 0, 0, 0, 0, 0, 50, 50] }"#,
+            r#"{ "increment_ms": 5000, "delay_ms": 5000 }"#,
+            r#"{ "delay_ms": -1 }"#,
+            r#"{ "overtime_penalty_per_minute": -10 }"#,
+            r#"{ "challenge_rule": "triple" }"#,
+            r#"{ "challenge_rule": "double", "challenge_points": 5 }"#,
+            r#"{ "challenge_points": 10 }"#,
+            r#"{ "challenge_rule": "points", "challenge_points": -5 }"#,
+        ] {
+            assert!(make_game_config_from_rules(bad).is_err(), "{bad}");
+        }
+    }
+
+    // the default policy is the classic one; the rules file can change the
+    // penalty and add a forfeit threshold and an increment or delay clock.
+    #[test]
+    fn overtime_policy_from_rules() {
+        let game_config = make_game_config_by_name("english").unwrap();
+        assert_eq!(game_config.clock_mode(), ClockMode::SuddenDeath);
+        assert_eq!(game_config.time_adjustment(0), 0);
+        assert_eq!(game_config.time_adjustment(-1), -10);
+        assert_eq!(game_config.time_adjustment(-60000), -10);
+        assert_eq!(game_config.time_adjustment(-60001), -20);
+        assert!(!game_config.is_time_forfeit(-3_600_000));
+
+        let game_config = make_game_config_from_rules(
+            r#"{ "increment_ms": 5000, "overtime_penalty_per_minute": 5,
+                 "overtime_forfeit_minutes": 3 }"#,
+        )
+        .unwrap();
+        assert_eq!(game_config.clock_mode(), ClockMode::Increment(5000));
+        assert_eq!(game_config.time_adjustment(1), 0);
+        assert_eq!(game_config.time_adjustment(-1), -5);
+        assert_eq!(game_config.time_adjustment(-120001), -15);
+        assert!(!game_config.is_time_forfeit(-180000));
+        assert!(game_config.is_time_forfeit(-180001));
+
+        let game_config = make_game_config_from_rules(r#"{ "delay_ms": 3000 }"#).unwrap();
+        assert_eq!(game_config.clock_mode(), ClockMode::Delay(3000));
+        assert_eq!(game_config.overtime_penalty_per_minute(), 10);
+
+        // absent keeps the base's clock and forfeit, null lifts the forfeit.
+        let game_config = make_game_config_from_rules(r#"{ "base": "polish" }"#).unwrap();
+        assert_eq!(game_config.clock_mode(), ClockMode::SuddenDeath);
+        assert!(!game_config.is_time_forfeit(-3_600_000));
+        let game_config =
+            make_game_config_from_rules(r#"{ "overtime_forfeit_minutes": null }"#).unwrap();
+        assert!(!game_config.is_time_forfeit(-3_600_000));
+    }
+}
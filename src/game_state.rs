@@ -1,6 +1,6 @@
 // Copyright (C) 2020-2026 Andy Kurnia.
 
-use super::{bag, equity, error, game_config, movegen};
+use super::{bag, bites, equity, error, game_config, movegen};
 use rand::prelude::*;
 
 pub fn use_tiles<II: IntoIterator<Item = u8>>(
@@ -14,10 +14,49 @@ pub fn use_tiles<II: IntoIterator<Item = u8>>(
     Ok(())
 }
 
+// What the other players know of a rack besides its size, such as tiles
+// exchanged and drawn back, or revealed by a phony. The rack holds at least the
+// present tiles, and none of the absent ones. Playing known tiles forgets them,
+// drawing forgets the absent ones, and exchanging forgets both.
+#[derive(Default)]
+pub struct KnownTiles {
+    pub present: Vec<u8>,
+    pub absent: Vec<u8>,
+}
+
+impl Clone for KnownTiles {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {
+            present: self.present.clone(),
+            absent: self.absent.clone(),
+        }
+    }
+
+    #[inline(always)]
+    fn clone_from(&mut self, source: &Self) {
+        self.present.clone_from(&source.present);
+        self.absent.clone_from(&source.absent);
+    }
+}
+
+impl KnownTiles {
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.present.is_empty() && self.absent.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.present.clear();
+        self.absent.clear();
+    }
+}
+
 pub struct GamePlayer {
     pub score: i32,
     pub rack: Vec<u8>,
     pub num_exchanges: i16, // lifetime
+    pub known: KnownTiles,
 }
 
 impl Clone for GamePlayer {
@@ -27,6 +66,7 @@ impl Clone for GamePlayer {
             score: self.score,
             rack: self.rack.clone(),
             num_exchanges: self.num_exchanges,
+            known: self.known.clone(),
         }
     }
 
@@ -35,6 +75,101 @@ impl Clone for GamePlayer {
         self.score.clone_from(&source.score);
         self.rack.clone_from(&source.rack);
         self.num_exchanges.clone_from(&source.num_exchanges);
+        self.known.clone_from(&source.known);
+    }
+}
+
+// The racks, scores and bag around a change made outside of play.
+struct Holdings {
+    racks: Box<[bites::Bites]>,
+    scores: Box<[i32]>,
+    bag: bag::BagLayout,
+}
+
+impl Clone for Holdings {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {
+            racks: self.racks.clone(),
+            scores: self.scores.clone(),
+            bag: self.bag.clone(),
+        }
+    }
+
+    #[inline(always)]
+    fn clone_from(&mut self, source: &Self) {
+        self.racks.clone_from(&source.racks);
+        self.scores.clone_from(&source.scores);
+        self.bag.clone_from(&source.bag);
+    }
+}
+
+// One entry of the turn log, enough to undo and redo a play exactly.
+pub struct Turn {
+    pub player: u8,
+    pub play: movegen::Play,
+    pub rack_before: bites::Bites,
+    pub drawn: bites::Bites,
+    pub score_delta: i32,
+    pub prev_zero_turns: u16,
+    pub prev_pass_turns: u16,
+    // lost to a challenge: a withdrawn phony (play is the phony), or a turn
+    // forfeited by a failed double challenge (play is a pass).
+    pub lost_turn: bool,
+    pub challenge_bonus: i32, // part of score_delta
+    turn_after: u8,           // set by undo, restored by redo
+    // put_back is random, so exchanges keep the bag before and after.
+    bag_layouts: Option<Box<(bag::BagLayout, bag::BagLayout)>>,
+    known_before: Option<Box<KnownTiles>>, // only if there was any
+    // set for racks dealt or scores adjusted outside of play (see
+    // is_adjustment), holding everything before and after.
+    adjustment: Option<Box<(Holdings, Holdings)>>,
+}
+
+impl Turn {
+    // True for an entry logged by set_current_rack or adjust_score rather than
+    // by a play. Its play is then a pass that was never made.
+    #[inline(always)]
+    pub fn is_adjustment(&self) -> bool {
+        self.adjustment.is_some()
+    }
+}
+
+impl Clone for Turn {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {
+            player: self.player,
+            play: self.play.clone(),
+            rack_before: self.rack_before.clone(),
+            drawn: self.drawn.clone(),
+            score_delta: self.score_delta,
+            prev_zero_turns: self.prev_zero_turns,
+            prev_pass_turns: self.prev_pass_turns,
+            lost_turn: self.lost_turn,
+            challenge_bonus: self.challenge_bonus,
+            turn_after: self.turn_after,
+            bag_layouts: self.bag_layouts.clone(),
+            known_before: self.known_before.clone(),
+            adjustment: self.adjustment.clone(),
+        }
+    }
+
+    #[inline(always)]
+    fn clone_from(&mut self, source: &Self) {
+        self.player.clone_from(&source.player);
+        self.play.clone_from(&source.play);
+        self.rack_before.clone_from(&source.rack_before);
+        self.drawn.clone_from(&source.drawn);
+        self.score_delta.clone_from(&source.score_delta);
+        self.prev_zero_turns.clone_from(&source.prev_zero_turns);
+        self.prev_pass_turns.clone_from(&source.prev_pass_turns);
+        self.lost_turn.clone_from(&source.lost_turn);
+        self.challenge_bonus.clone_from(&source.challenge_bonus);
+        self.turn_after.clone_from(&source.turn_after);
+        self.bag_layouts.clone_from(&source.bag_layouts);
+        self.known_before.clone_from(&source.known_before);
+        self.adjustment.clone_from(&source.adjustment);
     }
 }
 
@@ -45,6 +180,10 @@ pub struct GameState {
     pub turn: u8,
     pub zero_turns: u16,
     pub pass_turns: u16,
+    // off by default, so rollouts do not pay for a log they never read.
+    keep_history: bool,
+    history: Vec<Turn>,
+    undone: Vec<Turn>,
 }
 
 impl Clone for GameState {
@@ -57,6 +196,9 @@ impl Clone for GameState {
             turn: self.turn,
             zero_turns: self.zero_turns,
             pass_turns: self.pass_turns,
+            keep_history: self.keep_history,
+            history: self.history.clone(),
+            undone: self.undone.clone(),
         }
     }
 
@@ -68,6 +210,9 @@ impl Clone for GameState {
         self.turn.clone_from(&source.turn);
         self.zero_turns.clone_from(&source.zero_turns);
         self.pass_turns.clone_from(&source.pass_turns);
+        self.keep_history.clone_from(&source.keep_history);
+        self.history.clone_from(&source.history);
+        self.undone.clone_from(&source.undone);
     }
 }
 
@@ -84,6 +229,7 @@ impl GameState {
                     score: 0,
                     rack: Vec::with_capacity(rack_size),
                     num_exchanges: 0,
+                    known: KnownTiles::default(),
                 })
                 .collect(),
             board_tiles: vec![0u8; (dim.rows as usize) * (dim.cols as usize)].into_boxed_slice(),
@@ -91,20 +237,48 @@ impl GameState {
             turn: 0,
             zero_turns: 0,
             pass_turns: 0,
+            keep_history: false,
+            history: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+
+    // Enables the turn log used by undo and redo. Disabling clears it.
+    pub fn set_keep_history(&mut self, keep_history: bool) {
+        self.keep_history = keep_history;
+        if !keep_history {
+            self.clear_history();
         }
     }
 
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+        self.undone.clear();
+    }
+
+    // The plays that undo would take back, oldest first.
+    pub fn history(&self) -> &[Turn] {
+        &self.history
+    }
+
+    // The plays that redo would replay, next one last.
+    pub fn undone(&self) -> &[Turn] {
+        &self.undone
+    }
+
     pub fn reset(&mut self) {
         for player in self.players.iter_mut() {
             player.score = 0;
             player.rack.clear();
             player.num_exchanges = 0;
+            player.known.clear();
         }
         self.board_tiles.iter_mut().for_each(|m| *m = 0);
         self.bag.reset();
         self.turn = 0;
         self.zero_turns = 0;
         self.pass_turns = 0;
+        self.clear_history();
     }
 
     pub fn reset_and_draw_tiles(
@@ -135,7 +309,9 @@ impl GameState {
 
     // an opponent holding a desired tile not found in bag will draw another.
     // if desired tile is missing, final rack will be shorter.
+    // the turn log keeps this as an adjustment, so undo takes it back.
     pub fn set_current_rack(&mut self, desired_rack: &[u8]) {
+        let before = self.holdings();
         self.bag
             .return_tiles(&self.players[self.turn as usize].rack);
         self.players[self.turn as usize].rack.clear();
@@ -162,6 +338,74 @@ impl GameState {
                 }
             }
         }
+        self.log_adjustment(before);
+    }
+
+    // Adds delta to a player's score outside of play, such as end-of-game rack
+    // points or a time penalty. The turn log keeps this as an adjustment.
+    pub fn adjust_score(&mut self, player: u8, delta: i32) {
+        let before = self.holdings();
+        self.players[player as usize].score += delta;
+        self.log_adjustment(before);
+    }
+
+    // what an adjustment may change, captured only while keeping history.
+    fn holdings(&self) -> Option<Holdings> {
+        self.keep_history.then(|| Holdings {
+            racks: self
+                .players
+                .iter()
+                .map(|player| player.rack[..].into())
+                .collect(),
+            scores: self.players.iter().map(|player| player.score).collect(),
+            bag: self.bag.layout(),
+        })
+    }
+
+    fn set_holdings(&mut self, holdings: &Holdings) {
+        for ((player, rack), &score) in self
+            .players
+            .iter_mut()
+            .zip(holdings.racks.iter())
+            .zip(holdings.scores.iter())
+        {
+            player.rack.clear();
+            player.rack.extend_from_slice(rack);
+            player.score = score;
+        }
+        self.bag.set_layout(&holdings.bag);
+    }
+
+    // adjustments in a row share one entry, so undo takes them back together.
+    fn log_adjustment(&mut self, before: Option<Holdings>) {
+        let Some(before) = before else {
+            return;
+        };
+        let after = self.holdings().unwrap();
+        self.undone.clear();
+        if let Some(last_turn) = self.history.last_mut()
+            && let Some(adjustment) = &mut last_turn.adjustment
+        {
+            adjustment.1 = after;
+            return;
+        }
+        self.history.push(Turn {
+            player: self.turn,
+            play: movegen::Play::Exchange {
+                tiles: [][..].into(),
+            },
+            rack_before: self.current_player().rack[..].into(),
+            drawn: [][..].into(),
+            score_delta: 0,
+            prev_zero_turns: self.zero_turns,
+            prev_pass_turns: self.pass_turns,
+            lost_turn: false,
+            challenge_bonus: 0,
+            turn_after: self.turn,
+            bag_layouts: None,
+            known_before: None,
+            adjustment: Some(Box::new((before, after))),
+        });
     }
 
     pub fn current_player(&self) -> &GamePlayer {
@@ -169,12 +413,323 @@ impl GameState {
     }
 
     pub fn play(
+        &mut self,
+        game_config: &game_config::GameConfig,
+        rng: &mut dyn Rng,
+        play: &movegen::Play,
+    ) -> error::Returns<()> {
+        self.play_turn(game_config, rng, play, false, 0)
+    }
+
+    // Plays a Place that may be phony, challenged by challenger or not. valid
+    // is whether every word it forms is in the lexicon (see
+    // PlayScorer::words_are_valid). Exchanges and passes cannot be challenged.
+    // As with play, call next_turn afterwards, except when Refused. Under
+    // Double the challenger must be the next player, as in multiplayer games
+    // no other seat's turn can be taken here; any other challenger is an
+    // error. After a failed double challenge, the challenger's lost turn has
+    // been taken, so next_turn goes on to the player after them.
+    pub fn play_challenged(
+        &mut self,
+        game_config: &game_config::GameConfig,
+        rng: &mut dyn Rng,
+        play: &movegen::Play,
+        valid: bool,
+        challenger: Option<u8>,
+    ) -> error::Returns<ChallengeOutcome> {
+        let movegen::Play::Place {
+            down,
+            lane,
+            idx,
+            word,
+            ..
+        } = play
+        else {
+            if challenger.is_some() {
+                return_error!("only a place can be challenged".into());
+            }
+            self.play(game_config, rng, play)?;
+            return Ok(ChallengeOutcome::Unchallenged);
+        };
+        let rule = game_config.challenge_rule();
+        if !valid && rule == game_config::ChallengeRule::Void {
+            return Ok(ChallengeOutcome::Refused);
+        }
+        let Some(challenger) = challenger else {
+            self.play(game_config, rng, play)?;
+            return Ok(ChallengeOutcome::Unchallenged);
+        };
+        if challenger as usize >= self.players.len() || challenger == self.turn {
+            return_error!(format!("player {challenger} cannot challenge"));
+        }
+        if !valid {
+            // the phony goes back on the rack, so it must have come from there.
+            use_tiles(
+                &mut self.current_player().rack.clone(),
+                word.iter().filter_map(|&tile| {
+                    if tile != 0 {
+                        Some(tile & !((tile as i8) >> 7) as u8)
+                    } else {
+                        None
+                    }
+                }),
+            )?;
+            self.play_turn(game_config, rng, play, true, 0)?;
+            return Ok(ChallengeOutcome::Withdrawn);
+        }
+        let bonus = match rule {
+            game_config::ChallengeRule::Points(n) => n as i32 * equity::SCALE,
+            game_config::ChallengeRule::PointsPerWord(n) => {
+                n as i32
+                    * equity::SCALE
+                    * self.num_words_formed(game_config, *down, *lane, *idx, word)
+            }
+            _ => 0,
+        };
+        let mover = self.turn;
+        let mut lost_turn = rule == game_config::ChallengeRule::Double;
+        if lost_turn && challenger as usize != (mover as usize + 1) % self.players.len() {
+            return_error!(format!(
+                "player {challenger} cannot lose the next turn, it is not theirs"
+            ));
+        }
+        self.play_turn(game_config, rng, play, false, bonus)?;
+        // going out ends the game, leaving no turn to lose.
+        lost_turn &= !self.players[mover as usize].rack.is_empty();
+        if lost_turn {
+            self.next_turn();
+            let pass = movegen::Play::Exchange {
+                tiles: [][..].into(),
+            };
+            self.play_turn(game_config, rng, &pass, true, 0)?;
+        }
+        Ok(ChallengeOutcome::Failed { bonus, lost_turn })
+    }
+
+    // counts the main word (if not a single tile) and each cross word, on the
+    // board before the play.
+    fn num_words_formed(
+        &self,
+        game_config: &game_config::GameConfig,
+        down: bool,
+        lane: i8,
+        idx: i8,
+        word: &[u8],
+    ) -> i32 {
+        let dim = game_config.board_layout().dim();
+        let (num_lanes, lane_len) = if down {
+            (dim.cols, dim.rows)
+        } else {
+            (dim.rows, dim.cols)
+        };
+        let mut num_words = (word.len() >= 2) as i32;
+        for (i, &tile) in (idx..).zip(word.iter()) {
+            if tile != 0 && i >= 0 && i < lane_len {
+                let cross = dim.lane(!down, i);
+                num_words += ((lane > 0 && self.board_tiles[cross.at(lane - 1)] != 0)
+                    || (lane + 1 < num_lanes && self.board_tiles[cross.at(lane + 1)] != 0))
+                    as i32;
+            }
+        }
+        num_words
+    }
+
+    fn play_turn(
         &mut self,
         game_config: &game_config::GameConfig,
         mut rng: &mut dyn Rng,
         play: &movegen::Play,
+        lost_turn: bool,
+        challenge_bonus: i32,
     ) -> error::Returns<()> {
+        if !self.keep_history {
+            self.apply(
+                game_config,
+                &mut |bag, tiles| bag.put_back(&mut rng, tiles),
+                play,
+                lost_turn,
+                challenge_bonus,
+            )?;
+            return Ok(());
+        }
+        let player = self.turn;
+        let rack_before = self.players[player as usize].rack[..].into();
+        let score_before = self.players[player as usize].score;
+        let (prev_zero_turns, prev_pass_turns) = (self.zero_turns, self.pass_turns);
+        let known_before = &self.players[player as usize].known;
+        let known_before = (!known_before.is_empty()).then(|| Box::new(known_before.clone()));
+        let bag_before = match play {
+            movegen::Play::Exchange { tiles } if !tiles.is_empty() => Some(self.bag.layout()),
+            _ => None,
+        };
+        let num_kept = self.apply(
+            game_config,
+            &mut |bag, tiles| bag.put_back(&mut rng, tiles),
+            play,
+            lost_turn,
+            challenge_bonus,
+        )?;
+        let current_player = &self.players[player as usize];
+        self.history.push(Turn {
+            player,
+            play: play.clone(),
+            rack_before,
+            drawn: current_player.rack[num_kept..].into(),
+            score_delta: current_player.score - score_before,
+            prev_zero_turns,
+            prev_pass_turns,
+            lost_turn,
+            challenge_bonus,
+            turn_after: player,
+            bag_layouts: bag_before.map(|bag_before| Box::new((bag_before, self.bag.layout()))),
+            known_before,
+            adjustment: None,
+        });
+        self.undone.clear();
+        Ok(())
+    }
+
+    // Reverts the most recent play (or adjustment) exactly, including the bag
+    // order.
+    // Returns false if there is nothing to undo.
+    pub fn undo(&mut self, game_config: &game_config::GameConfig) -> bool {
+        let Some(mut last_turn) = self.history.pop() else {
+            return false;
+        };
+        last_turn.turn_after = self.turn;
+        if let Some(adjustment) = &last_turn.adjustment {
+            self.set_holdings(&adjustment.0);
+            self.turn = last_turn.player;
+            self.undone.push(last_turn);
+            return true;
+        }
+        let current_player = &mut self.players[last_turn.player as usize];
+        match &last_turn.play {
+            _ if last_turn.lost_turn => {}
+            movegen::Play::Exchange { tiles } => {
+                if let Some(bag_layouts) = &last_turn.bag_layouts {
+                    self.bag.set_layout(&bag_layouts.0);
+                }
+                if !tiles.is_empty() {
+                    current_player.num_exchanges -= 1;
+                }
+            }
+            movegen::Play::Place {
+                down,
+                lane,
+                idx,
+                word,
+                ..
+            } => {
+                let strider = game_config.board_layout().dim().lane(*down, *lane);
+                for (i, &tile) in (*idx..).zip(word.iter()) {
+                    if tile != 0 {
+                        self.board_tiles[strider.at(i)] = 0;
+                    }
+                }
+                self.bag
+                    .unreplenish(&last_turn.drawn, last_turn.player as usize);
+            }
+        }
+        current_player.score -= last_turn.score_delta;
+        current_player.rack.clear();
+        current_player
+            .rack
+            .extend_from_slice(&last_turn.rack_before);
+        match &last_turn.known_before {
+            Some(known_before) => current_player.known.clone_from(known_before),
+            None => current_player.known.clear(),
+        }
+        self.zero_turns = last_turn.prev_zero_turns;
+        self.pass_turns = last_turn.prev_pass_turns;
+        self.turn = last_turn.player;
+        self.undone.push(last_turn);
+        true
+    }
+
+    // Replays the most recently undone play, to the same state it had led to.
+    // Returns false if there is nothing to redo.
+    pub fn redo(&mut self, game_config: &game_config::GameConfig) -> bool {
+        let Some(next_turn) = self.undone.pop() else {
+            return false;
+        };
+        if let Some(adjustment) = &next_turn.adjustment {
+            self.set_holdings(&adjustment.1);
+            self.turn = next_turn.turn_after;
+            self.history.push(next_turn);
+            return true;
+        }
+        self.turn = next_turn.player;
+        let bag_after = next_turn
+            .bag_layouts
+            .as_ref()
+            .map(|bag_layouts| &bag_layouts.1);
+        // the logged play was legal from this exact state.
+        self.apply(
+            game_config,
+            &mut |bag, _| {
+                if let Some(bag_after) = bag_after {
+                    bag.set_layout(bag_after);
+                }
+            },
+            &next_turn.play,
+            next_turn.lost_turn,
+            next_turn.challenge_bonus,
+        )
+        .unwrap();
+        self.turn = next_turn.turn_after;
+        self.history.push(next_turn);
+        true
+    }
+
+    // a lost turn leaves the rack and board as they were, but the other players
+    // have now seen the tiles of a withdrawn phony.
+    fn apply(
+        &mut self,
+        game_config: &game_config::GameConfig,
+        put_back: &mut dyn FnMut(&mut bag::Bag, &[u8]),
+        play: &movegen::Play,
+        lost_turn: bool,
+        challenge_bonus: i32,
+    ) -> error::Returns<usize> {
+        if !lost_turn {
+            let num_kept = self.play_impl(game_config, put_back, play)?;
+            self.players[self.turn as usize].score += challenge_bonus;
+            return Ok(num_kept);
+        }
+        let current_player = &mut self.players[self.turn as usize];
+        if let movegen::Play::Place { word, .. } = play {
+            let mut revealed = word
+                .iter()
+                .filter(|&&tile| tile != 0)
+                .map(|&tile| tile & !((tile as i8) >> 7) as u8)
+                .collect::<Vec<_>>();
+            for &tile in current_player.known.present.iter() {
+                if let Some(pos) = revealed.iter().position(|&t| t == tile) {
+                    revealed.swap_remove(pos);
+                }
+            }
+            current_player.known.present.extend_from_slice(&revealed);
+        }
+        self.zero_turns += 1;
+        if game_config.challenges_are_passes() {
+            self.pass_turns += 1;
+        } else {
+            self.pass_turns = 0;
+        }
+        Ok(current_player.rack.len())
+    }
+
+    // put_back is called after the exchanging player has replenished.
+    // Returns how many tiles were on the rack before replenishing.
+    fn play_impl(
+        &mut self,
+        game_config: &game_config::GameConfig,
+        put_back: &mut dyn FnMut(&mut bag::Bag, &[u8]),
+        play: &movegen::Play,
+    ) -> error::Returns<usize> {
         let current_player = &mut self.players[self.turn as usize];
+        let mut num_kept = current_player.rack.len();
         match play {
             movegen::Play::Exchange { tiles } => {
                 if tiles.is_empty() {
@@ -182,12 +737,14 @@ impl GameState {
                     self.zero_turns += 1;
                 } else {
                     use_tiles(&mut current_player.rack, tiles.iter().copied())?;
+                    current_player.known.clear();
+                    num_kept = current_player.rack.len();
                     self.bag.replenish(
                         &mut current_player.rack,
                         game_config.rack_size() as usize,
                         self.turn as usize,
                     );
-                    self.bag.put_back(&mut rng, tiles);
+                    put_back(&mut self.bag, tiles);
                     self.pass_turns = 0;
                     current_player.num_exchanges += 1;
                     if game_config.exchanges_are_zeros() {
@@ -224,16 +781,33 @@ impl GameState {
                         }
                     }),
                 )?;
+                num_kept = current_player.rack.len();
                 self.bag.replenish(
                     &mut current_player.rack,
                     game_config.rack_size() as usize,
                     self.turn as usize,
                 );
+                if !current_player.known.is_empty() {
+                    // the tiles played were seen, so they are no longer known.
+                    for &tile in word.iter() {
+                        if tile != 0 {
+                            let tile = tile & !((tile as i8) >> 7) as u8;
+                            if let Some(pos) =
+                                current_player.known.present.iter().position(|&t| t == tile)
+                            {
+                                current_player.known.present.swap_remove(pos);
+                            }
+                        }
+                    }
+                    if current_player.rack.len() > num_kept {
+                        current_player.known.absent.clear();
+                    }
+                }
                 self.zero_turns = 0;
                 self.pass_turns = 0;
             }
         }
-        Ok(())
+        Ok(num_kept)
     }
 
     pub fn next_turn(&mut self) {
@@ -287,9 +861,341 @@ impl GameState {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChallengeOutcome {
+    // the play stands, valid or not.
+    Unchallenged,
+    // the void rule turned away an invalid play; the player is still to move.
+    Refused,
+    // the phony was taken back and the turn lost.
+    Withdrawn,
+    // the play stands, and the player earned bonus (if any) for it. lost_turn
+    // is whether the challenger's next turn has been taken from them.
+    Failed { bonus: i32, lost_turn: bool },
+}
+
 #[derive(Clone, Copy)]
 pub enum CheckGameEnded {
     NotEnded,
     PlayedOut,
     ZeroScores,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{TWO_LETTER_WORDS, make_tiny_kwg};
+    use crate::{klv, kwg};
+
+    fn assert_same_state(a: &GameState, b: &GameState) {
+        assert_eq!(a.board_tiles, b.board_tiles);
+        assert!(a.bag.layout() == b.bag.layout());
+        assert_eq!(a.turn, b.turn);
+        assert_eq!(a.zero_turns, b.zero_turns);
+        assert_eq!(a.pass_turns, b.pass_turns);
+        for (p, q) in a.players.iter().zip(b.players.iter()) {
+            assert_eq!(p.score, q.score);
+            assert_eq!(p.rack, q.rack);
+            assert_eq!(p.num_exchanges, q.num_exchanges);
+            assert_eq!(p.known.present, q.known.present);
+            assert_eq!(p.known.absent, q.known.absent);
+        }
+    }
+
+    // Plays places, exchanges and passes, then steps all the way back and
+    // forward again, checking every position is restored exactly.
+    #[test]
+    fn undo_and_redo_restore_exact_states() {
+        let game_config = game_config::make_english_game_config();
+        let kwg = make_tiny_kwg(TWO_LETTER_WORDS);
+        let klv = klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES);
+        let mut rng = rand::rngs::ChaCha20Rng::seed_from_u64(5);
+        let mut move_generator = movegen::KurniaMoveGenerator::new(&game_config);
+        let mut game_state = GameState::new(&game_config);
+        game_state.reset_and_draw_tiles_double_ended(&game_config, &mut rng);
+        game_state.set_keep_history(true);
+        let mut final_scores = vec![0; game_state.players.len()];
+        let mut snapshots = vec![game_state.clone()];
+        for num_turns in 0..40 {
+            let play = if num_turns % 5 == 3 && game_state.bag.len() >= 7 {
+                movegen::Play::Exchange {
+                    tiles: game_state.current_player().rack[..num_turns % 3 + 1].into(),
+                }
+            } else if num_turns % 7 == 6 {
+                movegen::Play::Exchange {
+                    tiles: [][..].into(),
+                }
+            } else {
+                move_generator.gen_moves_unfiltered(&movegen::GenMovesParams {
+                    board_snapshot: &movegen::BoardSnapshot {
+                        board_tiles: &game_state.board_tiles,
+                        game_config: &game_config,
+                        kwg: &kwg,
+                        klv: &klv,
+                    },
+                    rack: &game_state.current_player().rack,
+                    max_gen: 1,
+                    num_exchanges_by_this_player: game_state.current_player().num_exchanges,
+                    always_include_pass: false,
+                    dynamic_leaves: None,
+                });
+                move_generator.plays[0].play.clone()
+            };
+            game_state.play(&game_config, &mut rng, &play).unwrap();
+            let ended = !matches!(
+                game_state.check_game_ended(&game_config, &mut final_scores),
+                CheckGameEnded::NotEnded
+            );
+            if !ended {
+                game_state.next_turn();
+            }
+            snapshots.push(game_state.clone());
+            if ended {
+                break;
+            }
+        }
+        assert_eq!(game_state.history().len(), snapshots.len() - 1);
+        assert!(
+            game_state
+                .history()
+                .iter()
+                .any(|turn| turn.bag_layouts.is_some())
+        );
+
+        for snapshot in snapshots.iter().rev().skip(1) {
+            assert!(game_state.undo(&game_config));
+            assert_same_state(&game_state, snapshot);
+        }
+        assert!(!game_state.undo(&game_config));
+        for snapshot in snapshots.iter().skip(1) {
+            assert!(game_state.redo(&game_config));
+            assert_same_state(&game_state, snapshot);
+        }
+        assert!(!game_state.redo(&game_config));
+
+        // a new play after undo discards what could have been redone.
+        game_state.undo(&game_config);
+        game_state.undo(&game_config);
+        let pass = movegen::Play::Exchange {
+            tiles: [][..].into(),
+        };
+        game_state.play(&game_config, &mut rng, &pass).unwrap();
+        assert!(game_state.undone().is_empty());
+        assert!(!game_state.redo(&game_config));
+    }
+
+    // racks dealt and scores adjusted outside of play are undone like plays,
+    // with adjustments in a row taken back together.
+    #[test]
+    fn adjustments_are_logged() {
+        let game_config = game_config::make_english_game_config();
+        let mut rng = rand::rngs::ChaCha20Rng::seed_from_u64(6);
+        let mut game_state = GameState::new(&game_config);
+        game_state.reset_and_draw_tiles(&game_config, &mut rng);
+        game_state.set_keep_history(true);
+        let mut snapshots = vec![game_state.clone()];
+        game_state.set_current_rack(&[1, 1, 5, 26]);
+        game_state.turn = 1;
+        game_state.set_current_rack(&[26]);
+        snapshots.push(game_state.clone());
+        let pass = movegen::Play::Exchange {
+            tiles: [][..].into(),
+        };
+        game_state.play(&game_config, &mut rng, &pass).unwrap();
+        game_state.next_turn();
+        snapshots.push(game_state.clone());
+        game_state.adjust_score(1, -10 * equity::SCALE);
+        snapshots.push(game_state.clone());
+        assert_eq!(
+            game_state
+                .history()
+                .iter()
+                .map(|turn| turn.is_adjustment())
+                .collect::<Vec<_>>(),
+            [true, false, true]
+        );
+
+        for snapshot in snapshots.iter().rev().skip(1) {
+            assert!(game_state.undo(&game_config));
+            assert_same_state(&game_state, snapshot);
+        }
+        assert!(!game_state.undo(&game_config));
+        for snapshot in snapshots.iter().skip(1) {
+            assert!(game_state.redo(&game_config));
+            assert_same_state(&game_state, snapshot);
+        }
+        assert!(!game_state.redo(&game_config));
+    }
+
+    // known tiles are forgotten as they are played, absent ones once the
+    // player draws, and both on an exchange. undo brings them back.
+    #[test]
+    fn known_tiles_follow_plays() {
+        let game_config = game_config::make_english_game_config();
+        let mut rng = rand::rngs::ChaCha20Rng::seed_from_u64(5);
+        let mut game_state = GameState::new(&game_config);
+        game_state.reset_and_draw_tiles(&game_config, &mut rng);
+        game_state.set_keep_history(true);
+        let player = &mut game_state.players[0];
+        player.rack.clear();
+        player.rack.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7]);
+        player.known.present.extend_from_slice(&[1, 5]);
+        player.known.absent.push(26);
+
+        let place = movegen::Play::Place {
+            down: false,
+            lane: 7,
+            idx: 7,
+            word: [1, 2][..].into(),
+            score: 0,
+        };
+        game_state.play(&game_config, &mut rng, &place).unwrap();
+        assert_eq!(game_state.players[0].known.present, [5]);
+        assert!(game_state.players[0].known.absent.is_empty());
+        assert!(game_state.undo(&game_config));
+        assert_eq!(game_state.players[0].known.present, [1, 5]);
+        assert_eq!(game_state.players[0].known.absent, [26]);
+        assert!(game_state.redo(&game_config));
+        assert_eq!(game_state.players[0].known.present, [5]);
+
+        let exchange = movegen::Play::Exchange {
+            tiles: [3][..].into(),
+        };
+        game_state.play(&game_config, &mut rng, &exchange).unwrap();
+        assert!(game_state.players[0].known.is_empty());
+        assert!(game_state.undo(&game_config));
+        assert_eq!(game_state.players[0].known.present, [5]);
+    }
+
+    // a phony is withdrawn (its tiles now known) or refused under void, and a
+    // valid play challenged costs the challenger a turn or gives points. undo
+    // and redo follow all of it.
+    #[test]
+    fn challenges_follow_the_rule() {
+        let place = |down, lane, idx, word: &[u8]| movegen::Play::Place {
+            down,
+            lane,
+            idx,
+            word: word.into(),
+            score: 0,
+        };
+        let new_game = |game_config: &game_config::GameConfig| {
+            let mut rng = rand::rngs::ChaCha20Rng::seed_from_u64(5);
+            let mut game_state = GameState::new(game_config);
+            game_state.reset_and_draw_tiles(game_config, &mut rng);
+            game_state.set_keep_history(true);
+            game_state.players[0].rack.clear();
+            game_state.players[0]
+                .rack
+                .extend_from_slice(&[1, 2, 3, 4, 5, 6, 7]);
+            (game_state, rng)
+        };
+
+        let game_config = game_config::make_english_game_config();
+        let (mut game_state, mut rng) = new_game(&game_config);
+        let before = game_state.clone();
+        let phony = place(false, 7, 7, &[3, 2]);
+        assert_eq!(
+            game_state
+                .play_challenged(&game_config, &mut rng, &phony, false, Some(1))
+                .unwrap(),
+            ChallengeOutcome::Withdrawn
+        );
+        assert!(game_state.board_tiles.iter().all(|&t| t == 0));
+        assert_eq!(game_state.players[0].rack, before.players[0].rack);
+        assert_eq!(game_state.players[0].known.present, [3, 2]);
+        assert_eq!((game_state.zero_turns, game_state.pass_turns), (1, 0));
+        assert!(game_state.history()[0].lost_turn);
+        let withdrawn = game_state.clone();
+        assert!(game_state.undo(&game_config));
+        assert_same_state(&game_state, &before);
+        assert!(game_state.redo(&game_config));
+        assert_same_state(&game_state, &withdrawn);
+        assert!(
+            game_state
+                .play_challenged(&game_config, &mut rng, &phony, false, Some(0))
+                .is_err()
+        );
+
+        // double: the play stands, and player 1 has lost the next turn.
+        let (mut game_state, mut rng) = new_game(&game_config);
+        let play = place(false, 7, 7, &[1, 2]);
+        assert_eq!(
+            game_state
+                .play_challenged(&game_config, &mut rng, &play, true, Some(1))
+                .unwrap(),
+            ChallengeOutcome::Failed {
+                bonus: 0,
+                lost_turn: true
+            }
+        );
+        assert_eq!(game_state.turn, 1);
+        assert_eq!(game_state.players[0].rack.len(), 7);
+        assert_eq!((game_state.zero_turns, game_state.pass_turns), (1, 0));
+        assert_eq!(game_state.history().len(), 2);
+        game_state.next_turn();
+        assert_eq!(game_state.turn, 0);
+        assert!(game_state.undo(&game_config));
+        assert_eq!((game_state.turn, game_state.zero_turns), (1, 0));
+        assert!(game_state.undo(&game_config));
+        assert!(game_state.board_tiles.iter().all(|&t| t == 0));
+
+        // void: an invalid play is refused and nothing happens.
+        let game_config =
+            game_config::make_game_config_from_rules(r#"{ "challenge_rule": "void" }"#).unwrap();
+        let (mut game_state, mut rng) = new_game(&game_config);
+        let before = game_state.clone();
+        assert_eq!(
+            game_state
+                .play_challenged(&game_config, &mut rng, &phony, false, None)
+                .unwrap(),
+            ChallengeOutcome::Refused
+        );
+        assert_same_state(&game_state, &before);
+        assert!(game_state.history().is_empty());
+
+        // points per word: the second play forms its own word and a cross word.
+        let game_config = game_config::make_game_config_from_rules(
+            r#"{ "challenge_rule": "points_per_word", "challenges_are_passes": true }"#,
+        )
+        .unwrap();
+        let (mut game_state, mut rng) = new_game(&game_config);
+        assert_eq!(
+            game_state
+                .play_challenged(&game_config, &mut rng, &play, true, Some(1))
+                .unwrap(),
+            ChallengeOutcome::Failed {
+                bonus: 5 * equity::SCALE,
+                lost_turn: false
+            }
+        );
+        assert_eq!(game_state.players[0].score, 5 * equity::SCALE);
+        game_state.next_turn();
+        game_state.players[1].rack.clear();
+        game_state.players[1].rack.extend_from_slice(&[5, 9, 14]);
+        let play = place(true, 9, 6, &[5, 9, 14]);
+        assert_eq!(
+            game_state
+                .play_challenged(&game_config, &mut rng, &play, true, Some(0))
+                .unwrap(),
+            ChallengeOutcome::Failed {
+                bonus: 10 * equity::SCALE,
+                lost_turn: false
+            }
+        );
+        assert_eq!(game_state.players[1].score, 10 * equity::SCALE);
+        assert!(game_state.undo(&game_config));
+        assert_eq!(game_state.players[1].score, 0);
+        assert!(game_state.redo(&game_config));
+        assert_eq!(game_state.players[1].score, 10 * equity::SCALE);
+
+        // a withdrawn phony counts as a pass here.
+        game_state.next_turn();
+        let rack = &game_state.players[0].rack;
+        let phony = place(false, 9, 9, &[rack[0], rack[1]]);
+        game_state
+            .play_challenged(&game_config, &mut rng, &phony, false, Some(1))
+            .unwrap();
+        assert_eq!(game_state.pass_turns, 1);
+    }
+}
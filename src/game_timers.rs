@@ -1,17 +1,21 @@
 // Copyright (C) 2020-2026 Andy Kurnia.
 
+use super::game_config;
+
 pub struct GameTimers {
     instant: std::time::Instant,
     pub clocks_ms: Box<[i64]>,
     pub turn: i8, // -1 for nobody's
+    pub clock_mode: game_config::ClockMode,
 }
 
 impl GameTimers {
-    pub fn new(num_players: u8) -> Self {
+    pub fn new(game_config: &game_config::GameConfig) -> Self {
         Self {
             instant: std::time::Instant::now(),
-            clocks_ms: vec![0; num_players as usize].into_boxed_slice(),
+            clocks_ms: vec![0; game_config.num_players() as usize].into_boxed_slice(),
             turn: -1,
+            clock_mode: game_config.clock_mode(),
         }
     }
 
@@ -21,24 +25,189 @@ impl GameTimers {
         self.instant = std::time::Instant::now();
     }
 
+    // how much of a turn's elapsed time comes off the clock.
+    #[inline(always)]
+    fn charged_ms(&self, elapsed_ms: i64) -> i64 {
+        match self.clock_mode {
+            game_config::ClockMode::Delay(delay_ms) => (elapsed_ms - delay_ms).max(0),
+            _ => elapsed_ms,
+        }
+    }
+
+    // ends the running turn (if any), charging its time and adding any
+    // increment, and starts new_turn's.
     pub fn set_turn(&mut self, new_turn: i8) {
         let new_instant = std::time::Instant::now();
         if self.turn >= 0 && (self.turn as usize) < self.clocks_ms.len() {
-            self.clocks_ms[self.turn as usize] -= new_instant
-                .saturating_duration_since(self.instant)
-                .as_millis() as i64;
+            self.end_turn_ms(
+                new_instant
+                    .saturating_duration_since(self.instant)
+                    .as_millis() as i64,
+            );
         }
         self.instant = new_instant;
         self.turn = new_turn;
     }
 
+    fn end_turn_ms(&mut self, elapsed_ms: i64) {
+        let charged_ms = self.charged_ms(elapsed_ms);
+        let clock_ms = &mut self.clocks_ms[self.turn as usize];
+        *clock_ms -= charged_ms;
+        if let game_config::ClockMode::Increment(increment_ms) = self.clock_mode
+            && *clock_ms >= 0
+        {
+            *clock_ms += increment_ms;
+        }
+    }
+
     pub fn get_timer_as_at(&self, instant: std::time::Instant, turn: usize) -> i64 {
         if turn < self.clocks_ms.len() {
             self.clocks_ms[turn]
                 - (-((turn == self.turn as usize) as i64)
-                    & instant.saturating_duration_since(self.instant).as_millis() as i64)
+                    & self.charged_ms(
+                        instant.saturating_duration_since(self.instant).as_millis() as i64
+                    ))
         } else {
             0
         }
     }
 }
+
+// how long a move may think, in ms from when it starts. The picker stops at
+// soft once the leaders are apart, and at hard regardless.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MoveBudget {
+    pub soft_ms: i64,
+    pub hard_ms: i64,
+}
+
+// Spreads a player's clock, less reserve_ms, over the moves they can still
+// expect to make (tiles_per_move tiles drawn per turn until the bag empties,
+// then a couple more), counting a delay as free time. An increment is only
+// credited if the move ends on time, so it is not planned until it is on the
+// clock. A close decision may stretch to max_stretch times that, but never into
+// the reserve. Time a move does not use stays on the clock, so later budgets
+// grow.
+#[derive(Clone, Copy, Debug)]
+pub struct TimePolicy {
+    pub reserve_ms: i64,
+    pub tiles_per_move: f64,
+    pub max_stretch: f64,
+}
+
+impl Default for TimePolicy {
+    fn default() -> Self {
+        Self {
+            reserve_ms: 1000,
+            tiles_per_move: 4.5,
+            max_stretch: 3.0,
+        }
+    }
+}
+
+impl TimePolicy {
+    pub fn move_budget(&self, timers: &GameTimers, turn: usize, bag_len: usize) -> MoveBudget {
+        let clock_ms = timers.get_timer_as_at(std::time::Instant::now(), turn);
+        let free_ms = match timers.clock_mode {
+            game_config::ClockMode::SuddenDeath | game_config::ClockMode::Increment(_) => 0,
+            game_config::ClockMode::Delay(ms) => ms,
+        };
+        let num_moves_left =
+            bag_len as f64 / (self.tiles_per_move * timers.clocks_ms.len().max(1) as f64) + 2.0;
+        let spare_ms = (clock_ms - self.reserve_ms).max(0);
+        let hard_ms = spare_ms + free_ms;
+        let soft_ms = (spare_ms as f64 / num_moves_left) as i64 + free_ms;
+        MoveBudget {
+            soft_ms: soft_ms.min(hard_ms),
+            hard_ms: ((soft_ms as f64 * self.max_stretch) as i64).min(hard_ms),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_timers(clock_mode: game_config::ClockMode) -> GameTimers {
+        let mut timers = GameTimers::new(&game_config::make_english_game_config());
+        timers.clock_mode = clock_mode;
+        timers.reset_to(10000);
+        timers.turn = 0;
+        timers
+    }
+
+    // increments are only earned while still on time, and delays absorb the
+    // start of each turn.
+    #[test]
+    fn clock_modes_charge_turns() {
+        let mut timers = make_timers(game_config::ClockMode::SuddenDeath);
+        timers.end_turn_ms(4000);
+        assert_eq!(timers.clocks_ms[0], 6000);
+
+        let mut timers = make_timers(game_config::ClockMode::Increment(2000));
+        timers.end_turn_ms(4000);
+        assert_eq!(timers.clocks_ms[0], 8000);
+        timers.end_turn_ms(7000);
+        assert_eq!(timers.clocks_ms[0], 3000);
+        timers.end_turn_ms(4000);
+        assert_eq!(timers.clocks_ms[0], -1000);
+
+        let mut timers = make_timers(game_config::ClockMode::Delay(3000));
+        timers.end_turn_ms(2000);
+        assert_eq!(timers.clocks_ms[0], 10000);
+        timers.end_turn_ms(4000);
+        assert_eq!(timers.clocks_ms[0], 9000);
+        assert_eq!(timers.clocks_ms[1], 10000);
+    }
+
+    // the clock is shared out over the expected moves, more per move as the
+    // bag empties, and the reserve is never planned into.
+    #[test]
+    fn time_policy_shares_out_the_clock() {
+        let policy = TimePolicy::default();
+        let mut timers = make_timers(game_config::ClockMode::SuddenDeath);
+        timers.turn = -1;
+        timers.clocks_ms[0] = 91000;
+        let early = policy.move_budget(&timers, 0, 72);
+        assert_eq!(
+            early,
+            MoveBudget {
+                soft_ms: 9000,
+                hard_ms: 27000
+            }
+        );
+        let late = policy.move_budget(&timers, 0, 0);
+        assert_eq!(
+            late,
+            MoveBudget {
+                soft_ms: 45000,
+                hard_ms: 90000
+            }
+        );
+        timers.clocks_ms[0] = 500;
+        assert_eq!(
+            policy.move_budget(&timers, 0, 0),
+            MoveBudget {
+                soft_ms: 0,
+                hard_ms: 0
+            }
+        );
+        // an increment overrunning the clock would be forfeited, a delay not.
+        timers.clock_mode = game_config::ClockMode::Increment(2000);
+        assert_eq!(
+            policy.move_budget(&timers, 0, 0),
+            MoveBudget {
+                soft_ms: 0,
+                hard_ms: 0
+            }
+        );
+        timers.clock_mode = game_config::ClockMode::Delay(2000);
+        assert_eq!(
+            policy.move_budget(&timers, 0, 0),
+            MoveBudget {
+                soft_ms: 2000,
+                hard_ms: 2000
+            }
+        );
+    }
+}
@@ -0,0 +1,934 @@
+// Copyright (C) 2020-2026 Andy Kurnia.
+
+// GCG (poslfit.com/scrabble/gcg) import and export.
+//
+// parse() turns the text into typed events without needing a board.
+// Replayer then applies those events to a GameState, rescoring every
+// placement with PlayScorer and checking it against the recorded scores.
+// GcgWriter records games as they are played, in the form parse() reads.
+
+use super::{
+    alphabet, bites, display, equity, error, game_config, game_state, klv, kwg, movegen,
+    play_scorer,
+};
+use rand::prelude::*;
+use std::fmt::Write;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GcgPlayer {
+    pub nickname: String,
+    pub full_name: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GcgEventKind {
+    // word uses 0 for play-through, 0x80 | tile for blanks.
+    Place {
+        down: bool,
+        lane: i8,
+        idx: i8,
+        word: bites::Bites,
+    },
+    // tiles is empty if only the number of tiles is known.
+    Exchange {
+        tiles: bites::Bites,
+        num_tiles: u8,
+    },
+    Pass,
+    // "--", the previous placement is taken back.
+    PhonyWithdrawn,
+    // "(challenge)", awarded for an unsuccessful challenge of the previous
+    // placement, which must be by the same player.
+    ChallengeBonus,
+    // "(TILES)" with a positive score, the opponents' tiles after going out.
+    EndRackPoints {
+        tiles: bites::Bites,
+    },
+    // "(TILES)" with a negative score, own tiles left when the game ends.
+    EndRackPenalty {
+        tiles: bites::Bites,
+    },
+    // "(time)", overtime penalty.
+    TimePenalty,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GcgEvent {
+    pub line_number: usize, // 1-based
+    pub player: u8,
+    pub rack: Option<bites::Bites>,
+    pub kind: GcgEventKind,
+    pub score: i32,      // in points
+    pub cumulative: i32, // in points
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Gcg {
+    pub players: Vec<GcgPlayer>,
+    // all other pragmas in order, without the leading '#'.
+    pub pragmas: Vec<(String, String)>,
+    pub events: Vec<GcgEvent>,
+}
+
+impl Gcg {
+    pub fn pragma(&self, name: &str) -> Option<&str> {
+        self.pragmas
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+// "8H" is across (row 8, column H), "H8" is down.
+pub fn parse_coord(s: &str) -> Option<(bool, i8, i8)> {
+    let sb = s.as_bytes();
+    let num_digits = sb.iter().take_while(|c| c.is_ascii_digit()).count();
+    let (down, row_str, col_str) = if num_digits > 0 {
+        (false, &s[..num_digits], &sb[num_digits..])
+    } else {
+        let num_letters = sb.iter().take_while(|c| c.is_ascii_uppercase()).count();
+        (true, &s[num_letters..], &sb[..num_letters])
+    };
+    let row = row_str.parse::<usize>().ok()?.checked_sub(1)?;
+    let col = display::str_to_column_usize(col_str)?;
+    if row > i8::MAX as usize || col > i8::MAX as usize {
+        return None;
+    }
+    let (row, col) = (row as i8, col as i8);
+    Some(if down {
+        (true, col, row)
+    } else {
+        (false, row, col)
+    })
+}
+
+// play-through is either "." or letters in parentheses, both become 0.
+fn parse_word(plays_reader: &alphabet::AlphabetReader, s: &str) -> error::Returns<bites::Bites> {
+    let sb = s.as_bytes();
+    let mut v = Vec::with_capacity(sb.len());
+    let mut in_parens = false;
+    let mut ix = 0;
+    while ix < sb.len() {
+        match sb[ix] {
+            b'.' if !in_parens => {
+                v.push(0);
+                ix += 1;
+            }
+            b'(' if !in_parens => {
+                in_parens = true;
+                ix += 1;
+            }
+            b')' if in_parens => {
+                in_parens = false;
+                ix += 1;
+            }
+            _ => {
+                let (tile, end_ix) = plays_reader
+                    .next_tile(sb, ix)
+                    .ok_or_else(|| format!("invalid tile in word {s:?}"))?;
+                v.push(if in_parens { 0 } else { tile });
+                ix = end_ix;
+            }
+        }
+    }
+    if in_parens {
+        return_error!(format!("unbalanced parentheses in word {s:?}"));
+    }
+    Ok(v[..].into())
+}
+
+fn parse_rack(racks_reader: &alphabet::AlphabetReader, s: &str) -> error::Returns<bites::Bites> {
+    let mut v = Vec::new();
+    racks_reader.set_word(s, &mut v)?;
+    Ok(v[..].into())
+}
+
+pub fn parse(alphabet: &alphabet::Alphabet, text: &str) -> error::Returns<Gcg> {
+    let plays_reader = alphabet::AlphabetReader::new_for_plays(alphabet);
+    let racks_reader = alphabet::AlphabetReader::new_for_racks(alphabet);
+    let mut gcg = Gcg::default();
+    for (line_index, line) in text.lines().enumerate() {
+        let line_number = line_index + 1;
+        let line = line.trim();
+        if let Some(pragma) = line.strip_prefix('#') {
+            let (name, value) = pragma
+                .split_once(char::is_whitespace)
+                .unwrap_or((pragma, ""));
+            let value = value.trim();
+            if let Some(player_num) = name.strip_prefix("player")
+                && let Ok(player_num) = player_num.parse::<usize>()
+            {
+                let (nickname, full_name) = value
+                    .split_once(char::is_whitespace)
+                    .unwrap_or((value, value));
+                if player_num != gcg.players.len() + 1 {
+                    return_error!(format!("line {line_number}: unexpected #{name}"));
+                }
+                gcg.players.push(GcgPlayer {
+                    nickname: nickname.into(),
+                    full_name: full_name.trim().into(),
+                });
+            } else {
+                gcg.pragmas.push((name.into(), value.into()));
+            }
+            continue;
+        }
+        let Some(event_line) = line.strip_prefix('>') else {
+            // blank lines and continuations of #note.
+            continue;
+        };
+        let (nickname, rest) = event_line
+            .split_once(':')
+            .ok_or_else(|| format!("line {line_number}: missing ':'"))?;
+        let player = match gcg.players.iter().position(|p| p.nickname == nickname) {
+            Some(p) => p,
+            None => {
+                // no #player pragmas, assign players in order of appearance.
+                gcg.players.push(GcgPlayer {
+                    nickname: nickname.into(),
+                    full_name: nickname.into(),
+                });
+                gcg.players.len() - 1
+            }
+        };
+        let tokens = rest.split_whitespace().collect::<Vec<_>>();
+        if tokens.len() < 3 {
+            return_error!(format!("line {line_number}: too few fields"));
+        }
+        let score = tokens[tokens.len() - 2]
+            .parse::<i32>()
+            .map_err(|_| format!("line {line_number}: invalid score"))?;
+        let cumulative = tokens[tokens.len() - 1]
+            .parse::<i32>()
+            .map_err(|_| format!("line {line_number}: invalid cumulative score"))?;
+        let tokens = &tokens[..tokens.len() - 2];
+        let (rack, coord, action) = match tokens {
+            [rack, coord, word] => (Some(*rack), Some(*coord), *word),
+            [coord, word] if parse_coord(coord).is_some() => (None, Some(*coord), *word),
+            [rack, action] => (Some(*rack), None, *action),
+            [action] => (None, None, *action),
+            _ => {
+                return_error!(format!("line {line_number}: unexpected fields"));
+            }
+        };
+        let rack = match rack {
+            Some(rack) => Some(
+                parse_rack(&racks_reader, rack).map_err(|e| format!("line {line_number}: {e}"))?,
+            ),
+            None => None,
+        };
+        let kind = if let Some(coord) = coord {
+            let (down, lane, idx) = parse_coord(coord)
+                .ok_or_else(|| format!("line {line_number}: invalid coordinates"))?;
+            let word = parse_word(&plays_reader, action)
+                .map_err(|e| format!("line {line_number}: {e}"))?;
+            GcgEventKind::Place {
+                down,
+                lane,
+                idx,
+                word,
+            }
+        } else if action == "-" {
+            GcgEventKind::Pass
+        } else if action == "--" {
+            GcgEventKind::PhonyWithdrawn
+        } else if action == "(challenge)" {
+            GcgEventKind::ChallengeBonus
+        } else if action == "(time)" {
+            GcgEventKind::TimePenalty
+        } else if let Some(exchanged) = action.strip_prefix('-') {
+            if let Ok(num_tiles) = exchanged.parse::<u8>() {
+                GcgEventKind::Exchange {
+                    tiles: [][..].into(),
+                    num_tiles,
+                }
+            } else {
+                let tiles = parse_rack(&racks_reader, exchanged)
+                    .map_err(|e| format!("line {line_number}: {e}"))?;
+                let num_tiles = tiles.len() as u8;
+                GcgEventKind::Exchange { tiles, num_tiles }
+            }
+        } else if let Some(tiles) = action.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+            let tiles =
+                parse_rack(&racks_reader, tiles).map_err(|e| format!("line {line_number}: {e}"))?;
+            if score < 0 {
+                GcgEventKind::EndRackPenalty { tiles }
+            } else {
+                GcgEventKind::EndRackPoints { tiles }
+            }
+        } else {
+            return_error!(format!("line {line_number}: unrecognized move {action:?}"));
+        };
+        gcg.events.push(GcgEvent {
+            line_number,
+            player: player as u8,
+            rack,
+            kind,
+            score,
+            cumulative,
+        });
+    }
+    Ok(gcg)
+}
+
+// Applies GcgEvents one at a time, so callers can inspect each position.
+pub struct Replayer {
+    play_scorer: play_scorer::PlayScorer,
+    // the state before the most recent placement and that placement (scored),
+    // to replay it as challenged if the next event says it was.
+    last_place: Option<(game_state::GameState, movegen::Play)>,
+}
+
+impl Default for Replayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Replayer {
+    pub fn new() -> Self {
+        Self {
+            play_scorer: play_scorer::PlayScorer::new(),
+            last_place: None,
+        }
+    }
+
+    // Racks in the gcg are dealt with set_current_rack, tiles not known are
+    // whatever happens to be drawn. rng is only used for exchanges.
+    pub fn apply<N: kwg::Node, L: kwg::Node>(
+        &mut self,
+        game_config: &game_config::GameConfig,
+        kwg: &kwg::Kwg<N>,
+        klv: &klv::Klv<L>,
+        game_state: &mut game_state::GameState,
+        rng: &mut dyn Rng,
+        event: &GcgEvent,
+    ) -> error::Returns<()> {
+        let line_number = event.line_number;
+        let player = event.player as usize;
+        if player >= game_state.players.len() {
+            return_error!(format!("line {line_number}: too many players"));
+        }
+        let alphabet = game_config.alphabet();
+        match &event.kind {
+            GcgEventKind::Place {
+                down,
+                lane,
+                idx,
+                word,
+            } => {
+                game_state.turn = event.player;
+                let placed_tiles = word
+                    .iter()
+                    .filter(|&&tile| tile != 0)
+                    .map(|&tile| tile & !((tile as i8) >> 7) as u8)
+                    .collect::<Vec<_>>();
+                self.set_rack(game_state, event, &placed_tiles)?;
+                let mut play = movegen::Play::Place {
+                    down: *down,
+                    lane: *lane,
+                    idx: *idx,
+                    word: word.clone(),
+                    score: 0,
+                };
+                let board_snapshot = &movegen::BoardSnapshot {
+                    board_tiles: &game_state.board_tiles,
+                    game_config,
+                    kwg,
+                    klv,
+                };
+                self.play_scorer
+                    .validate_play(board_snapshot, game_state, &play)
+                    .map_err(|e| format!("line {line_number}: {e}"))?;
+                let computed_score = self.play_scorer.compute_score(board_snapshot, &play);
+                if computed_score != equity::scale_score(event.score) {
+                    return_error!(format!(
+                        "line {line_number}: score is {} but gcg says {}",
+                        equity::descale_score(computed_score),
+                        event.score
+                    ));
+                }
+                if let movegen::Play::Place { score, .. } = &mut play {
+                    *score = computed_score;
+                }
+                self.last_place = Some((game_state.clone(), play.clone()));
+                game_state
+                    .play(game_config, rng, &play)
+                    .map_err(|e| format!("line {line_number}: {e}"))?;
+                game_state.next_turn();
+            }
+            GcgEventKind::Exchange { tiles, num_tiles } => {
+                game_state.turn = event.player;
+                self.set_rack(game_state, event, tiles)?;
+                let tiles = if tiles.is_empty() {
+                    let rack = &game_state.current_player().rack;
+                    if rack.len() < *num_tiles as usize {
+                        return_error!(format!("line {line_number}: not enough tiles to exchange"));
+                    }
+                    rack[..*num_tiles as usize].into()
+                } else {
+                    tiles.clone()
+                };
+                let play = movegen::Play::Exchange { tiles };
+                let board_snapshot = &movegen::BoardSnapshot {
+                    board_tiles: &game_state.board_tiles,
+                    game_config,
+                    kwg,
+                    klv,
+                };
+                self.play_scorer
+                    .validate_play(board_snapshot, game_state, &play)
+                    .map_err(|e| format!("line {line_number}: {e}"))?;
+                self.check_score(event, 0)?;
+                game_state
+                    .play(game_config, rng, &play)
+                    .map_err(|e| format!("line {line_number}: {e}"))?;
+                self.last_place = None;
+                game_state.next_turn();
+            }
+            GcgEventKind::Pass => {
+                game_state.turn = event.player;
+                self.set_rack(game_state, event, &[])?;
+                self.check_score(event, 0)?;
+                game_state.play(
+                    game_config,
+                    rng,
+                    &movegen::Play::Exchange {
+                        tiles: [][..].into(),
+                    },
+                )?;
+                self.last_place = None;
+                game_state.next_turn();
+            }
+            GcgEventKind::PhonyWithdrawn | GcgEventKind::ChallengeBonus => {
+                // the placement is played again, this time challenged (by the
+                // next player, the only one who can be under double).
+                let Some((before_last_place, play)) = self.last_place.take() else {
+                    return_error!(format!("line {line_number}: no play was challenged"));
+                };
+                if before_last_place.turn != event.player {
+                    return_error!(format!(
+                        "line {line_number}: challenged play was not by this player"
+                    ));
+                }
+                let valid = event.kind == GcgEventKind::ChallengeBonus;
+                if !valid {
+                    let scored =
+                        game_state.players[player].score - before_last_place.players[player].score;
+                    self.check_score(event, -equity::descale_score(scored))?;
+                }
+                *game_state = before_last_place;
+                let challenger = ((player + 1) % game_state.players.len()) as u8;
+                match game_state
+                    .play_challenged(game_config, rng, &play, valid, Some(challenger))
+                    .map_err(|e| format!("line {line_number}: {e}"))?
+                {
+                    game_state::ChallengeOutcome::Withdrawn => {}
+                    game_state::ChallengeOutcome::Failed { bonus, .. } => {
+                        if bonus != equity::scale_score(event.score) {
+                            return_error!(format!(
+                                "line {line_number}: challenge bonus is {} but gcg says {}",
+                                equity::descale_score(bonus),
+                                event.score
+                            ));
+                        }
+                    }
+                    _ => {
+                        return_error!(format!(
+                            "line {line_number}: the challenge rule does not allow this"
+                        ));
+                    }
+                }
+                game_state.next_turn();
+            }
+            GcgEventKind::TimePenalty => {
+                self.set_rack(game_state, event, &[])?;
+                game_state.adjust_score(event.player, equity::scale_score(event.score));
+            }
+            GcgEventKind::EndRackPoints { tiles } => {
+                let multiplier = if game_state.players.len() == 2 { 2 } else { 1 };
+                self.check_score(event, multiplier * alphabet.rack_score(tiles))?;
+                game_state.adjust_score(event.player, equity::scale_score(event.score));
+            }
+            GcgEventKind::EndRackPenalty { tiles } => {
+                self.check_score(event, -alphabet.rack_score(tiles))?;
+                game_state.adjust_score(event.player, equity::scale_score(event.score));
+            }
+        }
+        let actual_cumulative = equity::descale_score(game_state.players[player].score);
+        if actual_cumulative != event.cumulative {
+            return_error!(format!(
+                "line {line_number}: cumulative score is {actual_cumulative} but gcg says {}",
+                event.cumulative
+            ));
+        }
+        Ok(())
+    }
+
+    // Without a rack in the gcg, the player must at least have the used tiles.
+    fn set_rack(
+        &self,
+        game_state: &mut game_state::GameState,
+        event: &GcgEvent,
+        used_tiles: &[u8],
+    ) -> error::Returns<()> {
+        let desired_rack = match &event.rack {
+            Some(rack) => &rack[..],
+            None => {
+                let rack = &game_state.players[event.player as usize].rack;
+                let mut rack_tally = [0u8; 256];
+                rack.iter().for_each(|&tile| rack_tally[tile as usize] += 1);
+                if used_tiles.iter().all(|&tile| {
+                    rack_tally[tile as usize] = rack_tally[tile as usize].wrapping_sub(1);
+                    rack_tally[tile as usize] != 0xff
+                }) {
+                    return Ok(());
+                }
+                used_tiles
+            }
+        };
+        let saved_turn = game_state.turn;
+        game_state.turn = event.player;
+        game_state.set_current_rack(desired_rack);
+        game_state.turn = saved_turn;
+        let mut actual_rack = game_state.players[event.player as usize].rack.clone();
+        let mut desired_rack = desired_rack.to_vec();
+        actual_rack.sort_unstable();
+        desired_rack.sort_unstable();
+        if actual_rack != desired_rack {
+            return_error!(format!(
+                "line {}: rack is not available from the bag",
+                event.line_number
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_score(&self, event: &GcgEvent, expected: i32) -> error::Returns<()> {
+        if event.score != expected {
+            return_error!(format!(
+                "line {}: score should be {expected} but gcg says {}",
+                event.line_number, event.score
+            ));
+        }
+        Ok(())
+    }
+}
+
+// Replays the whole gcg from an empty board.
+pub fn replay<N: kwg::Node, L: kwg::Node>(
+    game_config: &game_config::GameConfig,
+    kwg: &kwg::Kwg<N>,
+    klv: &klv::Klv<L>,
+    rng: &mut dyn Rng,
+    gcg: &Gcg,
+) -> error::Returns<game_state::GameState> {
+    if gcg.players.len() != game_config.num_players() as usize {
+        return_error!(format!(
+            "gcg has {} players but game config has {}",
+            gcg.players.len(),
+            game_config.num_players()
+        ));
+    }
+    let mut game_state = game_state::GameState::new(game_config);
+    let mut replayer = Replayer::new();
+    for event in gcg.events.iter() {
+        replayer.apply(game_config, kwg, klv, &mut game_state, rng, event)?;
+    }
+    Ok(game_state)
+}
+
+// Records a game as it is played. Call record_play before game_state.play
+// (it needs the rack as it was), and record_game_end once check_game_ended
+// says the game is over.
+pub struct GcgWriter {
+    buf: String,
+    nicknames: Box<[String]>,
+    cumulative: Box<[i32]>, // in points
+}
+
+impl GcgWriter {
+    // nicknames must not contain whitespace or ':'.
+    pub fn new(nicknames: &[&str]) -> Self {
+        let mut buf = String::new();
+        buf.push_str("#character-encoding UTF-8\n");
+        for (i, nickname) in nicknames.iter().enumerate() {
+            writeln!(buf, "#player{} {} {}", i + 1, nickname, nickname).unwrap();
+        }
+        Self {
+            buf,
+            nicknames: nicknames.iter().map(|&s| s.into()).collect(),
+            cumulative: vec![0; nicknames.len()].into_boxed_slice(),
+        }
+    }
+
+    // Pragmas should be written before the first move.
+    pub fn pragma(&mut self, name: &str, value: &str) {
+        writeln!(self.buf, "#{name} {value}").unwrap();
+    }
+
+    fn write_event(
+        &mut self,
+        alphabet: &alphabet::Alphabet,
+        player: u8,
+        rack: &[u8],
+        action: std::fmt::Arguments<'_>,
+        score: i32,
+    ) {
+        self.cumulative[player as usize] += score;
+        write!(self.buf, ">{}: ", self.nicknames[player as usize]).unwrap();
+        if !rack.is_empty() {
+            write!(self.buf, "{} ", alphabet.fmt_rack(rack)).unwrap();
+        }
+        writeln!(
+            self.buf,
+            "{action} {score:+} {}",
+            self.cumulative[player as usize]
+        )
+        .unwrap();
+    }
+
+    pub fn record_play(
+        &mut self,
+        game_config: &game_config::GameConfig,
+        player: u8,
+        rack: &[u8],
+        play: &movegen::Play,
+    ) {
+        let alphabet = game_config.alphabet();
+        match play {
+            movegen::Play::Exchange { tiles } => {
+                if tiles.is_empty() {
+                    self.write_event(alphabet, player, rack, format_args!("-"), 0);
+                } else {
+                    self.write_event(
+                        alphabet,
+                        player,
+                        rack,
+                        format_args!("-{}", alphabet.fmt_rack(tiles)),
+                        0,
+                    );
+                }
+            }
+            movegen::Play::Place {
+                down,
+                lane,
+                idx,
+                word,
+                score,
+            } => {
+                let mut action = String::new();
+                if *down {
+                    write!(action, "{}{} ", display::column(*lane), idx + 1).unwrap();
+                } else {
+                    write!(action, "{}{} ", lane + 1, display::column(*idx)).unwrap();
+                }
+                for &tile in word.iter() {
+                    action.push_str(alphabet.of_board(tile).unwrap_or("."));
+                }
+                self.write_event(
+                    alphabet,
+                    player,
+                    rack,
+                    format_args!("{action}"),
+                    equity::descale_score(*score),
+                );
+            }
+        }
+    }
+
+    // game_state.turn is the player whose move ended the game.
+    pub fn record_game_end(
+        &mut self,
+        game_config: &game_config::GameConfig,
+        game_state: &game_state::GameState,
+        end: game_state::CheckGameEnded,
+    ) {
+        let alphabet = game_config.alphabet();
+        let num_players = game_state.players.len();
+        match end {
+            game_state::CheckGameEnded::NotEnded => {}
+            game_state::CheckGameEnded::PlayedOut => {
+                let mut left_tiles = Vec::new();
+                for i in 0..num_players {
+                    if i != game_state.turn as usize {
+                        let rack = &game_state.players[i].rack;
+                        left_tiles.extend_from_slice(rack);
+                        if num_players != 2 {
+                            self.write_event(
+                                alphabet,
+                                i as u8,
+                                rack,
+                                format_args!("({})", alphabet.fmt_rack(rack)),
+                                -alphabet.rack_score(rack),
+                            );
+                        }
+                    }
+                }
+                let multiplier = if num_players == 2 { 2 } else { 1 };
+                self.write_event(
+                    alphabet,
+                    game_state.turn,
+                    &[],
+                    format_args!("({})", alphabet.fmt_rack(&left_tiles)),
+                    multiplier * alphabet.rack_score(&left_tiles),
+                );
+            }
+            game_state::CheckGameEnded::ZeroScores => {
+                for (i, player) in game_state.players.iter().enumerate() {
+                    if !player.rack.is_empty() {
+                        self.write_event(
+                            alphabet,
+                            i as u8,
+                            &player.rack,
+                            format_args!("({})", alphabet.fmt_rack(&player.rack)),
+                            -alphabet.rack_score(&player.rack),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    // points is usually negative, see game_config.time_adjustment.
+    pub fn record_time_penalty(
+        &mut self,
+        game_config: &game_config::GameConfig,
+        player: u8,
+        rack: &[u8],
+        points: i32,
+    ) {
+        self.write_event(
+            game_config.alphabet(),
+            player,
+            rack,
+            format_args!("(time)"),
+            points,
+        );
+    }
+
+    // GCG has no forfeit event, so the record ends with a note saying why.
+    pub fn record_time_forfeit(&mut self, player: u8) {
+        writeln!(
+            self.buf,
+            "#note {} forfeited on time",
+            self.nicknames[player as usize]
+        )
+        .unwrap();
+    }
+
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        &self.buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::make_tiny_kwg;
+
+    const GAME: &str = "#character-encoding UTF-8
+#player1 alice Alice A
+#player2 bob Bob B
+#lexicon TEST
+>alice: ACEKTUV 8G CAT +10 10
+>bob: ADEIORS I7 A.E +5 5
+>bob: ADEIORS (challenge) +5 10
+>alice: EKUVXYZ 9E ZEK +34 44
+>alice: EKUVXYZ -- -34 10
+>bob: EOORR?? - +0 10
+>alice: EKUVXYZ -VXZ +0 10
+#note a note
+that continues
+>alice: ABEKUXY (time) -10 0
+";
+
+    #[test]
+    fn parses_events() {
+        let game_config = game_config::make_english_game_config();
+        let gcg = parse(game_config.alphabet(), GAME).unwrap();
+        assert_eq!(gcg.players.len(), 2);
+        assert_eq!(gcg.players[1].nickname, "bob");
+        assert_eq!(gcg.players[1].full_name, "Bob B");
+        assert_eq!(gcg.pragma("lexicon"), Some("TEST"));
+        assert_eq!(gcg.events.len(), 8);
+        assert_eq!(
+            gcg.events[1].kind,
+            GcgEventKind::Place {
+                down: true,
+                lane: 8,
+                idx: 6,
+                word: [1, 0, 5][..].into(),
+            }
+        );
+        assert_eq!(gcg.events[2].kind, GcgEventKind::ChallengeBonus);
+        assert_eq!(gcg.events[4].kind, GcgEventKind::PhonyWithdrawn);
+        assert_eq!(gcg.events[5].kind, GcgEventKind::Pass);
+        assert_eq!(
+            gcg.events[6].kind,
+            GcgEventKind::Exchange {
+                tiles: [22, 24, 26][..].into(),
+                num_tiles: 3,
+            }
+        );
+        assert_eq!(gcg.events[7].line_number, 14);
+        assert_eq!(gcg.events[7].score, -10);
+        assert_eq!(parse_coord("H8"), Some((true, 7, 7)));
+        assert_eq!(parse_coord("15A"), Some((false, 14, 0)));
+        assert_eq!(parse_coord("8"), None);
+    }
+
+    #[test]
+    fn replays_and_checks_scores() {
+        let game_config =
+            game_config::make_game_config_from_rules(r#"{ "challenge_rule": "points" }"#).unwrap();
+        let kwg = kwg::Kwg::<kwg::Node22>::from_bytes_alloc(kwg::EMPTY_KWG_BYTES);
+        let klv = klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES);
+        let mut rng = rand::rngs::ChaCha20Rng::seed_from_u64(1);
+        let gcg = parse(game_config.alphabet(), GAME).unwrap();
+        let game_state = replay(&game_config, &kwg, &klv, &mut rng, &gcg).unwrap();
+        assert_eq!(game_state.players[0].score, equity::scale_score(0));
+        assert_eq!(game_state.players[1].score, equity::scale_score(10));
+        // the withdrawn ZEK is not on the board, bob's ATE is.
+        let dim = game_config.board_layout().dim();
+        assert_eq!(game_state.board_tiles[dim.at_row_col(8, 4)], 0);
+        assert_eq!(game_state.board_tiles[dim.at_row_col(8, 8)], 5);
+        assert_eq!(game_state.turn, 1);
+        assert_eq!(game_state.zero_turns, 3);
+        assert_eq!(game_state.pass_turns, 0);
+
+        // with the turn log kept, the replayed game steps back to its opening
+        // and forward again, racks and all.
+        let mut game_state = game_state::GameState::new(&game_config);
+        game_state.set_keep_history(true);
+        let opening = game_state.clone();
+        let mut replayer = Replayer::new();
+        for (i, event) in gcg.events.iter().enumerate() {
+            replayer
+                .apply(&game_config, &kwg, &klv, &mut game_state, &mut rng, event)
+                .unwrap();
+            let last_turn = game_state.history().last().unwrap();
+            if i == 2 {
+                assert_eq!(last_turn.challenge_bonus, equity::scale_score(5));
+            }
+            if i == 4 {
+                // the withdrawn phony went through the challenge, so bob saw ZEK.
+                assert!(last_turn.lost_turn);
+                let mut known = game_state.players[0].known.present.clone();
+                known.sort_unstable();
+                assert_eq!(known, [5, 11, 26]);
+            }
+        }
+        let ended = game_state.clone();
+        let same = |a: &game_state::GameState, b: &game_state::GameState| {
+            a.board_tiles == b.board_tiles
+                && a.bag.layout() == b.bag.layout()
+                && a.turn == b.turn
+                && a.players
+                    .iter()
+                    .zip(b.players.iter())
+                    .all(|(p, q)| p.score == q.score && p.rack == q.rack)
+        };
+        while game_state.undo(&game_config) {}
+        assert!(same(&game_state, &opening));
+        while game_state.redo(&game_config) {}
+        assert!(same(&game_state, &ended));
+
+        // under double, the challenger gets no points for a valid play.
+        let double_config = game_config::make_english_game_config();
+        let err = replay(&double_config, &kwg, &klv, &mut rng, &gcg)
+            .err()
+            .unwrap();
+        assert!(err.to_string().starts_with("line 7: challenge bonus is 0"));
+
+        let bad_gcg = parse(
+            game_config.alphabet(),
+            &GAME.replace("8G CAT +10 10", "8G CAT +11 11"),
+        )
+        .unwrap();
+        let err = replay(&game_config, &kwg, &klv, &mut rng, &bad_gcg)
+            .err()
+            .unwrap();
+        assert!(err.to_string().starts_with("line 5: score is 10"));
+    }
+
+    // Autoplays a game on a small lexicon, records it and replays the record.
+    #[test]
+    fn written_games_replay() {
+        let game_config = game_config::make_english_game_config();
+        let alphabet = game_config.alphabet();
+        let kwg = make_tiny_kwg(&[
+            "AA", "AB", "AD", "AE", "AG", "AH", "AI", "AL", "AM", "AN", "AR", "AS", "AT", "AW",
+            "AX", "AY", "BE", "DE", "DO", "ED", "EH", "EL", "EM", "EN", "ER", "ES", "EX", "GO",
+            "HE", "HI", "HO", "ID", "IF", "IN", "IS", "IT", "JO", "KA", "LA", "LI", "LO", "ME",
+            "MI", "MO", "MU", "NA", "NE", "NO", "NU", "OD", "OE", "OF", "OH", "OI", "OM", "ON",
+            "OP", "OR", "OS", "OW", "OX", "OY", "PA", "PE", "PI", "QI", "RE", "SH", "SI", "SO",
+            "TA", "TI", "TO", "UH", "UM", "UN", "UP", "US", "UT", "WE", "WO", "XI", "XU", "YA",
+            "YE", "YO", "ZA",
+        ]);
+        let klv = klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES);
+        let mut rng = rand::rngs::ChaCha20Rng::seed_from_u64(2);
+        let mut move_generator = movegen::KurniaMoveGenerator::new(&game_config);
+        let mut game_state = game_state::GameState::new(&game_config);
+        game_state.reset_and_draw_tiles(&game_config, &mut rng);
+        let mut final_scores = vec![0; game_state.players.len()];
+        let mut gcg_writer = GcgWriter::new(&["p1", "p2"]);
+        gcg_writer.pragma("lexicon", "TINY");
+        loop {
+            let board_snapshot = &movegen::BoardSnapshot {
+                board_tiles: &game_state.board_tiles,
+                game_config: &game_config,
+                kwg: &kwg,
+                klv: &klv,
+            };
+            move_generator.gen_moves_unfiltered(&movegen::GenMovesParams {
+                board_snapshot,
+                rack: &game_state.current_player().rack,
+                max_gen: 1,
+                num_exchanges_by_this_player: game_state.current_player().num_exchanges,
+                always_include_pass: false,
+                dynamic_leaves: None,
+            });
+            let play = &move_generator.plays[0].play;
+            gcg_writer.record_play(
+                &game_config,
+                game_state.turn,
+                &game_state.current_player().rack,
+                play,
+            );
+            game_state.play(&game_config, &mut rng, play).unwrap();
+            match game_state.check_game_ended(&game_config, &mut final_scores) {
+                game_state::CheckGameEnded::NotEnded => game_state.next_turn(),
+                end => {
+                    gcg_writer.record_game_end(&game_config, &game_state, end);
+                    break;
+                }
+            }
+        }
+        gcg_writer.record_time_penalty(&game_config, 1, &game_state.players[1].rack, -10);
+        final_scores[1] -= equity::scale_score(10);
+
+        let gcg = parse(alphabet, gcg_writer.as_str()).unwrap();
+        assert_eq!(gcg.pragma("lexicon"), Some("TINY"));
+        assert!(gcg.events.len() > 10);
+        let mut replay_rng = rand::rngs::ChaCha20Rng::seed_from_u64(3);
+        let replayed = replay(&game_config, &kwg, &klv, &mut replay_rng, &gcg).unwrap();
+        assert_eq!(replayed.board_tiles, game_state.board_tiles);
+        for (player, &final_score) in replayed.players.iter().zip(final_scores.iter()) {
+            assert_eq!(player.score, final_score);
+        }
+    }
+
+    // a forfeited game's record says so, and still parses.
+    #[test]
+    fn records_a_time_forfeit() {
+        let game_config = game_config::make_english_game_config();
+        let mut gcg_writer = GcgWriter::new(&["p1", "p2"]);
+        gcg_writer.record_time_penalty(&game_config, 0, &[1, 2], -10);
+        gcg_writer.record_time_forfeit(0);
+        let gcg = parse(game_config.alphabet(), gcg_writer.as_str()).unwrap();
+        assert_eq!(gcg.events.len(), 1);
+        assert_eq!(gcg.pragma("note"), Some("p1 forfeited on time"));
+    }
+}
@@ -0,0 +1,328 @@
+// Copyright (C) 2020-2026 Andy Kurnia.
+
+// infers what an opponent kept from the play they just made. a leave is
+// plausible when, holding the played tiles plus that leave, the observed play
+// is within a tolerance of the best play movegen finds for that rack.
+// plausible leaves are weighted by the number of ways to draw them from the
+// unseen tiles.
+
+use super::{error, kwg, movegen, prob};
+use rand::prelude::*;
+
+#[derive(Clone)]
+pub struct InferredLeave {
+    pub leave: Box<[u8]>, // sorted, blank is 0
+    pub weight: f64,
+}
+
+// a weighted distribution over the leaves an opponent may have kept.
+#[derive(Clone)]
+pub struct LeaveInference {
+    leaves: Vec<InferredLeave>,
+    total_weight: f64,
+}
+
+impl LeaveInference {
+    // for leaves weighted some other way.
+    pub fn from_leaves(leaves: Vec<InferredLeave>) -> Self {
+        let total_weight = leaves
+            .iter()
+            .map(|inferred_leave| inferred_leave.weight)
+            .sum();
+        Self {
+            leaves,
+            total_weight,
+        }
+    }
+
+    #[inline(always)]
+    pub fn leaves(&self) -> &[InferredLeave] {
+        &self.leaves
+    }
+
+    #[inline(always)]
+    pub fn total_weight(&self) -> f64 {
+        self.total_weight
+    }
+
+    // empty when no leave explains the play, such as when the tolerance is
+    // too tight for a bluff or a misplay.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    // a leave drawn in proportion to its weight.
+    pub fn sample(&self, rng: &mut dyn Rng) -> Option<&[u8]> {
+        let mut remaining = rng.random::<f64>() * self.total_weight;
+        for inferred_leave in self.leaves.iter() {
+            if remaining < inferred_leave.weight {
+                return Some(&inferred_leave.leave);
+            }
+            remaining -= inferred_leave.weight;
+        }
+        // only reachable through rounding.
+        self.leaves
+            .last()
+            .map(|inferred_leave| &inferred_leave.leave[..])
+    }
+}
+
+pub struct InferenceParams<'a, N: kwg::Node, L: kwg::Node> {
+    // the board before the play.
+    pub board_snapshot: &'a movegen::BoardSnapshot<'a, N, L>,
+    // a Place or a pass. the tiles of an exchange are not seen, so an exchange
+    // cannot be inferred from.
+    pub play: &'a movegen::Play,
+    // the tally of tiles the leave may come from: the tiles unseen by the
+    // observer, not counting the tiles of the play itself.
+    pub unseen: &'a [u8],
+    // number of tiles kept, usually rack_size minus the tiles played.
+    pub leave_size: usize,
+    pub num_exchanges_by_this_player: i16,
+    // in millipoints, how far below the best equity the play may be.
+    pub equity_tolerance: i32,
+    // the enumeration is abandoned with an error beyond this many leaves.
+    pub max_leaves: usize,
+}
+
+// number of distinct leaves of this size drawable from unseen, stopping early
+// once it exceeds limit.
+fn count_leaves(unseen: &[u8], leave_size: usize, limit: usize) -> usize {
+    // ways[k] = number of multisets of size k from the tiles so far.
+    let mut ways = vec![0usize; leave_size + 1];
+    ways[0] = 1;
+    for &count in unseen.iter() {
+        for k in (1..=leave_size).rev() {
+            let mut sum = ways[k];
+            for j in 1..=(count as usize).min(k) {
+                sum = sum.saturating_add(ways[k - j]);
+            }
+            ways[k] = sum.min(limit.saturating_add(1));
+        }
+    }
+    ways[leave_size]
+}
+
+pub fn infer_leaves<N: kwg::Node, L: kwg::Node>(
+    move_generator: &mut movegen::KurniaMoveGenerator,
+    params: &InferenceParams<'_, N, L>,
+) -> error::Returns<LeaveInference> {
+    let mut played_tiles = Vec::new();
+    match params.play {
+        movegen::Play::Place { word, .. } => {
+            for &tile in word.iter() {
+                if tile & 0x80 != 0 {
+                    played_tiles.push(0);
+                } else if tile != 0 {
+                    played_tiles.push(tile);
+                }
+            }
+        }
+        movegen::Play::Exchange { tiles } => {
+            if !tiles.is_empty() {
+                return_error!("cannot infer a leave from an exchange".into());
+            }
+        }
+    }
+    let num_leaves = count_leaves(params.unseen, params.leave_size, params.max_leaves);
+    if num_leaves > params.max_leaves {
+        return_error!(format!(
+            "too many leaves to infer from (more than {})",
+            params.max_leaves
+        ));
+    }
+    let max_count = params.unseen.iter().copied().max().unwrap_or(0) as usize;
+    let pascal = prob::Pascal::with_rows(max_count + 1);
+
+    let mut inference = LeaveInference {
+        leaves: Vec::with_capacity(num_leaves),
+        total_weight: 0.0,
+    };
+    let mut leave = Vec::with_capacity(params.leave_size);
+    let mut rack = Vec::with_capacity(played_tiles.len() + params.leave_size);
+    enumerate_leaves(
+        params.unseen,
+        0,
+        params.leave_size,
+        1.0,
+        &pascal,
+        &mut leave,
+        &mut |leave: &[u8], weight: f64| {
+            rack.clear();
+            rack.extend_from_slice(&played_tiles);
+            rack.extend_from_slice(leave);
+            rack.sort_unstable();
+            if play_is_plausible(move_generator, params, &rack) {
+                inference.total_weight += weight;
+                inference.leaves.push(InferredLeave {
+                    leave: leave.into(),
+                    weight,
+                });
+            }
+        },
+    );
+    Ok(inference)
+}
+
+// calls found with each sorted leave of leave_size tiles drawn from
+// unseen[tile..], and the number of ways to draw it.
+fn enumerate_leaves<F: FnMut(&[u8], f64)>(
+    unseen: &[u8],
+    tile: usize,
+    leave_size: usize,
+    weight: f64,
+    pascal: &prob::Pascal,
+    leave: &mut Vec<u8>,
+    found: &mut F,
+) {
+    if leave.len() == leave_size {
+        found(leave, weight);
+        return;
+    }
+    if tile >= unseen.len() {
+        return;
+    }
+    let remaining = leave_size - leave.len();
+    for num in (0..=(unseen[tile] as usize).min(remaining)).rev() {
+        let original_len = leave.len();
+        leave.extend(std::iter::repeat_n(tile as u8, num));
+        enumerate_leaves(
+            unseen,
+            tile + 1,
+            leave_size,
+            weight * pascal.binom(unseen[tile] as usize, num) as f64,
+            pascal,
+            leave,
+            found,
+        );
+        leave.truncate(original_len);
+    }
+}
+
+// whether the play is within the tolerance of the best play with this rack.
+fn play_is_plausible<N: kwg::Node, L: kwg::Node>(
+    move_generator: &mut movegen::KurniaMoveGenerator,
+    params: &InferenceParams<'_, N, L>,
+    rack: &[u8],
+) -> bool {
+    let gen_moves_params = movegen::GenMovesParams {
+        board_snapshot: params.board_snapshot,
+        rack,
+        max_gen: 1,
+        num_exchanges_by_this_player: params.num_exchanges_by_this_player,
+        always_include_pass: false,
+        dynamic_leaves: None,
+    };
+    move_generator.gen_moves_unfiltered(&gen_moves_params);
+    let Some(best) = move_generator.plays.first() else {
+        return false;
+    };
+    let best_equity = best.equity;
+
+    // generate only the observed play, to value it the same way.
+    let is_pass = matches!(params.play, movegen::Play::Exchange { .. });
+    move_generator.gen_moves_filtered(
+        &movegen::GenMovesParams {
+            always_include_pass: is_pass,
+            ..gen_moves_params
+        },
+        |down: bool, lane: i8, idx: i8, word: &[u8], _score: i32| {
+            matches!(params.play, movegen::Play::Place {
+                down: play_down,
+                lane: play_lane,
+                idx: play_idx,
+                word: play_word,
+                ..
+            } if *play_down == down && *play_lane == lane && *play_idx == idx && play_word[..] == *word)
+        },
+        |leave_value: i32| leave_value,
+        |_equity, play: &movegen::Play| match play {
+            movegen::Play::Place { .. } => !is_pass,
+            movegen::Play::Exchange { tiles } => is_pass && tiles.is_empty(),
+        },
+    );
+    match move_generator.plays.first() {
+        Some(observed) => {
+            best_equity.raw() as i64 - observed.equity.raw() as i64
+                <= params.equity_tolerance as i64
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::make_tiny_kwg;
+    use crate::{game_config, klv};
+
+    // an opening ZA that kept one tile: keeping S would have made ZAS, so it
+    // only survives a looser tolerance, and weights count the unseen copies.
+    #[test]
+    fn infers_leaves_from_opening_play() {
+        let game_config = game_config::make_english_game_config();
+        let alphabet = game_config.alphabet();
+        let kwg = make_tiny_kwg(&["ZA", "ZAS"]);
+        let klv = klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES);
+        let board_tiles = vec![0u8; 15 * 15];
+        let board_snapshot = movegen::BoardSnapshot {
+            board_tiles: &board_tiles,
+            game_config: &game_config,
+            kwg: &kwg,
+            klv: &klv,
+        };
+        let mut move_generator = movegen::KurniaMoveGenerator::new(&game_config);
+        move_generator.gen_moves_unfiltered(&movegen::GenMovesParams {
+            board_snapshot: &board_snapshot,
+            rack: &[1, 26],
+            max_gen: 1,
+            num_exchanges_by_this_player: 0,
+            always_include_pass: false,
+            dynamic_leaves: None,
+        });
+        let play = move_generator.plays[0].play.clone();
+        let mut unseen = (0..alphabet.len())
+            .map(|tile| alphabet.freq(tile))
+            .collect::<Vec<_>>();
+        unseen[1] -= 1;
+        unseen[26] -= 1;
+
+        let mut params = InferenceParams {
+            board_snapshot: &board_snapshot,
+            play: &play,
+            unseen: &unseen,
+            leave_size: 1,
+            num_exchanges_by_this_player: 0,
+            equity_tolerance: 0,
+            max_leaves: 100,
+        };
+        let inference = infer_leaves(&mut move_generator, &params).unwrap();
+        let weight_of = |inference: &LeaveInference, tile: u8| {
+            inference
+                .leaves()
+                .iter()
+                .find(|inferred_leave| inferred_leave.leave[..] == [tile])
+                .map(|inferred_leave| inferred_leave.weight)
+        };
+        assert_eq!(weight_of(&inference, 19), None);
+        assert_eq!(weight_of(&inference, 5), Some(12.0));
+        // a blank could make ZAS too, but for no more than ZA.
+        assert_eq!(weight_of(&inference, 0), Some(2.0));
+        assert_eq!(inference.leaves().len(), 25);
+        assert_eq!(inference.total_weight(), 98.0 - 4.0);
+
+        params.equity_tolerance = 5000;
+        let inference = infer_leaves(&mut move_generator, &params).unwrap();
+        assert_eq!(weight_of(&inference, 19), Some(4.0));
+        assert_eq!(inference.total_weight(), 98.0);
+        let mut rng = rand::rngs::ChaCha20Rng::seed_from_u64(1);
+        for _ in 0..10 {
+            assert_eq!(inference.sample(&mut rng).unwrap().len(), 1);
+        }
+
+        params.leave_size = 2;
+        params.max_leaves = 300;
+        assert!(infer_leaves(&mut move_generator, &params).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright (C) 2020-2026 Andy Kurnia.
 
-use super::{equity, error, game_config, movegen};
+use super::{endgame, equity, error, game_config, game_state, kwg, movegen, play_scorer};
+use rand::prelude::*;
 
 // note: only this representation uses -1i8 for blank-as-A (in "board" input
 // and "word" response for "action":"play"). everywhere else, use 0x81u8.
@@ -63,6 +64,18 @@ impl From<&movegen::Play> for JsonPlay {
     }
 }
 
+impl From<&endgame::PegMove> for JsonPlay {
+    #[inline(always)]
+    fn from(peg_move: &endgame::PegMove) -> Self {
+        match peg_move {
+            endgame::PegMove::Pass => Self::Exchange {
+                tiles: Box::new([]),
+            },
+            endgame::PegMove::Place(play) => play.into(),
+        }
+    }
+}
+
 impl From<&JsonPlay> for movegen::Play {
     #[inline(always)]
     fn from(play: &JsonPlay) -> Self {
@@ -100,6 +113,42 @@ impl From<&JsonPlay> for movegen::Play {
     }
 }
 
+// one word of a board audit (see play_scorer::PlayScorer::audit_board).
+// word: 1 for A, -1 for blank-as-A. introduced_by: index into the history.
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct JsonAuditedWord {
+    pub down: bool,
+    pub lane: i8,
+    pub idx: i8,
+    pub word: Box<[i8]>,
+    pub valid: bool,
+    pub introduced_by: Option<usize>,
+}
+
+impl From<&play_scorer::AuditedWord> for JsonAuditedWord {
+    #[inline(always)]
+    fn from(audited_word: &play_scorer::AuditedWord) -> Self {
+        Self {
+            down: audited_word.down,
+            lane: audited_word.lane,
+            idx: audited_word.idx,
+            word: audited_word
+                .word
+                .iter()
+                .map(|&x| {
+                    if x & 0x80 != 0 {
+                        -((x & !0x80) as i8)
+                    } else {
+                        x as i8
+                    }
+                })
+                .collect(),
+            valid: audited_word.valid,
+            introduced_by: audited_word.introduced_by,
+        }
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
 pub struct JsonPlayWithEquity {
     pub equity: f32,
@@ -127,6 +176,16 @@ impl From<&JsonPlayWithEquity> for movegen::ValuedMove {
     }
 }
 
+// the answer to an endgame question: the one-in-bag PEG result, or the solved
+// endgame's final margin and principal variation. Margins are scaled.
+pub enum EndgameAnswer {
+    Peg(endgame::PegResult),
+    Endgame {
+        margin: f32,
+        pv: Vec<(f32, movegen::Play)>,
+    },
+}
+
 pub struct Kibitzer {
     pub available_tally: Vec<u8>,
     pub board_tiles: Vec<u8>,
@@ -231,6 +290,97 @@ impl Kibitzer {
 
         Ok(())
     }
+
+    // the unseen tiles, in tile order.
+    pub fn unseen_tiles(&self) -> Vec<u8> {
+        (0u8..)
+            .zip(self.available_tally.iter())
+            .flat_map(|(tile, &count)| std::iter::repeat_n(tile, count as usize))
+            .collect()
+    }
+
+    // the position as a game state with the mover (player 0) to play: the
+    // unseen tiles are shuffled into the bag and each opponent draws a rack
+    // from it. scores are whole points in turn order, missing ones being 0.
+    pub fn make_game_state(
+        &self,
+        game_config: &game_config::GameConfig,
+        rack: &[u8],
+        scores: &[i32],
+        rng: &mut dyn Rng,
+    ) -> game_state::GameState {
+        let mut game_state = game_state::GameState::new(game_config);
+        game_state.board_tiles.copy_from_slice(&self.board_tiles);
+        game_state.bag.set_from_iter(self.unseen_tiles());
+        game_state.bag.shuffle(rng);
+        game_state.players[0].rack.extend_from_slice(rack);
+        for (player_idx, player) in game_state.players.iter_mut().enumerate().skip(1) {
+            game_state.bag.replenish(
+                &mut player.rack,
+                game_config.rack_size() as usize,
+                player_idx,
+            );
+        }
+        for (player, &score) in game_state.players.iter_mut().zip(scores.iter()) {
+            player.score = equity::scale_score(score);
+        }
+        game_state.turn = 0;
+        game_state
+    }
+
+    // solve the endgame for the mover, or the pre-endgame with one tile in the
+    // bag. scores are whole points in turn order, missing ones being 0; the
+    // solver is two-player.
+    pub fn solve_endgame<N: kwg::Node>(
+        &self,
+        move_generator: &mut movegen::KurniaMoveGenerator,
+        game_config: &game_config::GameConfig,
+        kwg: &kwg::Kwg<N>,
+        rack: &[u8],
+        scores: &[i32],
+    ) -> error::Returns<EndgameAnswer> {
+        if game_config.num_players() != 2 || scores.len() > 2 {
+            return_error!(format!(
+                "endgame needs two players, got {} with {} scores",
+                game_config.num_players(),
+                scores.len(),
+            ));
+        }
+        let score = |player_idx| scores.get(player_idx).copied().unwrap_or(0);
+        let unseen = self.unseen_tiles();
+        let rack_size = game_config.rack_size() as usize;
+        if unseen.len() > rack_size + 1 {
+            return_error!(format!(
+                "not endgame yet as there are {} unseen tiles",
+                unseen.len(),
+            ));
+        }
+        let smaller_kwg =
+            endgame::build_word_pruned_kwg(move_generator, game_config, kwg, &self.board_tiles)?;
+        let score_diff = equity::scale_score(score(0) - score(1)) as f32;
+        let mut egs =
+            endgame::EndgameSolver::<kwg::Node22, kwg::Node22>::new(game_config, &smaller_kwg);
+        if unseen.len() == rack_size + 1 {
+            let result = egs
+                .solve_peg_one_in_bag(
+                    0,
+                    &self.board_tiles,
+                    rack,
+                    &self.available_tally,
+                    score_diff,
+                )
+                .map_err(|e| error::new(format!("peg: {e}")))?;
+            return Ok(EndgameAnswer::Peg(result));
+        }
+        egs.init(&self.board_tiles, [rack, &unseen]);
+        let future_margin = egs.solve(0);
+        let mut pv = Vec::new();
+        egs.collect_pv(0, &mut pv);
+        Ok(EndgameAnswer::Endgame {
+            margin: score_diff + future_margin,
+            pv,
+        })
+    }
 }
 
 impl Default for Kibitzer {
@@ -19,22 +19,61 @@ pub struct DynamicLeavesRef<'a> {
     pub min_keep: usize,
 }
 
-pub struct Klv<L: kwg::Node> {
-    kwg: kwg::Kwg<L>,
+pub struct Klv<'a, L: kwg::Node> {
+    kwg: kwg::Kwg<'a, L>,
     counts: Box<[u32]>,
-    leaves: Box<[i32]>, // millipoints, converted from f32 at load time
+    leaves: Leaves<'a>,
+}
+
+enum Leaves<'a> {
+    // millipoints, converted from i16 or f32 at load time.
+    Converted(Box<[i32]>),
+    // the klv2 f32 bits in place, converted at lookup.
+    Borrowed(&'a [u32]),
 }
 
 // kwg::Node22
 pub static EMPTY_KLV_BYTES: &[u8] = b"\x01\x00\x00\x00\x00\x00\x40\x00\x00\x00\x00\x00";
 
-impl<L: kwg::Node> Klv<L> {
+#[inline(always)]
+fn f32_leave(raw: f32) -> i32 {
+    (raw * equity::SCALE as f32).round() as i32
+}
+
+impl<'a, L: kwg::Node> Klv<'a, L> {
     pub fn from_bytes_alloc(buf: &[u8]) -> Self {
         let mut r = 0;
         let kwg_bytes_len = (kwg::read_le_u32(buf, r) as usize) * 4;
         r += 4;
         let kwg = kwg::Kwg::from_bytes_alloc(&buf[r..r + kwg_bytes_len]);
         r += kwg_bytes_len;
+        Self::with_kwg_alloc(kwg, buf, r)
+    }
+
+    // Borrows the kwg and the klv2 leaves in place where possible (see
+    // kwg::Kwg::from_bytes), otherwise copies them.
+    pub fn from_bytes(buf: &'a [u8]) -> Self {
+        let mut r = 0;
+        let kwg_bytes_len = (kwg::read_le_u32(buf, r) as usize) * 4;
+        r += 4;
+        let kwg = kwg::Kwg::from_bytes(&buf[r..r + kwg_bytes_len]);
+        r += kwg_bytes_len;
+        let lv_len = kwg::read_le_u32(buf, r) as usize;
+        if let Some(leaves) = buf
+            .get(r + 4..r + 4 + 4 * lv_len)
+            .and_then(kwg::borrow_le_u32s)
+        {
+            let counts = kwg.count_words_alloc();
+            return Klv {
+                kwg,
+                counts,
+                leaves: Leaves::Borrowed(leaves),
+            };
+        }
+        Self::with_kwg_alloc(kwg, buf, r)
+    }
+
+    fn with_kwg_alloc(kwg: kwg::Kwg<'a, L>, buf: &[u8], mut r: usize) -> Self {
         let lv_len = kwg::read_le_u32(buf, r);
         r += 4;
         let mut elts = Vec::with_capacity(lv_len as usize);
@@ -47,8 +86,7 @@ impl<L: kwg::Node> Klv<L> {
         } else {
             // klv2 uses f32
             for _ in 0..lv_len {
-                let raw = f32::from_bits(kwg::read_le_u32(buf, r));
-                elts.push((raw * equity::SCALE as f32).round() as i32);
+                elts.push(f32_leave(f32::from_bits(kwg::read_le_u32(buf, r))));
                 r += 4;
             }
         }
@@ -56,13 +94,21 @@ impl<L: kwg::Node> Klv<L> {
         Klv {
             kwg,
             counts,
-            leaves: elts.into_boxed_slice(),
+            leaves: Leaves::Converted(elts.into_boxed_slice()),
         }
     }
 
+    #[inline(always)]
+    pub fn is_borrowed(&self) -> bool {
+        self.kwg.is_borrowed() && matches!(self.leaves, Leaves::Borrowed(_))
+    }
+
     #[inline(always)]
     pub fn leave(&self, leave_idx: u32) -> i32 {
-        self.leaves[leave_idx as usize]
+        match &self.leaves {
+            Leaves::Converted(leaves) => leaves[leave_idx as usize],
+            Leaves::Borrowed(leaves) => f32_leave(f32::from_bits(leaves[leave_idx as usize])),
+        }
     }
 
     #[inline(always)]
@@ -189,7 +235,7 @@ impl MultiLeaves {
             self.num_playeds.resize(place_value as usize, 0xff);
             self.num_playeds[0] = num_tiles_on_rack;
             struct Env<'a, L: kwg::Node> {
-                klv: &'a Klv<L>,
+                klv: &'a Klv<'a, L>,
                 unique_tiles: &'a [u8],
                 digits: &'a mut [MultiLeavesDigit],
                 leave_values: &'a mut [i32],
@@ -314,7 +360,7 @@ impl MultiLeaves {
         best_leave_values.clear();
         best_leave_values.resize(num_tiles_on_rack as usize + 1, i32::MIN);
         struct Env<'a, AdjustLeaveValue, L: kwg::Node> {
-            klv: &'a Klv<L>,
+            klv: &'a Klv<'a, L>,
             rack_tally: &'a mut [u8],
             kept_tally: &'a mut [u8],
             best_leave_values: &'a mut [i32],
@@ -531,7 +577,7 @@ impl MultiLeaves {
         exchange_buffer.clear();
         struct ExchangeEnv<'a, FoundExchangeMove: FnMut(&[u8], i32), L: kwg::Node> {
             rack_tally_len: u8,
-            klv: &'a Klv<L>,
+            klv: &'a Klv<'a, L>,
             found_exchange_move: FoundExchangeMove,
             rack_tally: &'a mut [u8],
             exchange_buffer: &'a mut Vec<u8>,
@@ -719,4 +765,42 @@ mod tests {
             census::UNPLAYABLE,
         );
     }
+
+    // a klv2 borrowed in place values leaves the same as one converted at load.
+    #[test]
+    fn from_bytes_borrows_klv2() {
+        use crate::{bites, build};
+        let words: Vec<bites::Bites> = [&[1u8][..], &[1, 1], &[1, 2], &[2]]
+            .iter()
+            .map(|&word| word.into())
+            .collect();
+        let kwg_bytes = build::build(
+            build::BuildContent::DawgOnly,
+            build::BuildLayout::Wolges,
+            &words,
+        )
+        .unwrap();
+        let raw_leaves = [1.5f32, -2.25, 0.0004, 3.0];
+        let mut words32 = vec![(kwg_bytes.len() / 4) as u32];
+        words32.extend(
+            kwg_bytes
+                .chunks_exact(4)
+                .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap())),
+        );
+        words32.push(raw_leaves.len() as u32);
+        words32.extend(raw_leaves.iter().map(|raw| raw.to_bits()));
+        let aligned = unsafe { words32.align_to::<u8>() }.1;
+        let owned = Klv::<kwg::Node22>::from_bytes_alloc(aligned);
+        let borrowed = Klv::<kwg::Node22>::from_bytes(aligned);
+        assert!(!owned.is_borrowed());
+        assert_eq!(borrowed.is_borrowed(), cfg!(target_endian = "little"));
+        for (leave_idx, want) in [1500, -2250, 0, 3000].into_iter().enumerate() {
+            assert_eq!(owned.leave(leave_idx as u32), want);
+            assert_eq!(borrowed.leave(leave_idx as u32), want);
+        }
+        // AA is the second word and AB the third, and BB is not a leave.
+        assert_eq!(borrowed.leave_value_from_tally(&[0, 2]), -2250);
+        assert_eq!(borrowed.leave_value_from_tally(&[0, 1, 1]), 0);
+        assert_eq!(borrowed.leave_value_from_tally(&[0, 0, 2]), 0);
+    }
 }
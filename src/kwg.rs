@@ -1,7 +1,13 @@
 // Copyright (C) 2020-2026 Andy Kurnia.
 
-pub trait Node: Copy {
+use super::{alphabet, error};
+
+pub trait Node: Copy + 'static {
     fn new(v: u32) -> Self;
+    // The same nodes in place, for Kwg::from_bytes, if Self is just the u32.
+    fn from_u32s(_v: &[u32]) -> Option<&[Self]> {
+        None
+    }
     fn tile(&self) -> u8;
     fn accepts(&self) -> bool;
     fn is_end(&self) -> bool;
@@ -9,6 +15,7 @@ pub trait Node: Copy {
 }
 
 #[derive(Clone, Copy)]
+#[repr(transparent)]
 pub struct Node22(u32);
 
 impl Node for Node22 {
@@ -17,6 +24,12 @@ impl Node for Node22 {
         Self(v)
     }
 
+    #[inline(always)]
+    fn from_u32s(v: &[u32]) -> Option<&[Self]> {
+        // sound because Self is repr(transparent) over u32.
+        Some(unsafe { std::slice::from_raw_parts(v.as_ptr() as *const Self, v.len()) })
+    }
+
     #[inline(always)]
     fn tile(&self) -> u8 {
         (self.0 >> 24) as u8
@@ -39,6 +52,7 @@ impl Node for Node22 {
 }
 
 #[derive(Clone, Copy)]
+#[repr(transparent)]
 pub struct Node24(u32);
 
 impl Node for Node24 {
@@ -47,6 +61,12 @@ impl Node for Node24 {
         Self(v)
     }
 
+    #[inline(always)]
+    fn from_u32s(v: &[u32]) -> Option<&[Self]> {
+        // sound because Self is repr(transparent) over u32.
+        Some(unsafe { std::slice::from_raw_parts(v.as_ptr() as *const Self, v.len()) })
+    }
+
     #[inline(always)]
     fn tile(&self) -> u8 {
         (self.0 & 0x3f) as u8
@@ -81,7 +101,78 @@ pub fn read_le_u32(bytes: &[u8], p: usize) -> u32 {
         | ((bytes[p + 3] as u32) << 24)
 }
 
-pub struct Kwg<N: Node>(pub Box<[N]>);
+// The nodes are either owned or borrowed in place from the file's bytes, such
+// as a memory-mapped file or a static include, so processes loading the same
+// lexicon can share its pages.
+pub struct Kwg<'a, N: Node>(pub std::borrow::Cow<'a, [N]>);
+
+// Bytes aligned for borrowing, such as
+// static KWG: &Aligned<[u8]> = &Aligned(*include_bytes!("CSW24.kwg"));
+#[repr(C, align(4))]
+pub struct Aligned<B: ?Sized>(pub B);
+
+// The little-endian u32s in buf, in place. This needs a little-endian target
+// and a 4-byte aligned buf.
+pub fn borrow_le_u32s(buf: &[u8]) -> Option<&[u32]> {
+    if cfg!(target_endian = "little") && buf.len().is_multiple_of(4) {
+        // align_to only yields a nonempty middle when the u32s line up.
+        let (head, u32s, tail) = unsafe { buf.align_to::<u32>() };
+        if head.is_empty() && tail.is_empty() {
+            return Some(u32s);
+        }
+    }
+    None
+}
+
+// A file's bytes, read into a 4-byte aligned buffer so Kwg::from_bytes and
+// klv::Klv::from_bytes can borrow them in place.
+pub struct AlignedBytes {
+    words: Box<[u32]>,
+    len: usize,
+}
+
+impl AlignedBytes {
+    pub fn read(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        Self::read_from(&mut std::fs::File::open(path)?)
+    }
+
+    pub fn read_from(reader: &mut dyn std::io::Read) -> std::io::Result<Self> {
+        let mut words = Vec::<u32>::new();
+        let mut len = 0;
+        loop {
+            if len == words.len() * 4 {
+                words.resize((words.len() * 2).max(1 << 14), 0);
+            }
+            let (_, bytes, _) = unsafe { words.align_to_mut::<u8>() };
+            match reader.read(&mut bytes[len..]) {
+                Ok(0) => break,
+                Ok(n) => len += n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        words.truncate(len.div_ceil(4));
+        Ok(Self {
+            words: words.into_boxed_slice(),
+            len,
+        })
+    }
+
+    // The bytes for the rest of the process, for lexicons loaded once.
+    pub fn leak(self) -> &'static [u8] {
+        let len = self.len;
+        &unsafe { Box::leak(self.words).align_to::<u8>() }.1[..len]
+    }
+}
+
+impl std::ops::Deref for AlignedBytes {
+    type Target = [u8];
+
+    #[inline(always)]
+    fn deref(&self) -> &[u8] {
+        &unsafe { self.words.align_to::<u8>() }.1[..self.len]
+    }
+}
 
 // Precomputed bitmasks of child tiles for each sibling group position.
 // letter_bits[i] = bitmask of ALL tiles from node i through end of group.
@@ -92,10 +183,100 @@ pub struct LetterBits {
     pub accepting_bits: Box<[u64]>,
 }
 
+// One position (or run of positions) of a Pattern. Tile sets are bitmasks,
+// bit t for tile t.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PatternElement {
+    // exactly this tile, given (such as on the board) rather than from the rack.
+    Tile(u8),
+    // any one tile of the set, such as a wildcard or a letter class.
+    OneOf(u64),
+    // from min to max tiles, each from the set.
+    Gap { tiles: u64, min: u8, max: u8 },
+}
+
+// A compiled word query for Kwg::find_pattern.
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    pub elements: Vec<PatternElement>,
+    pub min_len: usize,
+    pub max_len: usize,
+    // if set, the tiles not given as PatternElement::Tile must come from this
+    // tally, index 0 being blanks.
+    pub rack: Option<Vec<u8>>,
+}
+
+impl Pattern {
+    pub fn new(elements: Vec<PatternElement>) -> Self {
+        Self {
+            elements,
+            min_len: 0,
+            max_len: usize::MAX,
+            rack: None,
+        }
+    }
+
+    // Letters are given tiles, ? or . is any one tile, [AEIOU] is any one of
+    // those tiles ([^AEIOU] any other), and * is any run of tiles. Multi-letter
+    // tile labels such as [CH] are read as tiles first.
+    pub fn parse(alphabet: &alphabet::Alphabet, s: &str) -> error::Returns<Self> {
+        let alphabet_reader = alphabet::AlphabetReader::new_for_words(alphabet);
+        let all_tiles = ((1u128 << alphabet.len()) - 1) as u64 & !1;
+        let sb = s.as_bytes();
+        let mut elements = Vec::new();
+        let mut ix = 0;
+        while ix < sb.len() {
+            if let Some((tile, end_ix)) = alphabet_reader.next_tile(sb, ix) {
+                elements.push(PatternElement::Tile(tile));
+                ix = end_ix;
+                continue;
+            }
+            match sb[ix] {
+                b'?' | b'.' => elements.push(PatternElement::OneOf(all_tiles)),
+                b'*' => elements.push(PatternElement::Gap {
+                    tiles: all_tiles,
+                    min: 0,
+                    max: u8::MAX,
+                }),
+                b'[' => {
+                    ix += 1;
+                    let negated = sb.get(ix) == Some(&b'^');
+                    ix += negated as usize;
+                    let mut tiles = 0u64;
+                    loop {
+                        if ix >= sb.len() {
+                            return_error!(format!("unterminated [ in {s:?}"));
+                        }
+                        if sb[ix] == b']' {
+                            break;
+                        }
+                        if let Some((tile, end_ix)) = alphabet_reader.next_tile(sb, ix) {
+                            tiles |= 1 << tile;
+                            ix = end_ix;
+                        } else {
+                            return_error!(format!("invalid tile in [] at {ix} in {s:?}"));
+                        }
+                    }
+                    elements.push(PatternElement::OneOf(if negated {
+                        all_tiles & !tiles
+                    } else {
+                        tiles
+                    }));
+                }
+                _ => {
+                    return_error!(format!("invalid pattern at {ix} in {s:?}"));
+                }
+            }
+            ix += 1;
+        }
+        Ok(Self::new(elements))
+    }
+}
+
 // kwg::Node22
 pub static EMPTY_KWG_BYTES: &[u8] = b"\x00\x00\x40\x00\x00\x00\x40\x00";
 
-impl<N: Node> std::ops::Index<i32> for Kwg<N> {
+impl<N: Node> std::ops::Index<i32> for Kwg<'_, N> {
     type Output = N;
 
     #[inline(always)]
@@ -104,7 +285,7 @@ impl<N: Node> std::ops::Index<i32> for Kwg<N> {
     }
 }
 
-impl<N: Node> Kwg<N> {
+impl<'a, N: Node> Kwg<'a, N> {
     pub fn from_bytes_alloc(buf: &[u8]) -> Self {
         let kwg_len = buf.len() / 4;
         let mut elts = Vec::with_capacity(kwg_len);
@@ -113,7 +294,20 @@ impl<N: Node> Kwg<N> {
             elts.push(N::new(read_le_u32(buf, r)));
             r += 4;
         }
-        Kwg(elts.into_boxed_slice())
+        Kwg(elts.into())
+    }
+
+    // Borrows the nodes in place where possible, otherwise copies them.
+    pub fn from_bytes(buf: &'a [u8]) -> Self {
+        match borrow_le_u32s(buf).and_then(N::from_u32s) {
+            Some(nodes) => Kwg(nodes.into()),
+            None => Kwg::from_bytes_alloc(buf),
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self.0, std::borrow::Cow::Borrowed(_))
     }
 
     #[inline(always)]
@@ -200,7 +394,7 @@ impl<N: Node> Kwg<N> {
     }
 
     pub fn count_dawg_words_alloc(&self) -> Box<[u32]> {
-        fn max_from<N: Node>(nodes: &Kwg<N>, vis: &mut [u8], mut p: i32) -> i32 {
+        fn max_from<N: Node>(nodes: &Kwg<'_, N>, vis: &mut [u8], mut p: i32) -> i32 {
             let mut ret = 0;
             loop {
                 let p_byte_index = (p as usize) / 8;
@@ -388,4 +582,269 @@ impl<N: Node> Kwg<N> {
         }
         answer
     }
+
+    // Words of the DAWG matching the pattern, in order, as tiles. With a rack,
+    // tiles played as blanks are 0x80|tile, and each word is listed once.
+    pub fn find_pattern(&self, pattern: &Pattern) -> Vec<Box<[u8]>> {
+        // min_rest[i] = the fewest tiles elements[i..] can match.
+        let mut min_rest = vec![0; pattern.elements.len() + 1];
+        for (i, element) in pattern.elements.iter().enumerate().rev() {
+            min_rest[i] = min_rest[i + 1]
+                + match element {
+                    PatternElement::Gap { min, .. } => *min as usize,
+                    _ => 1,
+                };
+        }
+        let mut search = PatternSearch {
+            kwg: self,
+            pattern,
+            min_rest,
+            rack: pattern.rack.clone(),
+            word: Vec::new(),
+            found: Vec::new(),
+        };
+        search.walk(self[0].arc_index(), false, 0, 0);
+        // a word may match more than one way, with different blanks.
+        let mut found = search.found;
+        found.sort_unstable_by(|a, b| a.iter().map(|&t| t & 0x7f).cmp(b.iter().map(|&t| t & 0x7f)));
+        found.dedup_by(|a, b| a.iter().map(|&t| t & 0x7f).eq(b.iter().map(|&t| t & 0x7f)));
+        found
+    }
+}
+
+struct PatternSearch<'a, N: Node> {
+    kwg: &'a Kwg<'a, N>,
+    pattern: &'a Pattern,
+    min_rest: Vec<usize>,
+    rack: Option<Vec<u8>>,
+    word: Vec<u8>,
+    found: Vec<Box<[u8]>>,
+}
+
+impl<N: Node> PatternSearch<'_, N> {
+    // p is the first child of the word so far, which has matched
+    // elements[..idx] and gap_len tiles of elements[idx].
+    fn walk(&mut self, p: i32, accepts: bool, idx: usize, gap_len: u8) {
+        let elements = &self.pattern.elements[..];
+        let len = self.word.len();
+        if accepts && len >= self.pattern.min_len && self.can_end(idx, gap_len) {
+            self.found.push(self.word[..].into());
+        }
+        if p <= 0 || len >= self.pattern.max_len {
+            return;
+        }
+        // try each element the next tile may match, skipping satisfied gaps.
+        let mut idx = idx;
+        let mut gap_len = gap_len;
+        while idx < elements.len() {
+            let rest = self.min_rest[idx + 1]
+                + match elements[idx] {
+                    PatternElement::Gap { min, .. } => {
+                        (min as usize).saturating_sub(gap_len as usize + 1)
+                    }
+                    _ => 0,
+                };
+            let fits = len + 1 + rest <= self.pattern.max_len;
+            let (tiles, given, next_idx, next_gap_len, can_skip) = match elements[idx] {
+                PatternElement::Tile(tile) => (1u64 << tile, true, idx + 1, 0, false),
+                PatternElement::OneOf(tiles) => (tiles, false, idx + 1, 0, false),
+                PatternElement::Gap { tiles, min, max } => {
+                    if gap_len < max {
+                        (tiles, false, idx, gap_len + 1, gap_len >= min)
+                    } else {
+                        (0, false, idx, gap_len, true)
+                    }
+                }
+            };
+            if tiles != 0 && fits {
+                self.walk_tiles(p, tiles, given, next_idx, next_gap_len);
+            }
+            if !can_skip {
+                break;
+            }
+            idx += 1;
+            gap_len = 0;
+        }
+    }
+
+    fn walk_tiles(&mut self, mut p: i32, tiles: u64, given: bool, idx: usize, gap_len: u8) {
+        loop {
+            let node = self.kwg[p];
+            let tile = node.tile();
+            if tiles & (1 << tile) != 0 {
+                match (&mut self.rack, given) {
+                    (Some(rack), false) => {
+                        let from = if rack[tile as usize] > 0 {
+                            tile
+                        } else if rack[0] > 0 {
+                            0
+                        } else {
+                            0xff
+                        };
+                        if from != 0xff {
+                            rack[from as usize] -= 1;
+                            self.word.push(if from == 0 { tile | 0x80 } else { tile });
+                            self.walk(node.arc_index(), node.accepts(), idx, gap_len);
+                            self.word.pop();
+                            if let Some(rack) = &mut self.rack {
+                                rack[from as usize] += 1;
+                            }
+                        }
+                    }
+                    _ => {
+                        self.word.push(tile);
+                        self.walk(node.arc_index(), node.accepts(), idx, gap_len);
+                        self.word.pop();
+                    }
+                }
+            }
+            if node.is_end() {
+                break;
+            }
+            p += 1;
+        }
+    }
+
+    // whether elements[idx..] can match nothing more.
+    fn can_end(&self, idx: usize, gap_len: u8) -> bool {
+        let elements = &self.pattern.elements;
+        idx == elements.len()
+            || (matches!(elements[idx], PatternElement::Gap { min, .. } if gap_len >= min)
+                && self.min_rest[idx + 1] == 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{make_tiny_kwg, make_tiny_kwg_bytes};
+
+    fn to_strings(found: &[Box<[u8]>]) -> Vec<String> {
+        found
+            .iter()
+            .map(|word| {
+                word.iter()
+                    .map(|&t| {
+                        if t & 0x80 != 0 {
+                            (b'a' + (t & 0x7f) - 1) as char
+                        } else {
+                            (b'A' + t - 1) as char
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn find_pattern_matches_classes_gaps_and_racks() {
+        let alphabet = alphabet::make_english_alphabet();
+        let kwg = make_tiny_kwg(&[
+            "ACT", "CAT", "CATS", "COAT", "CUT", "EQUIP", "QUIT", "QUITE", "SCAT",
+        ]);
+        let find = |s: &str| to_strings(&kwg.find_pattern(&Pattern::parse(&alphabet, s).unwrap()));
+        assert_eq!(find("C?T"), ["CAT", "CUT"]);
+        assert_eq!(find("C.T*"), ["CAT", "CATS", "CUT"]);
+        assert_eq!(find("[AEIOU]*"), ["ACT", "EQUIP"]);
+        assert_eq!(find("[^C]*T"), ["ACT", "QUIT", "SCAT"]);
+        // each word once, though *A* matches CAT two ways.
+        assert_eq!(find("*A*"), ["ACT", "CAT", "CATS", "COAT", "SCAT"]);
+
+        let mut pattern = Pattern::parse(&alphabet, "*QU*").unwrap();
+        pattern.min_len = 5;
+        pattern.max_len = 5;
+        assert_eq!(to_strings(&kwg.find_pattern(&pattern)), ["EQUIP", "QUITE"]);
+
+        // two tiles then T.
+        let all_tiles = ((1u64 << alphabet.len()) - 1) & !1;
+        let pattern = Pattern::new(vec![
+            PatternElement::Gap {
+                tiles: all_tiles,
+                min: 2,
+                max: 2,
+            },
+            PatternElement::Tile(20),
+        ]);
+        assert_eq!(
+            to_strings(&kwg.find_pattern(&pattern)),
+            ["ACT", "CAT", "CUT"]
+        );
+
+        // ACT plus a blank, which shows in lowercase.
+        let mut pattern = Pattern::parse(&alphabet, "*").unwrap();
+        pattern.rack = Some({
+            let mut rack = vec![0; alphabet.len() as usize];
+            for tile in [0, 1, 3, 20] {
+                rack[tile] += 1;
+            }
+            rack
+        });
+        pattern.min_len = 4;
+        assert_eq!(
+            to_strings(&kwg.find_pattern(&pattern)),
+            ["CATs", "CoAT", "sCAT"]
+        );
+        // given tiles do not come from the rack.
+        pattern.elements = Pattern::parse(&alphabet, "C*").unwrap().elements;
+        pattern.rack.as_mut().unwrap()[3] = 0;
+        assert_eq!(to_strings(&kwg.find_pattern(&pattern)), ["CATs", "CoAT"]);
+
+        assert!(Pattern::parse(&alphabet, "[AB").is_err());
+        assert!(Pattern::parse(&alphabet, "C#").is_err());
+    }
+
+    // bytes read from anywhere are aligned, so they are borrowed.
+    #[test]
+    fn aligned_bytes_are_borrowed() {
+        let bytes = (0..40000u32)
+            .flat_map(|i| (i | 0x400000).to_le_bytes())
+            .chain([1, 2])
+            .collect::<Vec<u8>>();
+        let aligned = AlignedBytes::read_from(&mut &bytes[..]).unwrap();
+        assert_eq!(&aligned[..], &bytes[..]);
+        let aligned = AlignedBytes::read_from(&mut &bytes[..bytes.len() - 2]).unwrap();
+        assert_eq!(
+            Kwg::<Node22>::from_bytes(&aligned).is_borrowed(),
+            cfg!(target_endian = "little")
+        );
+        assert_eq!(aligned.leak().len(), bytes.len() - 2);
+    }
+
+    // the same nodes in place when the bytes line up, copied otherwise.
+    #[test]
+    fn from_bytes_borrows_aligned_bytes() {
+        let bytes = make_tiny_kwg_bytes(&["CAT", "CATS", "SCAT"]);
+        let nodes = bytes
+            .chunks_exact(4)
+            .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        let aligned = unsafe { nodes.align_to::<u8>() }.1;
+        let mut shifted = vec![0u8; bytes.len() + 1];
+        shifted[1..].copy_from_slice(&bytes);
+        let owned = Kwg::<Node22>::from_bytes_alloc(&bytes);
+        let borrowed = Kwg::<Node22>::from_bytes(aligned);
+        let copied = Kwg::<Node22>::from_bytes(&shifted[1..]);
+        assert!(!owned.is_borrowed());
+        assert_eq!(borrowed.is_borrowed(), cfg!(target_endian = "little"));
+        assert!(!copied.is_borrowed());
+        static EMPTY: &Aligned<[u8]> = &Aligned(*b"\x00\x00\x40\x00\x00\x00\x40\x00");
+        assert_eq!(
+            Kwg::<Node22>::from_bytes(&EMPTY.0).is_borrowed(),
+            cfg!(target_endian = "little")
+        );
+        let pattern = Pattern::parse(&alphabet::make_english_alphabet(), "*AT*").unwrap();
+        for kwg in [&borrowed, &copied] {
+            assert_eq!(kwg.0.len(), owned.0.len());
+            assert!((0..owned.0.len() as i32).all(|p| {
+                kwg[p].tile() == owned[p].tile()
+                    && kwg[p].accepts() == owned[p].accepts()
+                    && kwg[p].is_end() == owned[p].is_end()
+                    && kwg[p].arc_index() == owned[p].arc_index()
+            }));
+            assert_eq!(
+                to_strings(&kwg.find_pattern(&pattern)),
+                ["CAT", "CATS", "SCAT"]
+            );
+        }
+    }
 }
@@ -0,0 +1,366 @@
+// Copyright (C) 2020-2026 Andy Kurnia.
+
+// word study lookups over a gaddawg kwg: the dawg half lists words in order,
+// and the gaddag half reads words backwards, which finds front hooks without
+// trying every letter. words are tiles, blanks in results are 0x80|tile.
+
+use super::kwg;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnagramMode {
+    // words using some of the rack.
+    Sub,
+    // words using exactly the rack.
+    Exact,
+    // words using all of the rack and more.
+    Super,
+}
+
+// the node reached by following tiles from p, or -1 (also for an empty kwg).
+#[inline(always)]
+fn seek_all<N: kwg::Node, I: Iterator<Item = u8>>(kwg: &kwg::Kwg<N>, mut p: i32, tiles: I) -> i32 {
+    for tile in tiles {
+        p = kwg.seek(p, tile & 0x7f);
+        if p <= 0 {
+            return -1;
+        }
+    }
+    p
+}
+
+// tiles of the children of p that complete a word, in order.
+fn accepting_children<N: kwg::Node>(kwg: &kwg::Kwg<N>, p: i32) -> Vec<u8> {
+    let mut ret = Vec::new();
+    if p >= 0 {
+        let mut p = kwg[p].arc_index();
+        if p > 0 {
+            loop {
+                let node = kwg[p];
+                if node.tile() != 0 && node.accepts() {
+                    ret.push(node.tile());
+                }
+                if node.is_end() {
+                    break;
+                }
+                p += 1;
+            }
+        }
+    }
+    ret
+}
+
+pub fn is_word<N: kwg::Node>(kwg: &kwg::Kwg<N>, word: &[u8]) -> bool {
+    !word.is_empty() && {
+        let p = seek_all(kwg, 0, word.iter().copied());
+        p > 0 && kwg[p].accepts()
+    }
+}
+
+// tiles t such that t followed by the word is a word.
+pub fn front_hooks<N: kwg::Node>(kwg: &kwg::Kwg<N>, word: &[u8]) -> Vec<u8> {
+    if word.is_empty() {
+        return Vec::new();
+    }
+    accepting_children(kwg, seek_all(kwg, 1, word.iter().rev().copied()))
+}
+
+// tiles t such that the word followed by t is a word.
+pub fn back_hooks<N: kwg::Node>(kwg: &kwg::Kwg<N>, word: &[u8]) -> Vec<u8> {
+    if word.is_empty() {
+        return Vec::new();
+    }
+    accepting_children(kwg, seek_all(kwg, 0, word.iter().copied()))
+}
+
+// indexes of the tiles whose removal still leaves a word.
+pub fn inner_hooks<N: kwg::Node>(kwg: &kwg::Kwg<N>, word: &[u8]) -> Vec<usize> {
+    (0..word.len())
+        .filter(|&i| {
+            word.len() > 1 && {
+                let p = seek_all(
+                    kwg,
+                    0,
+                    word[..i].iter().chain(word[i + 1..].iter()).copied(),
+                );
+                p > 0 && kwg[p].accepts()
+            }
+        })
+        .collect()
+}
+
+// words in order that can be made from the rack tally (index 0 being blanks),
+// as the mode requires. rack tiles are used before blanks, and in Super mode
+// the tiles beyond the rack are not marked.
+pub fn anagrams<N: kwg::Node>(
+    kwg: &kwg::Kwg<N>,
+    rack_tally: &[u8],
+    mode: AnagramMode,
+) -> Vec<Box<[u8]>> {
+    let mut anagrammer = Anagrammer {
+        kwg,
+        rack: rack_tally.into(),
+        num_unused: rack_tally.iter().map(|&count| count as usize).sum(),
+        mode,
+        word: Vec::new(),
+        found: Vec::new(),
+    };
+    anagrammer.walk(kwg[0].arc_index());
+    anagrammer.found
+}
+
+#[inline(always)]
+pub fn subanagrams<N: kwg::Node>(kwg: &kwg::Kwg<N>, rack_tally: &[u8]) -> Vec<Box<[u8]>> {
+    anagrams(kwg, rack_tally, AnagramMode::Sub)
+}
+
+#[inline(always)]
+pub fn superanagrams<N: kwg::Node>(kwg: &kwg::Kwg<N>, rack_tally: &[u8]) -> Vec<Box<[u8]>> {
+    anagrams(kwg, rack_tally, AnagramMode::Super)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffKind {
+    Added,
+    Removed,
+}
+
+// calls found in order with each word only in old (Removed) or only in new
+// (Added), walking both dawgs in lockstep so shared words cost one pass. the
+// kwgs need not use the same node layout, but must share an alphabet.
+pub fn diff<N: kwg::Node, M: kwg::Node, F: FnMut(DiffKind, &[u8])>(
+    old: &kwg::Kwg<N>,
+    new: &kwg::Kwg<M>,
+    mut found: F,
+) {
+    diff_at(
+        old,
+        new,
+        old[0].arc_index(),
+        new[0].arc_index(),
+        &mut Vec::new(),
+        &mut found,
+    );
+}
+
+// p_old and p_new are sibling lists, or 0 for none.
+fn diff_at<N: kwg::Node, M: kwg::Node, F: FnMut(DiffKind, &[u8])>(
+    old: &kwg::Kwg<N>,
+    new: &kwg::Kwg<M>,
+    mut p_old: i32,
+    mut p_new: i32,
+    word: &mut Vec<u8>,
+    found: &mut F,
+) {
+    while p_old > 0 || p_new > 0 {
+        let tile_old = if p_old > 0 {
+            old[p_old].tile()
+        } else {
+            u8::MAX
+        };
+        let tile_new = if p_new > 0 {
+            new[p_new].tile()
+        } else {
+            u8::MAX
+        };
+        let tile = tile_old.min(tile_new);
+        word.push(tile);
+        if tile_old == tile_new {
+            let (node_old, node_new) = (old[p_old], new[p_new]);
+            if node_old.accepts() != node_new.accepts() {
+                found(
+                    if node_new.accepts() {
+                        DiffKind::Added
+                    } else {
+                        DiffKind::Removed
+                    },
+                    word,
+                );
+            }
+            diff_at(
+                old,
+                new,
+                node_old.arc_index(),
+                node_new.arc_index(),
+                word,
+                found,
+            );
+        } else if tile_old < tile_new {
+            words_at(old, p_old, word, &mut |word| found(DiffKind::Removed, word));
+        } else {
+            words_at(new, p_new, word, &mut |word| found(DiffKind::Added, word));
+        }
+        word.pop();
+        if tile_old == tile {
+            p_old = if old[p_old].is_end() { 0 } else { p_old + 1 };
+        }
+        if tile_new == tile {
+            p_new = if new[p_new].is_end() { 0 } else { p_new + 1 };
+        }
+    }
+}
+
+// calls found with every word through node p, whose tile is already in word.
+fn words_at<N: kwg::Node, F: FnMut(&[u8])>(
+    kwg: &kwg::Kwg<N>,
+    p: i32,
+    word: &mut Vec<u8>,
+    found: &mut F,
+) {
+    let node = kwg[p];
+    if node.accepts() {
+        found(word);
+    }
+    let mut p = node.arc_index();
+    if p > 0 {
+        loop {
+            word.push(kwg[p].tile());
+            words_at(kwg, p, word, found);
+            word.pop();
+            if kwg[p].is_end() {
+                break;
+            }
+            p += 1;
+        }
+    }
+}
+
+struct Anagrammer<'a, N: kwg::Node> {
+    kwg: &'a kwg::Kwg<'a, N>,
+    rack: Box<[u8]>,
+    num_unused: usize,
+    mode: AnagramMode,
+    word: Vec<u8>,
+    found: Vec<Box<[u8]>>,
+}
+
+impl<N: kwg::Node> Anagrammer<'_, N> {
+    fn walk(&mut self, mut p: i32) {
+        if p <= 0 || (self.num_unused == 0 && self.mode != AnagramMode::Super) {
+            return;
+        }
+        loop {
+            let node = self.kwg[p];
+            let tile = node.tile();
+            let from = if self.rack.get(tile as usize).is_some_and(|&count| count > 0) {
+                Some(tile)
+            } else if self.rack.first().is_some_and(|&count| count > 0) {
+                Some(0)
+            } else {
+                None
+            };
+            if from.is_some() || self.mode == AnagramMode::Super {
+                if let Some(from) = from {
+                    self.rack[from as usize] -= 1;
+                    self.num_unused -= 1;
+                    self.word.push(if from == 0 { tile | 0x80 } else { tile });
+                } else {
+                    self.word.push(tile);
+                }
+                if node.accepts() && (self.mode == AnagramMode::Sub || self.num_unused == 0) {
+                    self.found.push(self.word[..].into());
+                }
+                self.walk(node.arc_index());
+                self.word.pop();
+                if let Some(from) = from {
+                    self.rack[from as usize] += 1;
+                    self.num_unused += 1;
+                }
+            }
+            if node.is_end() {
+                break;
+            }
+            p += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::make_tiny_kwg;
+
+    fn tiles(s: &str) -> Vec<u8> {
+        s.bytes()
+            .map(|b| {
+                if b.is_ascii_lowercase() {
+                    (b - b'a' + 1) | 0x80
+                } else {
+                    b - b'A' + 1
+                }
+            })
+            .collect()
+    }
+
+    fn tally(s: &str) -> Vec<u8> {
+        let mut ret = vec![0; 27];
+        for b in s.bytes() {
+            ret[if b == b'?' {
+                0
+            } else {
+                (b - b'A' + 1) as usize
+            }] += 1;
+        }
+        ret
+    }
+
+    #[test]
+    fn hooks() {
+        let kwg = make_tiny_kwg(&["AT", "CAT", "CATS", "CHAT", "COAT", "OAT", "SCAT", "TA"]);
+        assert!(is_word(&kwg, &tiles("CAT")));
+        assert!(!is_word(&kwg, &tiles("CA")));
+        assert!(!is_word(&kwg, &[]));
+        assert_eq!(front_hooks(&kwg, &tiles("AT")), tiles("CO"));
+        assert_eq!(front_hooks(&kwg, &tiles("CAT")), tiles("S"));
+        assert_eq!(back_hooks(&kwg, &tiles("CAT")), tiles("S"));
+        assert!(back_hooks(&kwg, &tiles("CATS")).is_empty());
+        assert!(front_hooks(&kwg, &tiles("ZZ")).is_empty());
+        // CHAT less H, COAT less C or O.
+        assert_eq!(inner_hooks(&kwg, &tiles("CHAT")), [1]);
+        assert_eq!(inner_hooks(&kwg, &tiles("COAT")), [0, 1]);
+        assert_eq!(inner_hooks(&kwg, &tiles("SCAT")), [0]);
+    }
+
+    #[test]
+    fn anagram_modes() {
+        let kwg = make_tiny_kwg(&["ACT", "AT", "CAT", "CATS", "SCAT", "TA", "TACO"]);
+        let exact = anagrams(&kwg, &tally("TAC"), AnagramMode::Exact);
+        assert_eq!(exact, [tiles("ACT").into(), tiles("CAT").into()]);
+        assert_eq!(
+            subanagrams(&kwg, &tally("TA?")),
+            [
+                tiles("AcT").into(),
+                tiles("AT").into(),
+                tiles("cAT").into(),
+                tiles("TA").into(),
+            ]
+        );
+        assert_eq!(
+            superanagrams(&kwg, &tally("CS")),
+            [tiles("CATS").into(), tiles("SCAT").into()]
+        );
+        assert_eq!(superanagrams(&kwg, &tally("O?")), [tiles("tACO").into()]);
+        assert!(anagrams(&kwg, &tally("Z"), AnagramMode::Exact).is_empty());
+    }
+
+    #[test]
+    fn diff_lists_added_and_removed_words() {
+        let old = make_tiny_kwg(&["AT", "CAT", "CATS", "DOG", "OAT"]);
+        let new = make_tiny_kwg(&["AT", "CAT", "CHAT", "COAT", "DOG", "DOGS", "ZA"]);
+        let mut changes = Vec::new();
+        diff(&old, &new, |kind, word| changes.push((kind, word.to_vec())));
+        assert_eq!(
+            changes,
+            [
+                (DiffKind::Removed, tiles("CATS")),
+                (DiffKind::Added, tiles("CHAT")),
+                (DiffKind::Added, tiles("COAT")),
+                (DiffKind::Added, tiles("DOGS")),
+                (DiffKind::Removed, tiles("OAT")),
+                (DiffKind::Added, tiles("ZA")),
+            ]
+        );
+        let mut num_changes = 0;
+        diff(&new, &new, |_, _| num_changes += 1);
+        assert_eq!(num_changes, 0);
+    }
+}
@@ -10,7 +10,7 @@ pub enum MacondoFormat {
 // Macondo project is at https://github.com/domino14/macondo/.
 // This function converts a KWG into a Macondo-compatible gaddag or dawg file.
 pub fn to_macondo<'a, N: kwg::Node>(
-    kwg: &'a kwg::Kwg<N>,
+    kwg: &'a kwg::Kwg<'a, N>,
     alphabet: &'a alphabet::Alphabet,
     lexicon_name: &'a str,
     build_format: MacondoFormat,
@@ -51,7 +51,7 @@ pub fn to_macondo<'a, N: kwg::Node>(
     }
 
     struct Env<'a, N: kwg::Node> {
-        kwg: &'a kwg::Kwg<N>,
+        kwg: &'a kwg::Kwg<'a, N>,
         unicode_sorted_tiles: &'a [(u8, &'a str)],
         tile_mapping: &'a [u8],
         node_indexes: &'a mut [u32],
@@ -205,7 +205,7 @@ fn str_to_windows_vec_u8(s: &str, v: &mut Vec<u8>) -> Option<()> {
 }
 
 pub fn to_lxd<'a, N: kwg::Node>(
-    kwg: &'a kwg::Kwg<N>,
+    kwg: &'a kwg::Kwg<'a, N>,
     alphabet: &'a alphabet::Alphabet,
     title_str_unicode: &'a str,
     date_str_unicode: &'a str,
@@ -230,7 +230,7 @@ pub fn to_lxd<'a, N: kwg::Node>(
 
     struct Env<'a, N: kwg::Node> {
         alphabet: &'a alphabet::Alphabet,
-        kwg: &'a kwg::Kwg<N>,
+        kwg: &'a kwg::Kwg<'a, N>,
         node_indexes: &'a mut [u32],
         word_counts: &'a mut [u32],
         nodes: &'a mut Vec<u32>,
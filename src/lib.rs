@@ -9,6 +9,7 @@ pub mod bites;
 pub mod bites_str;
 pub mod board_layout;
 pub mod build;
+pub mod bundle;
 pub mod census;
 pub mod display;
 pub mod endgame;
@@ -17,16 +18,22 @@ pub mod fash;
 pub mod game_config;
 pub mod game_state;
 pub mod game_timers;
+pub mod gcg;
+pub mod inference;
 pub mod kibitzer;
 pub mod klv;
 pub mod kwg;
+pub mod lexicon;
 pub mod lexport;
 pub mod matrix;
 pub mod move_filter;
 pub mod move_picker;
 pub mod movegen;
+pub mod multi_endgame;
 pub mod play_scorer;
 pub mod prob;
 pub mod simmer;
 pub mod stats;
+#[cfg(test)]
+mod test_util;
 pub mod win_pct;
@@ -6,7 +6,7 @@ use std::io::{BufWriter, Write};
 use std::sync::{Arc, Mutex};
 use wolges::{
     alphabet, bag, bites, build, display, equity, error, fash, game_config, game_state,
-    game_timers, klv, kwg, move_filter, move_picker, movegen, play_scorer, stats,
+    game_timers, gcg, klv, kwg, move_filter, move_picker, movegen, play_scorer, stats,
 };
 
 // opt-in endgame-position harvester (WOLGES_ENDGAME_HARVEST=<path>): a single
@@ -49,31 +49,76 @@ fn main() -> error::Returns<()> {
         Ok(path) => Some(Arc::new(Mutex::new(BufWriter::new(File::create(path)?)))),
         Err(_) => None,
     };
+    // opt-in game recorder (WOLGES_GCG_DIR=<dir>): every finished game is written
+    // to <dir>/game-<n>.gcg, so bot games can be opened in ordinary viewers.
+    let gcg_dir = std::env::var("WOLGES_GCG_DIR")
+        .ok()
+        .filter(|x| !x.is_empty());
+    if let Some(gcg_dir) = &gcg_dir {
+        std::fs::create_dir_all(gcg_dir)?;
+    }
+    // opt-in clock-planned sims (WOLGES_SIM_CLOCK=1): each sim decision is
+    // limited by a budget planned from the mover's game clock. Unset keeps the
+    // fixed-iteration sims.
+    let time_policy = std::env::var("WOLGES_SIM_CLOCK")
+        .ok()
+        .filter(|x| !x.is_empty())
+        .map(|_| game_timers::TimePolicy::default());
+
+    // opt-in rules file (WOLGES_RULES_FILE=<file.json>) in place of the
+    // built-in game config.
+    let rules_config = std::env::var("WOLGES_RULES_FILE")
+        .ok()
+        .filter(|x| !x.is_empty())
+        .map(|path| game_config::load_game_config_from_rules_file(&path))
+        .transpose()?;
 
     match 1 {
         1 => do_it(
-            &kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/CSW24.kwg")?),
-            &klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/CSW24.klv2")?),
-            &game_config::make_english_game_config(),
+            &kwg::Kwg::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/CSW24.kwg")?.leak(),
+            ),
+            &klv::Klv::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/CSW24.klv2")?.leak(),
+            ),
+            &rules_config.unwrap_or_else(game_config::make_english_game_config),
             harvest,
+            gcg_dir,
+            time_policy,
         ),
         2 => do_it(
-            &kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/CSW24.kad")?),
-            &klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/CSW24.klv2")?),
-            &game_config::make_jumbled_english_game_config(),
+            &kwg::Kwg::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/CSW24.kad")?.leak(),
+            ),
+            &klv::Klv::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/CSW24.klv2")?.leak(),
+            ),
+            &rules_config.unwrap_or_else(game_config::make_jumbled_english_game_config),
             harvest,
+            gcg_dir,
+            time_policy,
         ),
         3 => do_it(
-            &kwg::Kwg::<kwg::Node24>::from_bytes_alloc(&std::fs::read("lexbin/DSW25.kbwg")?),
+            &kwg::Kwg::<kwg::Node24>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/DSW25.kbwg")?.leak(),
+            ),
             &klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES),
-            &game_config::make_dutch_game_config(),
+            &rules_config.unwrap_or_else(game_config::make_dutch_game_config),
             harvest,
+            gcg_dir,
+            time_policy,
         ),
         4 => do_it(
-            &kwg::Kwg::<kwg::Node24>::from_bytes_alloc(&std::fs::read("lexbin/DSW25.kbwg")?),
-            &klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/DSW25.klv2")?),
-            &game_config::make_dutch_game_config(),
+            &kwg::Kwg::<kwg::Node24>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/DSW25.kbwg")?.leak(),
+            ),
+            &klv::Klv::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/DSW25.klv2")?.leak(),
+            ),
+            &rules_config.unwrap_or_else(game_config::make_dutch_game_config),
             harvest,
+            gcg_dir,
+            time_policy,
         ),
         _ => unimplemented!(),
     }
@@ -84,6 +129,8 @@ fn do_it<N: kwg::Node + Sync>(
     klv: &klv::Klv<kwg::Node22>,
     game_config: &game_config::GameConfig,
     harvest: Option<HarvestWriter>,
+    gcg_dir: Option<String>,
+    time_policy: Option<game_timers::TimePolicy>,
 ) -> error::Returns<()> {
     let mut fen_parser =
         display::BoardFenParser::new(game_config.alphabet(), game_config.board_layout());
@@ -115,7 +162,7 @@ fn do_it<N: kwg::Node + Sync>(
     //let mut rng = rand::rngs::ChaCha20Rng::try_from_rng(&mut rand::rngs::SysRng)?;
     // "the seed is an array of 32 bytes".len() == 32.
     let mut rng = rand::rngs::ChaCha20Rng::from_seed(*b"Wolges Copyright (C) Andy Kurnia");
-    let mut timers = game_timers::GameTimers::new(game_config.num_players());
+    let mut timers = game_timers::GameTimers::new(game_config);
     if false {
         // https://discord.com/channels/741321677828522035/1157118170398724176/1193946371129094154
         let fen_str = "ZONULE1B2APAID/1KY2RHANJA4/GAM4R2HUI2/7G6D/6FECIT3O/6AE1TOWIES/6I7E/1EnGUARD6D/NAOI2W8/6AT7/5PYE7/5L1L7/2COVE1L7/5X1E7/7N7";
@@ -215,14 +262,39 @@ fn do_it<N: kwg::Node + Sync>(
     }
     let mut final_scores = vec![0i32; game_state.players.len()];
     let mut display_scores = vec![0i32; game_state.players.len()];
+    let nicknames = (1..=game_state.players.len())
+        .map(|i| format!("p{i}"))
+        .collect::<Vec<_>>();
+    let nicknames = nicknames.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+    let mut num_games = 0u64;
     loop {
         game_state.reset_and_draw_tiles_double_ended(game_config, &mut rng);
+        let mut gcg_writer = gcg_dir.as_ref().map(|_| gcg::GcgWriter::new(&nicknames));
         final_scores.iter_mut().for_each(|s| *s = 0);
         //timers.reset_to(25 * 60 * 1000);
         timers.reset_to(15 * 1000);
+        let mut time_forfeit = None;
 
         loop {
             timers.set_turn(game_state.turn as i8);
+            // set_turn has charged the turn that just ended; any clock now
+            // past the forfeit threshold ends the game.
+            if let Some(i) = timers
+                .clocks_ms
+                .iter()
+                .position(|&clock_ms| game_config.is_time_forfeit(clock_ms))
+            {
+                println!("Player {} forfeited on time", i + 1);
+                for (final_score, player) in final_scores.iter_mut().zip(game_state.players.iter())
+                {
+                    *final_score = player.score;
+                }
+                if let Some(gcg_writer) = &mut gcg_writer {
+                    gcg_writer.record_time_forfeit(i as u8);
+                }
+                time_forfeit = Some(i);
+                break;
+            }
             display::print_game_state(game_config, &game_state, Some(&timers));
 
             if false {
@@ -433,6 +505,11 @@ fn do_it<N: kwg::Node + Sync>(
                 // not required for now.
                 move_generator.reset_for_another_kwg();
             }
+            if let move_picker::MovePicker::Simmer(simmer) = move_picker {
+                simmer.set_time_budget(time_policy.map(|time_policy| {
+                    time_policy.move_budget(&timers, game_state.turn as usize, game_state.bag.len())
+                }));
+            }
             move_picker.pick_a_move(
                 filtered_movegen,
                 &mut move_generator,
@@ -443,10 +520,22 @@ fn do_it<N: kwg::Node + Sync>(
             let plays = &mut move_generator.plays;
             let play = &plays[0].play; // assume at least there's always Pass
             println!("Playing: {}", play.fmt(board_snapshot));
+            if let Some(gcg_writer) = &mut gcg_writer {
+                gcg_writer.record_play(
+                    game_config,
+                    game_state.turn,
+                    &game_state.current_player().rack,
+                    play,
+                );
+            }
 
             game_state.play(game_config, &mut rng, play)?;
 
-            match game_state.check_game_ended(game_config, &mut final_scores) {
+            let end = game_state.check_game_ended(game_config, &mut final_scores);
+            if let Some(gcg_writer) = &mut gcg_writer {
+                gcg_writer.record_game_end(game_config, &game_state, end);
+            }
+            match end {
                 game_state::CheckGameEnded::PlayedOut => {
                     println!("Player {} went out", game_state.turn + 1);
                     break;
@@ -497,6 +586,14 @@ fn do_it<N: kwg::Node + Sync>(
                 println!("Player {} adjustment {}", i + 1, adjustment);
                 final_scores[i] += equity::scale_score(adjustment as i32);
                 has_time_adjustment = true;
+                if let Some(gcg_writer) = &mut gcg_writer {
+                    gcg_writer.record_time_penalty(
+                        game_config,
+                        i as u8,
+                        &game_state.players[i].rack,
+                        adjustment as i32,
+                    );
+                }
             }
         }
         if has_time_adjustment {
@@ -505,9 +602,20 @@ fn do_it<N: kwg::Node + Sync>(
             }
             println!("Really final scores: {display_scores:?}");
         }
+        num_games += 1;
+        if let (Some(gcg_dir), Some(gcg_writer)) = (&gcg_dir, &gcg_writer) {
+            std::fs::write(
+                format!("{gcg_dir}/game-{num_games}.gcg"),
+                gcg_writer.as_str(),
+            )?;
+        }
 
         let spr = display_scores[0] - display_scores[1];
-        let p0dw = spr.signum() + 1; // double win
+        let p0dw = match time_forfeit {
+            Some(0) => 0,
+            Some(_) => 2,
+            None => spr.signum() + 1, // double win
+        };
         score_stats_0.update(display_scores[0] as f64);
         score_stats_1.update(display_scores[1] as f64);
         spread_stats_0.update(spr as f64);
@@ -1,7 +1,7 @@
 // Copyright (C) 2020-2026 Andy Kurnia.
 
 use wolges::kwg::Node;
-use wolges::{alphabet, bites, build, error, fash, kwg, lexport, prob};
+use wolges::{alphabet, bites, build, bundle, error, fash, game_config, kwg, lexport, prob};
 
 fn parse_machine_words(
     alphabet_reader: &alphabet::AlphabetReader,
@@ -305,9 +305,76 @@ fn do_lang<AlphabetMaker: Fn() -> alphabet::Alphabet>(
                     make_writer(&args[3])?.write_all(&built)?;
                     Ok(true)
                 }
+                "-kwg-edit" | "-kbwg-edit" => {
+                    let alph = make_alphabet();
+                    let alphabet_reader = alphabet::AlphabetReader::new_for_words(&alph);
+                    let add_words = read_machine_words(
+                        &alphabet_reader,
+                        &read_to_string(&mut make_reader(&args[3])?)?,
+                    )?;
+                    let remove_words = read_machine_words(
+                        &alphabet_reader,
+                        &read_to_string(&mut make_reader(&args[4])?)?,
+                    )?;
+                    let kwg_bytes = kwg::AlignedBytes::read(&args[2])?;
+                    let built = if args1_suffix.starts_with("-kbwg") {
+                        build::edit_big(
+                            build::BuildContent::Gaddawg,
+                            build_layout,
+                            &kwg::Kwg::<kwg::Node24>::from_bytes(&kwg_bytes),
+                            &add_words,
+                            &remove_words,
+                        )?
+                    } else {
+                        build::edit(
+                            build::BuildContent::Gaddawg,
+                            build_layout,
+                            &kwg::Kwg::<kwg::Node22>::from_bytes(&kwg_bytes),
+                            &add_words,
+                            &remove_words,
+                        )?
+                    };
+                    make_writer(&args[5])?.write_all(&built)?;
+                    Ok(true)
+                }
+                "-kwg-bundle" | "-kbwg-bundle" | "-kwg-alpha-bundle" | "-kbwg-alpha-bundle"
+                | "-kwg-dawg-bundle" | "-kbwg-dawg-bundle" => {
+                    let kwg_bytes = std::fs::read(&args[2])?;
+                    let klv_bytes = match args.get(6) {
+                        Some(klv_path) => Some(std::fs::read(klv_path)?),
+                        None => None,
+                    };
+                    let built = bundle::Bundle {
+                        metadata: bundle::Metadata {
+                            lexicon_name: args[3].clone(),
+                            build_date: args[4].clone(),
+                            layout: build_layout,
+                            content: if args1_suffix.contains("alpha") {
+                                bundle::Content::AlphaDawg
+                            } else if args1_suffix.contains("dawg") {
+                                bundle::Content::DawgOnly
+                            } else {
+                                bundle::Content::Gaddawg
+                            },
+                            node_width: if args1_suffix.starts_with("-kbwg") {
+                                bundle::NodeWidth::Node24
+                            } else {
+                                bundle::NodeWidth::Node22
+                            },
+                        },
+                        alphabet_text: &make_alphabet().to_static_text(),
+                        kwg: &kwg_bytes,
+                        klv: klv_bytes.as_deref(),
+                    }
+                    .to_bytes()?;
+                    make_writer(&args[5])?.write_all(&built)?;
+                    Ok(true)
+                }
                 "-macondo" => {
                     let alphabet = make_alphabet();
-                    let kwg = kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read(&args[2])?);
+                    let kwg = kwg::Kwg::<kwg::Node22>::from_bytes(
+                        kwg::AlignedBytes::read(&args[2])?.leak(),
+                    );
                     make_writer(&args[4])?.write_all(&lexport::to_macondo(
                         &kwg,
                         &alphabet,
@@ -324,7 +391,9 @@ fn do_lang<AlphabetMaker: Fn() -> alphabet::Alphabet>(
                 }
                 "-lxd" => {
                     let alphabet = make_alphabet();
-                    let kwg = kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read(&args[2])?);
+                    let kwg = kwg::Kwg::<kwg::Node22>::from_bytes(
+                        kwg::AlignedBytes::read(&args[2])?.leak(),
+                    );
                     make_writer(&args[5])?
                         .write_all(&lexport::to_lxd(&kwg, &alphabet, &args[3], &args[4])?)?;
                     Ok(true)
@@ -429,6 +498,15 @@ fn main() -> error::Returns<()> {
     generate kwg file containing gaddawg (supports 4M nodes)
   english-kbwg CSW24.txt CSW24.kbwg
     generate kbwg file containing gaddawg (big variant supports 16M nodes)
+  english-kwg-edit CSW24.kwg add.txt remove.txt out.kwg
+  english-kbwg-edit CSW24.kbwg add.txt remove.txt out.kbwg
+    same as generating from the kwg's words plus add.txt minus remove.txt,
+    without a full rebuild (use the same layout the kwg was generated with)
+  english-kwg-bundle CSW24.kwg CSW24 \"17 June 2021\" CSW24.wlb [CSW24.klv2]
+    pack a kwg (and klv) with its alphabet, name, date, layout and content
+    into a lexicon bundle, also -kbwg-bundle, -kwg-alpha-bundle,
+    -kbwg-alpha-bundle, -kwg-dawg-bundle, -kbwg-dawg-bundle
+    (use the same layout the kwg was generated with)
   english-macondo CSW24.kwg CSW24 CSW24.dawg CSW24.gaddag
     read kwg file, with lexicon name save macondo dawg/gaddag
   english-lxd CSW24.kwg \"CSW24 something\" \"17 June 2021\" UKNA.lxd
@@ -460,6 +538,8 @@ fn main() -> error::Returns<()> {
     slovene, spanish, swedish, decimal, hex)
   (english can also be custom, with an extra alphabet file argument:
     custom-kwg alphabet.txt words.txt out.kwg)
+  (english can also be rules, with an extra .json rules file argument
+    whose alphabet is used: rules-kwg club.json words.txt out.kwg)
 input/output files can be \"-\" (not advisable for binary files)"
         );
         Ok(())
@@ -493,6 +573,17 @@ input/output files can be \"-\" (not advisable for binary files)"
             })? {
                 return Err("invalid argument".into());
             }
+        } else if args[1].starts_with("rules-") {
+            if args.len() < 3 {
+                return Err("need rules file".into());
+            }
+            // only the alphabet of the rules file matters for building.
+            let alphabet = game_config::load_game_config_from_rules_file(&args[2])?.into_alphabet();
+            let mut shifted_args = vec![args[0].clone(), args[1].clone()];
+            shifted_args.extend_from_slice(&args[3..]);
+            if !do_lang(&shifted_args, "rules", || alphabet.clone())? {
+                return Err("invalid argument".into());
+            }
         } else {
             return Err("invalid argument".into());
         }
@@ -590,8 +681,9 @@ fn old_main() -> error::Returns<()> {
         let t0 = std::time::Instant::now();
         {
             let t0 = std::time::Instant::now();
-            let kwg =
-                kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/CSW24.kwg")?);
+            let kwg = kwg::Kwg::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/CSW24.kwg")?.leak(),
+            );
             println!("{:?} for rereading CSW24.kwg", t0.elapsed());
             let t0 = std::time::Instant::now();
             std::fs::write(
@@ -617,8 +709,9 @@ fn old_main() -> error::Returns<()> {
             println!("{:?} for exporting CSW24 gaddag", t0.elapsed());
         }
         {
-            let kwg =
-                kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/NWL23.kwg")?);
+            let kwg = kwg::Kwg::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/NWL23.kwg")?.leak(),
+            );
             std::fs::write(
                 "lexbin/NWL23.dawg",
                 lexport::to_macondo(
@@ -639,7 +732,9 @@ fn old_main() -> error::Returns<()> {
             )?;
         }
         {
-            let kwg = kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/ECWL.kwg")?);
+            let kwg = kwg::Kwg::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/ECWL.kwg")?.leak(),
+            );
             std::fs::write(
                 "lexbin/ECWL.dawg",
                 lexport::to_macondo(
@@ -662,8 +757,9 @@ fn old_main() -> error::Returns<()> {
         println!("{:?} for exporting many files", t0.elapsed());
         if true {
             let t0 = std::time::Instant::now();
-            let kwg =
-                kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/OSPS49.kwg")?);
+            let kwg = kwg::Kwg::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/OSPS49.kwg")?.leak(),
+            );
             println!("{:?} for rereading OSPS49.kwg", t0.elapsed());
             let t0 = std::time::Instant::now();
             std::fs::write(
@@ -798,7 +894,9 @@ fn old_main() -> error::Returns<()> {
 
     if true {
         // proof-of-concept
-        let kwg = kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/allgdw.kwg")?);
+        let kwg = kwg::Kwg::<kwg::Node22>::from_bytes(
+            kwg::AlignedBytes::read("lexbin/allgdw.kwg")?.leak(),
+        );
         let word_counts = kwg.count_dawg_words_alloc();
         // because dawg do not need gaddag nodes
         println!("only counting {} nodes", word_counts.len());
@@ -2,8 +2,8 @@
 
 use rand::prelude::*;
 use wolges::{
-    alphabet, bites, build, display, endgame, equity, error, fash, game_config, game_state, klv,
-    kwg, matrix, movegen, play_scorer,
+    alphabet, bites, build, bundle, display, endgame, equity, error, fash, game_config, game_state,
+    klv, kwg, matrix, movegen, multi_endgame, play_scorer,
 };
 
 // this is reusing most of main_json, but main_json is the most current code.
@@ -432,7 +432,8 @@ impl Question {
 // assumes the harvested positions are CSW24 english (as produced by autoplay).
 fn run_batch(path: &str) -> error::Returns<()> {
     let game_config = game_config::make_english_game_config();
-    let kwg = kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/CSW24.kwg")?);
+    let kwg =
+        kwg::Kwg::<kwg::Node22>::from_bytes(kwg::AlignedBytes::read("lexbin/CSW24.kwg")?.leak());
     let empty_klv = klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES);
     let alphabet = game_config.alphabet();
     let board_layout = game_config.board_layout();
@@ -1245,6 +1246,9 @@ fn main() -> error::Returns<()> {
 // so the printed win/draw/loss reflects the actual game, not just the value of
 // the tiles left to play. Default 0 asks only about the rest of the game,
 // ignoring the score so far.
+//
+// a config for more than two players (a rules file) instead takes every rack
+// and score, and runs multi_endgame's solver by each strategy.
 fn run_cli(args: &[String]) -> error::Returns<()> {
     const USAGE: &str = "\
 usage: endgame <config> <kwg-file> <fen> <rack> [score-diff]
@@ -1254,12 +1258,20 @@ usage: endgame <config> <kwg-file> <fen> <rack> [score-diff]
               particular word list. Prefix jumbled- for the anagram variant
               (needs a .kad alphagram dawg) and/or super- for the 21x21
               board; suffix -big for a .kbwg (24-bit, e.g. a Dutch word list
-              too large for a plain .kwg), e.g. jumbled-dutch-big
-  kwg-file:   path to a word graph built for that config
+              too large for a plain .kwg), e.g. jumbled-dutch-big. Or the
+              path of a .json rules file (see game_config::RulesFile),
+              which also takes the -big suffix
+  kwg-file:   path to a word graph built for that config, or a lexicon
+              bundle (which needs no -big suffix, and is checked against
+              the config)
   fen:        board in FEN notation (quote it -- it contains '/')
-  rack:       your tiles, e.g. ADENOOO (? for a blank)
+  rack:       your tiles, e.g. ADENOOO (? for a blank). With more than
+              two players, every rack in turn order from yours, separated
+              by '/', e.g. AT/QV/QV
   score-diff: how many points you are AHEAD of the opponent right now
-              (negative if you are behind), default 0";
+              (negative if you are behind), default 0. With more than two
+              players, every score in turn order from yours, separated by
+              ',', e.g. 120,95,101 (default all 0)";
     if args.len() != 5 && args.len() != 6 {
         wolges::return_error!(format!(
             "expected 4 or 5 arguments, got {}\n{USAGE}",
@@ -1270,73 +1282,190 @@ usage: endgame <config> <kwg-file> <fen> <rack> [score-diff]
     let kwg_path = &args[2];
     let fen = &args[3];
     let rack = &args[4];
-    let score_diff = if args.len() == 6 {
-        let Ok(score_diff) = args[5].parse::<i32>() else {
-            wolges::return_error!(format!("invalid score-diff {:?}\n{USAGE}", args[5]));
-        };
-        score_diff
-    } else {
-        0
-    };
     // the -big suffix picks the 24-bit node layout (a .kbwg) for word lists
     // too large to fit a plain .kwg; the base name still selects the config.
     let (base_name, big) = match config_name.strip_suffix("-big") {
         Some(base) => (base, true),
         None => (config_name.as_str(), false),
     };
-    let Some(game_config) = game_config_for_name(base_name) else {
-        wolges::return_error!(format!("invalid config {config_name:?}\n{USAGE}"));
+    let game_config = game_config::load_game_config(base_name)
+        .map_err(|e| error::new(format!("{e}\n{USAGE}")))?;
+    let position = if game_config.num_players() > 2 {
+        // every rack is needed, as the unseen tiles cannot be split up.
+        CliPosition::Multi(make_multi_game_state(
+            &game_config,
+            fen,
+            rack,
+            args.get(5).map(String::as_str),
+        )?)
+    } else {
+        let score_diff = if args.len() == 6 {
+            let Ok(score_diff) = args[5].parse::<i32>() else {
+                wolges::return_error!(format!("invalid score-diff {:?}\n{USAGE}", args[5]));
+            };
+            score_diff
+        } else {
+            0
+        };
+        CliPosition::Single(
+            Question::from_fen(&game_config, config_name, fen, rack)?,
+            score_diff,
+        )
+    };
+    let file_bytes = kwg::AlignedBytes::read(kwg_path)?;
+    // a bundle says its own node width.
+    let (kwg_bytes, big) = match bundle::detect(&file_bytes, &game_config)? {
+        Some(bundle) => (
+            bundle.kwg,
+            bundle.metadata.node_width == bundle::NodeWidth::Node24,
+        ),
+        None => (&file_bytes[..], big),
     };
-    let question = Question::from_fen(&game_config, config_name, fen, rack)?;
-    let kwg_bytes = std::fs::read(kwg_path)?;
     // Node22 (.kwg/.kad) vs Node24 (.kbwg): only the source graph's layout
     // differs; solve_position is generic over it and rebuilds a Node22 pruned
     // graph internally, so both arms converge after the load.
     if big {
-        let kwg = kwg::Kwg::<kwg::Node24>::from_bytes_alloc(&kwg_bytes);
-        solve_position(&game_config, &kwg, &question, score_diff)
+        let kwg = kwg::Kwg::<kwg::Node24>::from_bytes(kwg_bytes);
+        solve_cli_position(&game_config, &kwg, &position)
     } else {
-        let kwg = kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&kwg_bytes);
-        solve_position(&game_config, &kwg, &question, score_diff)
+        let kwg = kwg::Kwg::<kwg::Node22>::from_bytes(kwg_bytes);
+        solve_cli_position(&game_config, &kwg, &position)
+    }
+}
+
+// what run_cli was asked to solve: the mover's view of a two-player position
+// with their lead, or a multi-player position with every rack known.
+enum CliPosition {
+    Single(Question, i32),
+    Multi(game_state::GameState),
+}
+
+fn solve_cli_position<N: kwg::Node>(
+    game_config: &game_config::GameConfig,
+    kwg: &kwg::Kwg<N>,
+    position: &CliPosition,
+) -> error::Returns<()> {
+    match position {
+        CliPosition::Single(question, score_diff) => {
+            solve_position(game_config, kwg, question, *score_diff)
+        }
+        CliPosition::Multi(game_state) => solve_multi_position(game_config, kwg, game_state),
+    }
+}
+
+// the position with the mover (player 0) to play, "/"-separated racks in turn
+// order and ","-separated whole-point scores, missing ones being 0. Tiles not
+// on the board or a rack are left in the bag, which the solver refuses.
+fn make_multi_game_state(
+    game_config: &game_config::GameConfig,
+    fen: &str,
+    racks: &str,
+    scores: Option<&str>,
+) -> error::Returns<game_state::GameState> {
+    let alphabet = game_config.alphabet();
+    let mut game_state = game_state::GameState::new(game_config);
+    let mut fen_parser = display::BoardFenParser::new(alphabet, game_config.board_layout());
+    game_state
+        .board_tiles
+        .copy_from_slice(fen_parser.parse(fen)?);
+    let racks = racks.split('/').collect::<Vec<_>>();
+    if racks.len() != game_state.players.len() {
+        wolges::return_error!(format!(
+            "expected {} racks, got {}",
+            game_state.players.len(),
+            racks.len(),
+        ));
+    }
+    let racks_alphabet_reader = alphabet::AlphabetReader::new_for_racks(alphabet);
+    for (player, rack) in game_state.players.iter_mut().zip(racks) {
+        racks_alphabet_reader
+            .set_word(rack, &mut player.rack)
+            .map_err(|e| error::new(format!("invalid rack {rack:?}: {e}")))?;
+    }
+    if let Some(scores) = scores {
+        let scores = scores.split(',').collect::<Vec<_>>();
+        if scores.len() > game_state.players.len() {
+            wolges::return_error!(format!(
+                "expected at most {} scores, got {}",
+                game_state.players.len(),
+                scores.len(),
+            ));
+        }
+        for (player, score) in game_state.players.iter_mut().zip(scores) {
+            let Ok(score) = score.parse::<i32>() else {
+                wolges::return_error!(format!("invalid score {score:?}"));
+            };
+            player.score = equity::scale_score(score);
+        }
     }
+    let mut unseen_tally = (0..alphabet.len())
+        .map(|tile| alphabet.freq(tile))
+        .collect::<Box<_>>();
+    let tiles = game_state
+        .board_tiles
+        .iter()
+        .filter(|&&tile| tile != 0)
+        .map(|&tile| if tile & 0x80 != 0 { 0 } else { tile })
+        .chain(
+            game_state
+                .players
+                .iter()
+                .flat_map(|player| player.rack.iter().copied()),
+        );
+    for tile in tiles {
+        if unseen_tally[tile as usize] == 0 {
+            wolges::return_error!(format!(
+                "too many tile {} (bag contains only {})",
+                tile,
+                alphabet.freq(tile),
+            ));
+        }
+        unseen_tally[tile as usize] -= 1;
+    }
+    game_state.bag.set_from_iter(
+        (0u8..)
+            .zip(unseen_tally.iter())
+            .flat_map(|(tile, &count)| std::iter::repeat_n(tile, count as usize)),
+    );
+    Ok(game_state)
 }
 
-// the alphabet, board layout, and tile set for a config name -- language-
-// named, not lexicon-named, since a config is reusable across any word list
-// built for it (a config has no notion of which words are valid). The
-// jumbled- prefix selects the anagram variant (any letters in any order form
-// a word), the super- prefix selects the 21x21 Super board, and they combine
-// as jumbled-super-. A jumbled config expects a .kad (alphagram dawg) word
-// graph rather than a .kwg gaddawg; the word-prune step below rebuilds in the
-// matching shape automatically.
-fn game_config_for_name(name: &str) -> Option<game_config::GameConfig> {
-    Some(match name {
-        "english" => game_config::make_english_game_config(),
-        "jumbled-english" => game_config::make_jumbled_english_game_config(),
-        "super-english" => game_config::make_super_english_game_config(),
-        "jumbled-super-english" => game_config::make_jumbled_super_english_game_config(),
-        "catalan" => game_config::make_catalan_game_config(),
-        "jumbled-catalan" => game_config::make_jumbled_catalan_game_config(),
-        "super-catalan" => game_config::make_super_catalan_game_config(),
-        "jumbled-super-catalan" => game_config::make_jumbled_super_catalan_game_config(),
-        "dutch" => game_config::make_dutch_game_config(),
-        "jumbled-dutch" => game_config::make_jumbled_dutch_game_config(),
-        "french" => game_config::make_french_game_config(),
-        "jumbled-french" => game_config::make_jumbled_french_game_config(),
-        "german" => game_config::make_german_game_config(),
-        "jumbled-german" => game_config::make_jumbled_german_game_config(),
-        "norwegian" => game_config::make_norwegian_game_config(),
-        "jumbled-norwegian" => game_config::make_jumbled_norwegian_game_config(),
-        "polish" => game_config::make_polish_game_config(),
-        "jumbled-polish" => game_config::make_jumbled_polish_game_config(),
-        "slovene" => game_config::make_slovene_game_config(),
-        "jumbled-slovene" => game_config::make_jumbled_slovene_game_config(),
-        "spanish" => game_config::make_spanish_game_config(),
-        "jumbled-spanish" => game_config::make_jumbled_spanish_game_config(),
-        "swedish" => game_config::make_swedish_game_config(),
-        "jumbled-swedish" => game_config::make_jumbled_swedish_game_config(),
-        _ => return None,
-    })
+// solve a multi-player endgame by each strategy, printing the best play and
+// the scores at the end of its line.
+fn solve_multi_position<N: kwg::Node>(
+    game_config: &game_config::GameConfig,
+    kwg: &kwg::Kwg<N>,
+    game_state: &game_state::GameState,
+) -> error::Returns<()> {
+    let klv = klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES);
+    let board_snapshot = &movegen::BoardSnapshot {
+        board_tiles: &game_state.board_tiles,
+        game_config,
+        kwg,
+        klv: &klv,
+    };
+    let mut solver = multi_endgame::MultiEndgameSolver::new(game_config, kwg);
+    for strategy in [
+        multi_endgame::MultiEndgameStrategy::MaxN,
+        multi_endgame::MultiEndgameStrategy::Paranoid,
+    ] {
+        let result = solver.solve(game_state, strategy)?;
+        match &result.best_play {
+            Some(play) => println!("{strategy:?}: best move = {}", play.fmt(board_snapshot)),
+            None => println!("{strategy:?}: no move searched"),
+        }
+        println!(
+            "{strategy:?}: final scores = {}, margin = {}",
+            result
+                .final_scores
+                .iter()
+                .map(|&score| equity::descale_score(score).to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+            equity::descale_score(multi_endgame::margin(&result.final_scores, 0)),
+        );
+    }
+    Ok(())
 }
 
 // word-prune a board into a smaller word graph for the solver to search: keep
@@ -1370,11 +1499,15 @@ fn solve_question(question: &Question, score_diff: i32) -> error::Returns<()> {
     // of course this should be cached
     let (kwg, game_config) = match question.lexicon.as_str() {
         "CSW24" => (
-            kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/CSW24.kwg")?),
+            kwg::Kwg::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/CSW24.kwg")?.leak(),
+            ),
             game_config::make_english_game_config(),
         ),
         "NWL23" => (
-            kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/NWL23.kwg")?),
+            kwg::Kwg::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/NWL23.kwg")?.leak(),
+            ),
             game_config::make_english_game_config(),
         ),
         _ => {
@@ -1659,42 +1792,27 @@ mod tests {
         );
     }
 
-    // unknown config is rejected with None so the CLI can print usage.
+    // every rack and score is dealt in turn order, and the tiles left over
+    // stay in the bag.
     #[test]
-    fn game_config_for_name_rejects_unknown() {
-        assert!(game_config_for_name("english").is_some());
-        assert!(game_config_for_name("BOGUS").is_none());
-    }
-
-    // the jumbled- and super- prefixes resolve, and their game_rules match
-    // (jumbled -> Jumbled, so the word-prune below picks the alphagram dawg).
-    #[test]
-    fn game_config_for_name_handles_jumbled_and_super() {
-        assert!(matches!(
-            game_config_for_name("english").unwrap().game_rules(),
-            game_config::GameRules::Classic
-        ));
-        assert!(matches!(
-            game_config_for_name("jumbled-english")
-                .unwrap()
-                .game_rules(),
-            game_config::GameRules::Jumbled
-        ));
-        assert!(matches!(
-            game_config_for_name("jumbled-super-english")
-                .unwrap()
-                .game_rules(),
-            game_config::GameRules::Jumbled
-        ));
-        // super- alone stays classic (only the board grows to 21x21).
-        let super_english = game_config_for_name("super-english").unwrap();
-        assert!(matches!(
-            super_english.game_rules(),
-            game_config::GameRules::Classic
-        ));
-        assert_eq!(super_english.board_layout().dim().rows, 21);
-        // the -big node-width suffix is not part of the config name itself.
-        assert!(game_config_for_name("english-big").is_none());
+    fn make_multi_game_state_deals_every_rack() {
+        let game_config =
+            game_config::make_game_config_from_rules(r#"{ "num_players": 3 }"#).unwrap();
+        let dim = game_config.board_layout().dim();
+        let fen = std::iter::repeat_n(dim.cols.to_string(), dim.rows as usize)
+            .collect::<Vec<_>>()
+            .join("/");
+        let game_state =
+            make_multi_game_state(&game_config, &fen, "AT/QV/?", Some("10,-3")).unwrap();
+        assert_eq!(game_state.players[0].rack, [1, 20]);
+        assert_eq!(game_state.players[1].rack, [17, 22]);
+        assert_eq!(game_state.players[2].rack, [0]);
+        assert_eq!(game_state.players[0].score, equity::scale_score(10));
+        assert_eq!(game_state.players[1].score, equity::scale_score(-3));
+        assert_eq!(game_state.players[2].score, 0);
+        assert_eq!(game_state.bag.len(), 100 - 5);
+        assert!(make_multi_game_state(&game_config, &fen, "AT/QV", None).is_err());
+        assert!(make_multi_game_state(&game_config, &fen, "QQ/Q/Q", None).is_err());
     }
 
     // the classic word-prune builds a gaddawg (finds the word forward), the
@@ -0,0 +1,675 @@
+// Copyright (C) 2020-2026 Andy Kurnia.
+
+use rand::prelude::*;
+use std::io::Write;
+use wolges::{
+    alphabet, bundle, display, equity, error, game_config, kibitzer, klv, kwg, move_picker,
+    movegen, simmer,
+};
+
+// a long-running, line-oriented engine in the spirit of UCI: the word graph,
+// leaves, and config are loaded once, then commands are read from stdin one
+// per line and every result is written to stdout as one JSON object per line,
+// tagged by "type". Plays use the same JSON shape as the json binary
+// (kibitzer::JsonPlay), so -1 is blank-as-A and 0 in a word is play-through.
+//
+//   isready                                        -> readyok
+//   position fen <fen> rack <rack> [score <mine> <theirs>]
+//   go movegen <n>                                 -> play..., done
+//   go sim iters <n> [plays <m>] [seed <s>] [period <p>] [plies <k|end>]
+//          [exchanges <x>]                         -> sim..., bestmove
+//   go endgame                                     -> endgame or peg
+//   stop                                           (ends a running go sim)
+//   quit
+//
+// a bad command is answered with an "error" line and the engine keeps going.
+// the mover is always the side to move in the current position; "score" is
+// in whole points. plies is how deep each rollout goes (default 2), or end to
+// roll out the rest of the game. exchanges sims the top m placements, the top x
+// exchanges by leave value and pass, instead of the top m plays.
+
+const USAGE: &str = "\
+usage: engine <config> <kwg-file> [klv-file]
+  config:   english, catalan, dutch, french, german, norwegian, polish,
+            slovene, spanish, or swedish, optionally prefixed jumbled-
+            and/or super-, or a .json rules file, and suffixed -big for a
+            .kbwg (see endgame)
+  kwg-file: path to a word graph built for that config, or a lexicon
+            bundle (which needs no -big suffix, and is checked against
+            the config)
+  klv-file: path to a .klv2 leave file (default: the bundle's, or no
+            leaves)";
+
+const DEFAULT_SIM_PLAYS: usize = 100;
+const DEFAULT_SIM_PERIOD: u64 = 100;
+const DEFAULT_SIM_PLIES: usize = 2;
+const SIM_LEADERS: usize = 5;
+
+// one sim leaderboard entry: the candidate play with its static equity, plus
+// the sim objective mean, mean equity (points), and mean win rate so far.
+#[derive(serde::Serialize)]
+struct SimLeader {
+    #[serde(flatten)]
+    play: kibitzer::JsonPlayWithEquity,
+    mean: f64,
+    sim_equity: f64,
+    win_rate: f64,
+}
+
+#[derive(serde::Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Response {
+    ReadyOk,
+    Play {
+        rank: usize,
+        #[serde(flatten)]
+        play: kibitzer::JsonPlayWithEquity,
+    },
+    Done {
+        count: usize,
+    },
+    Sim {
+        iters: u64,
+        leaders: Vec<SimLeader>,
+    },
+    BestMove {
+        #[serde(flatten)]
+        play: kibitzer::JsonPlayWithEquity,
+    },
+    // margin is the final game margin in points (current score difference
+    // plus the value of the rest of the game); each pv entry's equity is the
+    // solver's valuation after that play.
+    Endgame {
+        margin: f32,
+        pv: Vec<kibitzer::JsonPlayWithEquity>,
+    },
+    // one tile in the bag. best_move is None under the clairvoyant fallback,
+    // and a pass is an exchange of no tiles.
+    Peg {
+        committed: bool,
+        win_pct: f32,
+        expected_margin: f32,
+        best_move: Option<kibitzer::JsonPlay>,
+    },
+    Error {
+        message: String,
+    },
+}
+
+fn emit(out: &mut impl Write, response: &Response) -> error::Returns<()> {
+    serde_json::to_writer(&mut *out, response)?;
+    writeln!(out)?;
+    out.flush()?;
+    Ok(())
+}
+
+// the value following a keyword in "key value key value ..." arguments.
+fn parse_options<'s>(args: &[&'s str], keys: &[&str]) -> error::Returns<Vec<Option<&'s str>>> {
+    let mut values = vec![None; keys.len()];
+    let mut it = args.iter();
+    while let Some(&key) = it.next() {
+        let Some(pos) = keys.iter().position(|&k| k == key) else {
+            wolges::return_error!(format!("unknown option {key:?}"));
+        };
+        let Some(&value) = it.next() else {
+            wolges::return_error!(format!("missing value for {key:?}"));
+        };
+        values[pos] = Some(value);
+    }
+    Ok(values)
+}
+
+fn parse_number<T: std::str::FromStr>(key: &str, value: &str) -> error::Returns<T> {
+    value
+        .parse::<T>()
+        .map_err(|_| error::new(format!("invalid {key} {value:?}")).into())
+}
+
+struct Engine<'a, N: kwg::Node, L: kwg::Node> {
+    game_config: &'a game_config::GameConfig,
+    kwg: &'a kwg::Kwg<'a, N>,
+    klv: &'a klv::Klv<'a, L>,
+    move_generator: movegen::KurniaMoveGenerator,
+    // board_tiles and available_tally (the unseen tiles) of the position.
+    kibitzer: kibitzer::Kibitzer,
+    rack: Vec<u8>,
+    scores: [i32; 2],
+    has_position: bool,
+    rng: rand::rngs::ChaCha20Rng,
+    // lines read while a search was running, to be handled after it.
+    pending: std::collections::VecDeque<String>,
+}
+
+impl<'a, N: kwg::Node + Sync, L: kwg::Node + Sync> Engine<'a, N, L> {
+    fn new(
+        game_config: &'a game_config::GameConfig,
+        kwg: &'a kwg::Kwg<'a, N>,
+        klv: &'a klv::Klv<'a, L>,
+        rng: rand::rngs::ChaCha20Rng,
+    ) -> Self {
+        Self {
+            game_config,
+            kwg,
+            klv,
+            move_generator: movegen::KurniaMoveGenerator::new(game_config),
+            kibitzer: kibitzer::Kibitzer::new(),
+            rack: Vec::new(),
+            scores: [0, 0],
+            has_position: false,
+            rng,
+            pending: std::collections::VecDeque::new(),
+        }
+    }
+
+    // handle lines until quit or end of input.
+    fn run(
+        &mut self,
+        lines: &std::sync::mpsc::Receiver<String>,
+        out: &mut impl Write,
+    ) -> error::Returns<()> {
+        emit(out, &Response::ReadyOk)?;
+        loop {
+            let line = match self.pending.pop_front() {
+                Some(line) => line,
+                None => match lines.recv() {
+                    Ok(line) => line,
+                    Err(_) => return Ok(()),
+                },
+            };
+            if !self.handle(&line, lines, out)? {
+                return Ok(());
+            }
+        }
+    }
+
+    // returns false on quit. command errors are reported, not returned.
+    fn handle(
+        &mut self,
+        line: &str,
+        lines: &std::sync::mpsc::Receiver<String>,
+        out: &mut impl Write,
+    ) -> error::Returns<bool> {
+        let tokens = line.split_whitespace().collect::<Vec<_>>();
+        let result = match tokens.as_slice() {
+            [] | ["stop"] => Ok(()),
+            ["quit"] => return Ok(false),
+            ["isready"] => emit(out, &Response::ReadyOk),
+            ["position", args @ ..] => self.set_position(args),
+            ["go", "movegen", args @ ..] => self.go_movegen(args, out),
+            ["go", "sim", args @ ..] => self.go_sim(args, lines, out),
+            ["go", "endgame"] => self.go_endgame(out),
+            _ => Err(error::new(format!("unknown command {line:?}")).into()),
+        };
+        if let Err(e) = result {
+            emit(
+                out,
+                &Response::Error {
+                    message: e.to_string(),
+                },
+            )?;
+        }
+        Ok(true)
+    }
+
+    fn set_position(&mut self, args: &[&str]) -> error::Returns<()> {
+        let (fen, rack, scores) = match args {
+            ["fen", fen, "rack", rack] => (fen, rack, None),
+            ["fen", fen, "rack", rack, "score", mine, theirs] => (
+                fen,
+                rack,
+                Some([
+                    parse_number::<i32>("score", mine)?,
+                    parse_number::<i32>("score", theirs)?,
+                ]),
+            ),
+            _ => {
+                wolges::return_error!(
+                    "expected position fen <fen> rack <rack> [score <mine> <theirs>]".into()
+                );
+            }
+        };
+        let alphabet = self.game_config.alphabet();
+        let dim = self.game_config.board_layout().dim();
+        let mut rack_tiles = Vec::new();
+        alphabet::AlphabetReader::new_for_racks(alphabet)
+            .set_word(rack, &mut rack_tiles)
+            .map_err(|e| error::new(format!("invalid rack {rack:?}: {e}")))?;
+        if rack_tiles.len() > self.game_config.rack_size() as usize {
+            wolges::return_error!(format!("rack {rack:?} has too many tiles"));
+        }
+        let mut fen_parser =
+            display::BoardFenParser::new(alphabet, self.game_config.board_layout());
+        let board_tiles = fen_parser.parse(fen)?;
+        // turn 0x81u8, 0x82u8 into -1i8, -2i8 for the kibitzer.
+        let signed_board_tiles = board_tiles
+            .chunks_exact(dim.cols as usize)
+            .map(|row| {
+                row.iter()
+                    .map(|&x| {
+                        if x & 0x80 == 0 {
+                            x as i8
+                        } else {
+                            -0x80i8 - (x as i8)
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        self.has_position = false;
+        self.kibitzer
+            .prepare(self.game_config, &rack_tiles, &signed_board_tiles)?;
+        self.rack = rack_tiles;
+        self.scores = scores.unwrap_or([0, 0]);
+        self.has_position = true;
+        Ok(())
+    }
+
+    fn check_position(&self) -> error::Returns<()> {
+        if !self.has_position {
+            wolges::return_error!("no position set".into());
+        }
+        Ok(())
+    }
+
+    fn go_movegen(&mut self, args: &[&str], out: &mut impl Write) -> error::Returns<()> {
+        self.check_position()?;
+        let max_gen = match args {
+            [n] => parse_number::<usize>("count", n)?,
+            _ => {
+                wolges::return_error!("expected go movegen <n>".into());
+            }
+        };
+        let board_snapshot = &movegen::BoardSnapshot {
+            board_tiles: &self.kibitzer.board_tiles,
+            game_config: self.game_config,
+            kwg: self.kwg,
+            klv: self.klv,
+        };
+        self.move_generator
+            .gen_moves_unfiltered(&movegen::GenMovesParams {
+                board_snapshot,
+                rack: &self.rack,
+                max_gen,
+                num_exchanges_by_this_player: 0,
+                always_include_pass: false,
+                dynamic_leaves: None,
+            });
+        for (rank, play) in self.move_generator.plays.iter().enumerate() {
+            emit(
+                out,
+                &Response::Play {
+                    rank,
+                    play: play.into(),
+                },
+            )?;
+        }
+        emit(
+            out,
+            &Response::Done {
+                count: self.move_generator.plays.len(),
+            },
+        )
+    }
+
+    fn go_sim(
+        &mut self,
+        args: &[&str],
+        lines: &std::sync::mpsc::Receiver<String>,
+        out: &mut impl Write,
+    ) -> error::Returns<()> {
+        self.check_position()?;
+        let options = parse_options(
+            args,
+            &["iters", "plays", "seed", "period", "plies", "exchanges"],
+        )?;
+        let Some(iters) = options[0] else {
+            wolges::return_error!(
+                "expected go sim iters <n> [plays <m>] [seed <s>] [period <p>] [plies <k|end>] [exchanges <x>]"
+                    .into()
+            );
+        };
+        let iters = parse_number::<u64>("iters", iters)?;
+        let max_gen = match options[1] {
+            Some(v) => parse_number::<usize>("plays", v)?,
+            None => DEFAULT_SIM_PLAYS,
+        };
+        let seed = match options[2] {
+            Some(v) => parse_number::<u64>("seed", v)?,
+            None => self.rng.next_u64(),
+        };
+        let period = match options[3] {
+            Some(v) => parse_number::<u64>("period", v)?.max(1),
+            None => DEFAULT_SIM_PERIOD,
+        };
+        let num_sim_plies = match options[4] {
+            Some("end") => simmer::PLAY_TO_END,
+            Some(v) => parse_number::<usize>("plies", v)?,
+            None => DEFAULT_SIM_PLIES,
+        };
+        let candidate_policy = match options[5] {
+            Some(v) => Some(move_picker::CandidatePolicy {
+                num_placements: max_gen,
+                num_exchanges: parse_number::<usize>("exchanges", v)?,
+                include_pass: true,
+            }),
+            None => None,
+        };
+
+        let mut rng = rand::rngs::ChaCha20Rng::seed_from_u64(seed);
+        let game_state =
+            self.kibitzer
+                .make_game_state(self.game_config, &self.rack, &self.scores, &mut rng);
+        let board_snapshot = &movegen::BoardSnapshot {
+            board_tiles: &game_state.board_tiles,
+            game_config: self.game_config,
+            kwg: self.kwg,
+            klv: self.klv,
+        };
+        // exchanges may outrank placements, so with a candidate policy every play
+        // is generated and select_candidates keeps the top max_gen placements.
+        let gen_max_gen = if candidate_policy.is_some() {
+            usize::MAX
+        } else {
+            max_gen
+        };
+        self.move_generator
+            .gen_moves_unfiltered(&movegen::GenMovesParams {
+                board_snapshot,
+                rack: &self.rack,
+                max_gen: gen_max_gen,
+                num_exchanges_by_this_player: 0,
+                always_include_pass: false,
+                dynamic_leaves: None,
+            });
+        let mut simmer = move_picker::Simmer::new(self.game_config, self.kwg, self.klv);
+        simmer.set_candidate_policy(candidate_policy);
+        simmer.select_candidates(&mut self.move_generator, &game_state);
+        if self.move_generator.plays.is_empty() {
+            wolges::return_error!("no candidate plays".into());
+        }
+
+        simmer.set_verbose(false);
+        simmer.set_observe(true);
+        simmer.set_num_sim_iters(iters);
+        simmer.set_num_sim_plies(num_sim_plies);
+        simmer.reseed(seed);
+        let mut iters_done = period.min(iters);
+        simmer.begin_decision(&self.move_generator, &game_state, iters_done);
+        loop {
+            let plays = &self.move_generator.plays;
+            let leaders = simmer
+                .leaderboard(SIM_LEADERS)
+                .into_iter()
+                .map(|(play_index, mean, sim_equity, win_rate)| SimLeader {
+                    play: (&plays[play_index]).into(),
+                    mean,
+                    sim_equity,
+                    win_rate,
+                })
+                .collect();
+            emit(
+                out,
+                &Response::Sim {
+                    iters: iters_done,
+                    leaders,
+                },
+            )?;
+            if iters_done >= iters || self.poll_stop(lines, out)? {
+                break;
+            }
+            let extra_iters = period.min(iters - iters_done);
+            simmer.resume(&self.move_generator, extra_iters);
+            iters_done += extra_iters;
+        }
+        emit(
+            out,
+            &Response::BestMove {
+                play: (&self.move_generator.plays[simmer.best_so_far()]).into(),
+            },
+        )
+    }
+
+    // drain lines that arrived during a search. stop (or quit, which is kept
+    // to be handled next) ends the search; isready is answered immediately;
+    // anything else waits until the search is done.
+    fn poll_stop(
+        &mut self,
+        lines: &std::sync::mpsc::Receiver<String>,
+        out: &mut impl Write,
+    ) -> error::Returns<bool> {
+        while let Ok(line) = lines.try_recv() {
+            match line.trim() {
+                "stop" => return Ok(true),
+                "isready" => emit(out, &Response::ReadyOk)?,
+                "quit" => {
+                    self.pending.push_back(line);
+                    return Ok(true);
+                }
+                _ => self.pending.push_back(line),
+            }
+        }
+        Ok(false)
+    }
+
+    fn go_endgame(&mut self, out: &mut impl Write) -> error::Returns<()> {
+        self.check_position()?;
+        let response = match self.kibitzer.solve_endgame(
+            &mut self.move_generator,
+            self.game_config,
+            self.kwg,
+            &self.rack,
+            &self.scores,
+        )? {
+            kibitzer::EndgameAnswer::Peg(result) => Response::Peg {
+                committed: result.committed,
+                win_pct: result.win_pct,
+                expected_margin: result.expected_margin / equity::SCALE as f32,
+                best_move: result.best_move.as_ref().map(|best_move| best_move.into()),
+            },
+            kibitzer::EndgameAnswer::Endgame { margin, pv } => Response::Endgame {
+                margin: margin / equity::SCALE as f32,
+                pv: pv
+                    .iter()
+                    .map(|(value, play)| kibitzer::JsonPlayWithEquity {
+                        equity: value / equity::SCALE as f32,
+                        play: play.into(),
+                    })
+                    .collect(),
+            },
+        };
+        emit(out, &response)
+    }
+}
+
+fn run_engine<N: kwg::Node + Sync>(
+    game_config: &game_config::GameConfig,
+    kwg: &kwg::Kwg<N>,
+    klv: &klv::Klv<kwg::Node22>,
+) -> error::Returns<()> {
+    let rng = rand::rngs::ChaCha20Rng::try_from_rng(&mut rand::rngs::SysRng)?;
+    // a reader thread, so a running search can notice stop between chunks.
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lines() {
+            let Ok(line) = line else { break };
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    let mut engine = Engine::new(game_config, kwg, klv, rng);
+    engine.run(&receiver, &mut std::io::stdout().lock())
+}
+
+fn main() -> error::Returns<()> {
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.len() != 3 && args.len() != 4 {
+        wolges::return_error!(format!(
+            "expected 2 or 3 arguments, got {}\n{USAGE}",
+            args.len() - 1,
+        ));
+    }
+    let config_name = &args[1];
+    let (base_name, big) = match config_name.strip_suffix("-big") {
+        Some(base) => (base, true),
+        None => (config_name.as_str(), false),
+    };
+    let game_config = game_config::load_game_config(base_name)
+        .map_err(|e| error::new(format!("{e}\n{USAGE}")))?;
+    let file_bytes = kwg::AlignedBytes::read(&args[2])?;
+    let (kwg_bytes, big, bundled_klv_bytes) = match bundle::detect(&file_bytes, &game_config)? {
+        Some(bundle) => (
+            bundle.kwg,
+            bundle.metadata.node_width == bundle::NodeWidth::Node24,
+            bundle.klv,
+        ),
+        None => (&file_bytes[..], big, None),
+    };
+    let klv_file_bytes = args.get(3).map(kwg::AlignedBytes::read).transpose()?;
+    let klv = klv::Klv::<kwg::Node22>::from_bytes(match &klv_file_bytes {
+        Some(klv_file_bytes) => klv_file_bytes,
+        None => bundled_klv_bytes.unwrap_or(klv::EMPTY_KLV_BYTES),
+    });
+    if big {
+        let kwg = kwg::Kwg::<kwg::Node24>::from_bytes(kwg_bytes);
+        run_engine(&game_config, &kwg, &klv)
+    } else {
+        let kwg = kwg::Kwg::<kwg::Node22>::from_bytes(kwg_bytes);
+        run_engine(&game_config, &kwg, &klv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wolges::{bites, build};
+
+    fn tiny_kwg(game_config: &game_config::GameConfig) -> kwg::Kwg<'static, kwg::Node22> {
+        let reader = alphabet::AlphabetReader::new_for_words(game_config.alphabet());
+        let mut words = ["AB", "BA", "CAB", "DAB", "BAD", "CAD", "ABA"]
+            .iter()
+            .map(|w| {
+                let mut buf = Vec::new();
+                reader.set_word(w, &mut buf).unwrap();
+                bites::Bites::from(&buf[..])
+            })
+            .collect::<Vec<_>>();
+        words.sort_unstable();
+        kwg::Kwg::from_bytes_alloc(
+            &build::build(
+                build::BuildContent::Gaddawg,
+                build::BuildLayout::Wolges,
+                &words,
+            )
+            .unwrap(),
+        )
+    }
+
+    // feed the lines to a fresh engine and return its output lines, parsed.
+    fn run_lines(input: &[&str]) -> Vec<serde_json::Value> {
+        let game_config = game_config::make_english_game_config();
+        let kwg = tiny_kwg(&game_config);
+        let klv = klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        for line in input {
+            sender.send(line.to_string()).unwrap();
+        }
+        drop(sender);
+        let mut engine = Engine::new(
+            &game_config,
+            &kwg,
+            &klv,
+            rand::rngs::ChaCha20Rng::seed_from_u64(1),
+        );
+        let mut out = Vec::new();
+        engine.run(&receiver, &mut out).unwrap();
+        String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    fn empty_fen() -> String {
+        vec!["15"; 15].join("/")
+    }
+
+    // movegen streams ranked plays then a done line; errors do not stop the
+    // engine, and nothing after quit is handled.
+    #[test]
+    fn movegen_and_errors() {
+        let position = format!("position fen {} rack ABCD", empty_fen());
+        let out = run_lines(&[
+            "go movegen 3",
+            &position,
+            "go movegen 3",
+            "bogus",
+            "isready",
+            "quit",
+            "isready",
+        ]);
+        let types = out
+            .iter()
+            .map(|v| v["type"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            [
+                "readyok", "error", "play", "play", "play", "done", "error", "readyok"
+            ]
+        );
+        assert_eq!(out[2]["rank"], 0);
+        assert_eq!(out[2]["action"], "play");
+        assert_eq!(out[5]["count"], 3);
+    }
+
+    // a seeded sim reports each chunk and ends with a bestmove, reproducibly.
+    #[test]
+    fn sim_is_chunked_and_seeded() {
+        let position = format!("position fen {} rack ABCD score 10 20", empty_fen());
+        let input = [
+            position.as_str(),
+            "go sim iters 40 plays 5 seed 7 period 16",
+        ];
+        let out = run_lines(&input);
+        let sims = out
+            .iter()
+            .filter(|v| v["type"] == "sim")
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sims.iter()
+                .map(|v| v["iters"].as_u64().unwrap())
+                .collect::<Vec<_>>(),
+            [16, 32, 40]
+        );
+        assert_eq!(out.last().unwrap()["type"], "bestmove");
+        assert_eq!(out, run_lines(&input));
+
+        // rolling out to the end of the game is just another depth.
+        let input = [
+            position.as_str(),
+            "go sim iters 8 plays 3 seed 7 plies end",
+            "go sim iters 8 plies deep",
+        ];
+        let types = run_lines(&input)
+            .iter()
+            .map(|v| v["type"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert!(types.ends_with(&["sim".into(), "bestmove".into(), "error".into()]));
+
+        // exchanges puts exchanges and pass in contention next to the placements.
+        let input = [
+            position.as_str(),
+            "go sim iters 8 plays 2 seed 7 exchanges 2",
+        ];
+        let out = run_lines(&input);
+        let leaders = out.iter().rfind(|v| v["type"] == "sim").unwrap()["leaders"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v["action"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(leaders.len(), 5);
+        assert_eq!(leaders.iter().filter(|&a| a == "exchange").count(), 3);
+    }
+}
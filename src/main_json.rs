@@ -10,9 +10,11 @@ use wolges::{
 // rack: array of numbers. 0 for blank, 1 for A.
 // board: 2D array of numbers. 0 for empty, 1 for A, -1 for blank-as-A.
 // lexicon: this implies board size and other rules too.
-// count: maximum number of moves returned.
+// count: maximum number of moves returned (required, unless auditing).
 // (note: equal moves are not stably sorted;
 //  different counts may tie-break the last move differently.)
+// audit: if true, instead list every word on the board, whether it is valid,
+// and which play of history (plays as returned, oldest first) introduced it.
 #[derive(serde::Deserialize)]
 struct Question {
     lexicon: String,
@@ -20,7 +22,11 @@ struct Question {
     #[serde(rename = "board")]
     board_tiles: Vec<Vec<i8>>,
     #[serde(rename = "count")]
-    max_gen: usize,
+    max_gen: Option<usize>,
+    #[serde(default)]
+    audit: bool,
+    #[serde(default)]
+    history: Vec<kibitzer::JsonPlay>,
 }
 
 fn main() -> error::Returns<()> {
@@ -155,38 +161,63 @@ fn main() -> error::Returns<()> {
       ..A..O..E..p..e...I..
       .AR.ONY.DI.HO.PHO.AT.
     */
-    let question = serde_json::from_str::<Question>(data)?;
+    // or a question from a file, such as an audit.
+    let data = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => data.to_string(),
+    };
+    let question = serde_json::from_str::<Question>(&data)?;
 
     // of course this should be cached
     let (kwg, klv, game_config) = match question.lexicon.as_str() {
         "CSW24" => (
-            kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/CSW24.kwg")?),
-            klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/CSW24.klv2")?),
+            kwg::Kwg::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/CSW24.kwg")?.leak(),
+            ),
+            klv::Klv::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/CSW24.klv2")?.leak(),
+            ),
             game_config::make_english_game_config(),
         ),
         "super-CSW24" => (
-            kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/CSW24.kwg")?),
-            klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/super-CSW24.klv2")?),
+            kwg::Kwg::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/CSW24.kwg")?.leak(),
+            ),
+            klv::Klv::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/super-CSW24.klv2")?.leak(),
+            ),
             game_config::make_super_english_game_config(),
         ),
         "NWL23" => (
-            kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/NWL23.kwg")?),
-            klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/NWL23.klv2")?),
+            kwg::Kwg::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/NWL23.kwg")?.leak(),
+            ),
+            klv::Klv::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/NWL23.klv2")?.leak(),
+            ),
             game_config::make_english_game_config(),
         ),
         "super-NWL23" => (
-            kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/NWL23.kwg")?),
-            klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/super-NWL23.klv2")?),
+            kwg::Kwg::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/NWL23.kwg")?.leak(),
+            ),
+            klv::Klv::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/super-NWL23.klv2")?.leak(),
+            ),
             game_config::make_english_game_config(),
         ),
         "ECWL" => (
-            kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/ECWL.kwg")?),
-            klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/ECWL.klv2")?),
+            kwg::Kwg::<kwg::Node22>::from_bytes(kwg::AlignedBytes::read("lexbin/ECWL.kwg")?.leak()),
+            klv::Klv::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/ECWL.klv2")?.leak(),
+            ),
             game_config::make_english_game_config(),
         ),
         "super-ECWL" => (
-            kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/ECWL.kwg")?),
-            klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/super-ECWL.klv2")?),
+            kwg::Kwg::<kwg::Node22>::from_bytes(kwg::AlignedBytes::read("lexbin/ECWL.kwg")?.leak()),
+            klv::Klv::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/super-ECWL.klv2")?.leak(),
+            ),
             game_config::make_english_game_config(),
         ),
         _ => {
@@ -194,6 +225,15 @@ fn main() -> error::Returns<()> {
         }
     };
 
+    // opt-in rules file (WOLGES_RULES_FILE=<file.json>) in place of the
+    // lexicon's usual game config.
+    let rules_config = std::env::var("WOLGES_RULES_FILE")
+        .ok()
+        .filter(|x| !x.is_empty())
+        .map(|path| game_config::load_game_config_from_rules_file(&path))
+        .transpose()?;
+    let game_config = rules_config.unwrap_or(game_config);
+
     let mut rng = rand::rngs::ChaCha20Rng::try_from_rng(&mut rand::rngs::SysRng)?;
     let mut game_state = game_state::GameState::new(&game_config);
     // temp hardcode
@@ -232,6 +272,21 @@ fn main() -> error::Returns<()> {
         klv: &klv,
     };
 
+    if question.audit {
+        let history = question
+            .history
+            .iter()
+            .map(|play| play.into())
+            .collect::<Vec<movegen::Play>>();
+        let result = play_scorer::PlayScorer::new()
+            .audit_board(board_snapshot, &history)?
+            .iter()
+            .map(|audited_word| audited_word.into())
+            .collect::<Vec<kibitzer::JsonAuditedWord>>();
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
     if false {
         for &tile in &question.rack {
             game_state.players[0].rack.push(tile);
@@ -250,10 +305,13 @@ fn main() -> error::Returns<()> {
         }
     }
 
+    let Some(max_gen) = question.max_gen.filter(|&max_gen| max_gen > 0) else {
+        wolges::return_error!("count must be a positive number of moves".into());
+    };
     move_generator.gen_moves_unfiltered(&movegen::GenMovesParams {
         board_snapshot,
         rack: &question.rack,
-        max_gen: question.max_gen,
+        max_gen,
         num_exchanges_by_this_player: game_state.current_player().num_exchanges,
         always_include_pass: false,
         dynamic_leaves: None,
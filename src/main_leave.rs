@@ -5,8 +5,8 @@ use std::fmt::Write;
 use std::io::Write as _;
 use std::str::FromStr;
 use wolges::{
-    alphabet, bites, build, census, display, equity, error, fash, game_config, game_state, klv,
-    kwg, move_filter, move_picker, movegen, play_scorer, prob, simmer, stats, win_pct,
+    alphabet, bites, build, census, display, equity, error, fash, game_config, game_state, gcg,
+    klv, kwg, move_filter, move_picker, movegen, play_scorer, prob, simmer, stats, win_pct,
 };
 
 static BASE62: &[u8; 62] = b"\
@@ -101,13 +101,6 @@ fn make_reader(filename: &str) -> Result<Box<dyn std::io::Read>, std::io::Error>
     })
 }
 
-// slower than std::fs::read because it cannot preallocate the correct size.
-fn read_to_end(reader: &mut Box<dyn std::io::Read>) -> Result<Vec<u8>, std::io::Error> {
-    let mut v = Vec::new();
-    reader.read_to_end(&mut v)?;
-    Ok(v)
-}
-
 fn do_lang<GameConfigMaker: Fn() -> game_config::GameConfig>(
     args: &[String],
     language_name: &str,
@@ -149,16 +142,17 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                 } else {
                     None
                 };
-                let kwg =
-                    kwg::Kwg::<N>::from_bytes_alloc(&read_to_end(&mut make_reader(&args[2])?)?);
+                let kwg = kwg::Kwg::<N>::from_bytes(
+                    kwg::AlignedBytes::read_from(&mut make_reader(&args[2])?)?.leak(),
+                );
                 let arc_klv0 = if args3 == "-" {
                     std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(
                         klv::EMPTY_KLV_BYTES,
                     ))
                 } else {
-                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read(
-                        args3,
-                    )?))
+                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes(
+                        kwg::AlignedBytes::read(args3)?.leak(),
+                    ))
                 };
                 let arc_klv1 = if args3 == args4 {
                     std::sync::Arc::clone(&arc_klv0)
@@ -167,9 +161,9 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                         klv::EMPTY_KLV_BYTES,
                     ))
                 } else {
-                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read(
-                        args4,
-                    )?))
+                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes(
+                        kwg::AlignedBytes::read(args4)?.leak(),
+                    ))
                 };
                 generate_autoplay_logs::<true, false, _, _>(
                     make_game_config(),
@@ -200,16 +194,17 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                 } else {
                     None
                 };
-                let kwg =
-                    kwg::Kwg::<N>::from_bytes_alloc(&read_to_end(&mut make_reader(&args[2])?)?);
+                let kwg = kwg::Kwg::<N>::from_bytes(
+                    kwg::AlignedBytes::read_from(&mut make_reader(&args[2])?)?.leak(),
+                );
                 let arc_klv0 = if args3 == "-" {
                     std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(
                         klv::EMPTY_KLV_BYTES,
                     ))
                 } else {
-                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read(
-                        args3,
-                    )?))
+                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes(
+                        kwg::AlignedBytes::read(args3)?.leak(),
+                    ))
                 };
                 let arc_klv1 = if args3 == args4 {
                     std::sync::Arc::clone(&arc_klv0)
@@ -218,9 +213,9 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                         klv::EMPTY_KLV_BYTES,
                     ))
                 } else {
-                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read(
-                        args4,
-                    )?))
+                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes(
+                        kwg::AlignedBytes::read(args4)?.leak(),
+                    ))
                 };
                 generate_autoplay_logs::<true, true, _, _>(
                     make_game_config(),
@@ -251,16 +246,17 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                 } else {
                     None
                 };
-                let kwg =
-                    kwg::Kwg::<N>::from_bytes_alloc(&read_to_end(&mut make_reader(&args[2])?)?);
+                let kwg = kwg::Kwg::<N>::from_bytes(
+                    kwg::AlignedBytes::read_from(&mut make_reader(&args[2])?)?.leak(),
+                );
                 let arc_klv0 = if args3 == "-" {
                     std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(
                         klv::EMPTY_KLV_BYTES,
                     ))
                 } else {
-                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read(
-                        args3,
-                    )?))
+                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes(
+                        kwg::AlignedBytes::read(args3)?.leak(),
+                    ))
                 };
                 let arc_klv1 = if args3 == args4 {
                     std::sync::Arc::clone(&arc_klv0)
@@ -269,9 +265,9 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                         klv::EMPTY_KLV_BYTES,
                     ))
                 } else {
-                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read(
-                        args4,
-                    )?))
+                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes(
+                        kwg::AlignedBytes::read(args4)?.leak(),
+                    ))
                 };
                 generate_autoplay_logs::<false, true, _, _>(
                     make_game_config(),
@@ -303,16 +299,17 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                 } else {
                     None
                 };
-                let kwg =
-                    kwg::Kwg::<N>::from_bytes_alloc(&read_to_end(&mut make_reader(&args[2])?)?);
+                let kwg = kwg::Kwg::<N>::from_bytes(
+                    kwg::AlignedBytes::read_from(&mut make_reader(&args[2])?)?.leak(),
+                );
                 let arc_klv0 = if args3 == "-" {
                     std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(
                         klv::EMPTY_KLV_BYTES,
                     ))
                 } else {
-                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read(
-                        args3,
-                    )?))
+                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes(
+                        kwg::AlignedBytes::read(args3)?.leak(),
+                    ))
                 };
                 let arc_klv1 = if args3 == args4 {
                     std::sync::Arc::clone(&arc_klv0)
@@ -321,9 +318,9 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                         klv::EMPTY_KLV_BYTES,
                     ))
                 } else {
-                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read(
-                        args4,
-                    )?))
+                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes(
+                        kwg::AlignedBytes::read(args4)?.leak(),
+                    ))
                 };
                 generate_gilles_summary(
                     make_game_config(),
@@ -353,16 +350,17 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                 } else {
                     None
                 };
-                let kwg =
-                    kwg::Kwg::<N>::from_bytes_alloc(&read_to_end(&mut make_reader(&args[2])?)?);
+                let kwg = kwg::Kwg::<N>::from_bytes(
+                    kwg::AlignedBytes::read_from(&mut make_reader(&args[2])?)?.leak(),
+                );
                 let arc_klv0 = if args3 == "-" {
                     std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(
                         klv::EMPTY_KLV_BYTES,
                     ))
                 } else {
-                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read(
-                        args3,
-                    )?))
+                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes(
+                        kwg::AlignedBytes::read(args3)?.leak(),
+                    ))
                 };
                 let arc_klv1 = if args3 == args4 {
                     std::sync::Arc::clone(&arc_klv0)
@@ -371,9 +369,9 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                         klv::EMPTY_KLV_BYTES,
                     ))
                 } else {
-                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read(
-                        args4,
-                    )?))
+                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes(
+                        kwg::AlignedBytes::read(args4)?.leak(),
+                    ))
                 };
                 generate_census_leaves(
                     make_game_config(),
@@ -399,16 +397,17 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                 } else {
                     None
                 };
-                let kwg =
-                    kwg::Kwg::<N>::from_bytes_alloc(&read_to_end(&mut make_reader(&args[2])?)?);
+                let kwg = kwg::Kwg::<N>::from_bytes(
+                    kwg::AlignedBytes::read_from(&mut make_reader(&args[2])?)?.leak(),
+                );
                 let arc_klv0 = if args3 == "-" {
                     std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(
                         klv::EMPTY_KLV_BYTES,
                     ))
                 } else {
-                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read(
-                        args3,
-                    )?))
+                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes(
+                        kwg::AlignedBytes::read(args3)?.leak(),
+                    ))
                 };
                 let arc_klv1 = if args3 == args4 {
                     std::sync::Arc::clone(&arc_klv0)
@@ -417,9 +416,9 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                         klv::EMPTY_KLV_BYTES,
                     ))
                 } else {
-                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read(
-                        args4,
-                    )?))
+                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes(
+                        kwg::AlignedBytes::read(args4)?.leak(),
+                    ))
                 };
                 compare_leaves(
                     make_game_config(),
@@ -439,9 +438,9 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                 // and a shared budget WOLGES_SIM_ITERS. Give both seats the same
                 // config to confirm the harness is unbiased (about 50%).
                 let arc_klv = if args.len() > 3 && args[3] != "-" {
-                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read(
-                        &args[3],
-                    )?))
+                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes(
+                        kwg::AlignedBytes::read(&args[3])?.leak(),
+                    ))
                 } else {
                     std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(
                         klv::EMPTY_KLV_BYTES,
@@ -457,8 +456,9 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                 } else {
                     None
                 };
-                let kwg =
-                    kwg::Kwg::<N>::from_bytes_alloc(&read_to_end(&mut make_reader(&args[2])?)?);
+                let kwg = kwg::Kwg::<N>::from_bytes(
+                    kwg::AlignedBytes::read_from(&mut make_reader(&args[2])?)?.leak(),
+                );
                 sim_compare(make_game_config(), kwg, arc_klv, num_game_pairs, seed)?;
                 Ok(true)
             }
@@ -469,7 +469,7 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                 // into begin_decision + resume, proving resume continues the
                 // rollout stream without repeating or dropping iterations.
                 let klv = if args.len() > 3 && args[3] != "-" {
-                    klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read(&args[3])?)
+                    klv::Klv::<kwg::Node22>::from_bytes(kwg::AlignedBytes::read(&args[3])?.leak())
                 } else {
                     klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES)
                 };
@@ -483,8 +483,9 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                 } else {
                     1
                 };
-                let kwg =
-                    kwg::Kwg::<N>::from_bytes_alloc(&read_to_end(&mut make_reader(&args[2])?)?);
+                let kwg = kwg::Kwg::<N>::from_bytes(
+                    kwg::AlignedBytes::read_from(&mut make_reader(&args[2])?)?.leak(),
+                );
                 let game_config = make_game_config();
                 let mut rng = rand::rngs::ChaCha20Rng::seed_from_u64(seed);
                 let mut game_state = game_state::GameState::new(&game_config);
@@ -538,7 +539,7 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                 // and running more iterations must keep its earlier statistics
                 // (its sample count only grows, never resets).
                 let klv = if args.len() > 3 && args[3] != "-" {
-                    klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read(&args[3])?)
+                    klv::Klv::<kwg::Node22>::from_bytes(kwg::AlignedBytes::read(&args[3])?.leak())
                 } else {
                     klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES)
                 };
@@ -552,8 +553,9 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                 } else {
                     1
                 };
-                let kwg =
-                    kwg::Kwg::<N>::from_bytes_alloc(&read_to_end(&mut make_reader(&args[2])?)?);
+                let kwg = kwg::Kwg::<N>::from_bytes(
+                    kwg::AlignedBytes::read_from(&mut make_reader(&args[2])?)?.leak(),
+                );
                 let game_config = make_game_config();
                 let mut rng = rand::rngs::ChaCha20Rng::seed_from_u64(seed);
                 let mut game_state = game_state::GameState::new(&game_config);
@@ -614,16 +616,17 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                 } else {
                     None
                 };
-                let kwg =
-                    kwg::Kwg::<N>::from_bytes_alloc(&read_to_end(&mut make_reader(&args[2])?)?);
+                let kwg = kwg::Kwg::<N>::from_bytes(
+                    kwg::AlignedBytes::read_from(&mut make_reader(&args[2])?)?.leak(),
+                );
                 let arc_klv = if args3 == "-" {
                     std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(
                         klv::EMPTY_KLV_BYTES,
                     ))
                 } else {
-                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read(
-                        args3,
-                    )?))
+                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes(
+                        kwg::AlignedBytes::read(args3)?.leak(),
+                    ))
                 };
                 generate_rollout_leaves(make_game_config(), kwg, arc_klv, num_games, seed)?;
                 Ok(true)
@@ -643,16 +646,17 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                 } else {
                     None
                 };
-                let kwg =
-                    kwg::Kwg::<N>::from_bytes_alloc(&read_to_end(&mut make_reader(&args[2])?)?);
+                let kwg = kwg::Kwg::<N>::from_bytes(
+                    kwg::AlignedBytes::read_from(&mut make_reader(&args[2])?)?.leak(),
+                );
                 let arc_klv = if args3 == "-" {
                     std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(
                         klv::EMPTY_KLV_BYTES,
                     ))
                 } else {
-                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read(
-                        args3,
-                    )?))
+                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes(
+                        kwg::AlignedBytes::read(args3)?.leak(),
+                    ))
                 };
                 generate_winpct_table(make_game_config(), kwg, arc_klv, num_games, seed)?;
                 Ok(true)
@@ -671,16 +675,17 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                 } else {
                     None
                 };
-                let kwg =
-                    kwg::Kwg::<N>::from_bytes_alloc(&read_to_end(&mut make_reader(&args[2])?)?);
+                let kwg = kwg::Kwg::<N>::from_bytes(
+                    kwg::AlignedBytes::read_from(&mut make_reader(&args[2])?)?.leak(),
+                );
                 let arc_klv = if args3 == "-" {
                     std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(
                         klv::EMPTY_KLV_BYTES,
                     ))
                 } else {
-                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read(
-                        args3,
-                    )?))
+                    std::sync::Arc::new(klv::Klv::<kwg::Node22>::from_bytes(
+                        kwg::AlignedBytes::read(args3)?.leak(),
+                    ))
                 };
                 let table = win_pct::WinPctTable::from_csv(&std::fs::read_to_string(&args[4])?)?;
                 generate_winpct_eval(make_game_config(), kwg, arc_klv, table, num_games, seed)?;
@@ -779,12 +784,13 @@ fn do_lang_kwg<GameConfigMaker: Fn() -> game_config::GameConfig, N: kwg::Node +
                 } else {
                     None
                 };
-                let kwg =
-                    kwg::Kwg::<N>::from_bytes_alloc(&read_to_end(&mut make_reader(&args[2])?)?);
+                let kwg = kwg::Kwg::<N>::from_bytes(
+                    kwg::AlignedBytes::read_from(&mut make_reader(&args[2])?)?.leak(),
+                );
                 let klv = if args3 == "-" {
                     klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES)
                 } else {
-                    klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read(args3)?)
+                    klv::Klv::<kwg::Node22>::from_bytes(kwg::AlignedBytes::read(args3)?.leak())
                 };
                 discover_playability(make_game_config(), kwg, klv, num_games, seed)?;
                 Ok(true)
@@ -869,11 +875,13 @@ fn main() -> error::Returns<()> {
     if klv is \"-\" or omitted, uses no leave.
     number of game pairs is optional (default 10000).
     seed is optional; prints auto-generated seed to stderr if not provided.
+    WOLGES_GCG_DIR=dir writes every game to dir/pair-<n>-game-<g>.gcg.
   english-sim-compare CSW24.kwg leaves.klv2 1000 [seed]
     play game pairs where both seats choose moves by the 2-ply simmer,
     each seat configured by WOLGES_SIM_P0_* / WOLGES_SIM_P1_* (and a shared
     WOLGES_SIM_ITERS budget), to A/B simmer configurations.
     each pair: same tile draw, alternating starting player.
+    WOLGES_GCG_DIR=dir writes every game to dir/pair-<n>-game-<g>.gcg.
   english-sim-study-check CSW24.kwg leaves.klv2 64 [seed]
     self-check that a resumed decision (begin_decision then resume) matches
     the same decision run in one call; prints SIM_RESUME_OK on success.
@@ -886,6 +894,8 @@ fn main() -> error::Returns<()> {
   jumbled-english-autoplay CSW24.kad leave0.klv leave1.klv 1000
     (all also take jumbled- prefix, including jumbled-super-;
     note that jumbled autoplay requires .kad instead of .kwg)
+  (english can also be rules, with an extra .json rules file argument:
+    rules-autoplay club.json CSW24.kwg leave0.klv leave1.klv 1000)
 input/output files can be \"-\" (not advisable for binary files).
 for english-autoplay only the kwg can come from \"-\".
 when low disk space, note that in bash:
@@ -993,6 +1003,17 @@ when low disk space, note that in bash:
                 game_config::make_jumbled_swedish_game_config,
             )?
         {
+        } else if args[1].starts_with("rules-") {
+            if args.len() < 3 {
+                return Err("need rules file".into());
+            }
+            // loaded once, so a bad rules file fails before anything runs.
+            let game_config = game_config::load_game_config_from_rules_file(&args[2])?;
+            let mut shifted_args = vec![args[0].clone(), args[1].clone()];
+            shifted_args.extend_from_slice(&args[3..]);
+            if !do_lang(&shifted_args, "rules", || game_config.clone())? {
+                return Err("invalid argument".into());
+            }
         } else {
             return Err("invalid argument".into());
         }
@@ -4492,8 +4513,8 @@ const fn census_mix64(mut z: u64) -> u64 {
 // context; SpellPool is the unseen pool and its per-letter rack caps.
 struct SpellTables<'a, N: kwg::Node, L: kwg::Node> {
     game_config: &'a game_config::GameConfig,
-    kwg: &'a kwg::Kwg<N>,
-    klv: &'a klv::Klv<L>,
+    kwg: &'a kwg::Kwg<'a, N>,
+    klv: &'a klv::Klv<'a, L>,
     lat: &'a census::MultisetLattice,
 }
 
@@ -5470,8 +5491,8 @@ fn generate_census_leaves<N: kwg::Node + Sync + Send, L: kwg::Node + Sync + Send
         }
     }
     if let Some((rid, num, path)) = resumed {
-        let bytes = std::fs::read(&path)?;
-        let resume_klv = klv::Klv::<L>::from_bytes_alloc(&bytes);
+        let bytes = kwg::AlignedBytes::read(&path)?;
+        let resume_klv = klv::Klv::<L>::from_bytes(&bytes);
         for (idx, slot) in leave_cur.iter_mut().enumerate() {
             lat.unrank_into(idx, &mut tally_buf);
             *slot = resume_klv.leave_value_from_tally(&tally_buf);
@@ -8577,8 +8598,23 @@ fn generate_rollout_leaves<N: kwg::Node + Sync + Send, L: kwg::Node + Sync + Sen
 // winpct_play_game stays within clippy::too_many_arguments.
 struct WinpctTables<'a, N: kwg::Node, L: kwg::Node> {
     game_config: &'a game_config::GameConfig,
-    kwg: &'a kwg::Kwg<N>,
-    arc_klv: &'a klv::Klv<L>,
+    kwg: &'a kwg::Kwg<'a, N>,
+    arc_klv: &'a klv::Klv<'a, L>,
+}
+
+// the mover's lead in points over the best opponent.
+fn winpct_lead(mover: usize, scores: impl Iterator<Item = i32>) -> i32 {
+    let mut mover_score = 0;
+    let mut best_opponent_score = i32::MIN;
+    for (i, score) in scores.enumerate() {
+        let score = equity::descale_score(score);
+        if i == mover {
+            mover_score = score;
+        } else {
+            best_opponent_score = best_opponent_score.max(score);
+        }
+    }
+    mover_score - best_opponent_score
 }
 
 fn winpct_play_game<N: kwg::Node, L: kwg::Node>(
@@ -8597,13 +8633,19 @@ fn winpct_play_game<N: kwg::Node, L: kwg::Node>(
     snapshots.clear();
     loop {
         let mover = game_state.turn as usize;
-        let other = 1 - mover;
-        let lead = equity::descale_score(game_state.players[mover].score)
-            - equity::descale_score(game_state.players[other].score);
+        // with more than two players, against the best (and all) opponents.
+        let lead = winpct_lead(mover, game_state.players.iter().map(|player| player.score));
+        let opp = game_state
+            .players
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != mover)
+            .map(|(_, player)| player.rack.len())
+            .sum();
         snapshots.push((
             game_state.bag.len(),
             game_state.players[mover].rack.len(),
-            game_state.players[other].rack.len(),
+            opp,
             mover,
             lead,
         ));
@@ -8687,9 +8729,8 @@ fn generate_winpct_table<N: kwg::Node + Sync + Send, L: kwg::Node + Sync + Send>
                     );
                     // fold each snapshot's future swing, from its own mover's view.
                     for &(bag, my, opp, mover, lead) in &snapshots {
-                        let mover_final = equity::descale_score(final_scores[mover])
-                            - equity::descale_score(final_scores[1 - mover]);
-                        acc.record(bag, my, opp, lead, mover_final);
+                        let mover_final = winpct_lead(mover, final_scores.iter().copied());
+                        acc.record_n_player(final_scores.len(), bag, my, opp, lead, mover_final);
                     }
                     if (g + 1).is_multiple_of(report_every) {
                         eprintln!("winpct: {} games", g + 1);
@@ -8803,6 +8844,25 @@ fn generate_winpct_eval<N: kwg::Node + Sync + Send, L: kwg::Node + Sync + Send>(
     Ok(())
 }
 
+// the opt-in game recorder for -compare and -sim-compare (WOLGES_GCG_DIR=<dir>).
+// the directory is created up front so a bad path fails before any game is played.
+fn wolges_gcg_dir() -> error::Returns<Option<String>> {
+    let gcg_dir = env_path("WOLGES_GCG_DIR");
+    if let Some(gcg_dir) = &gcg_dir {
+        std::fs::create_dir_all(gcg_dir)?;
+    }
+    Ok(gcg_dir)
+}
+
+// writes one game of a pair to <dir>/pair-<n>-game-<g>.gcg. this runs on a
+// worker thread, so a failed write is logged and the comparison goes on.
+fn write_compare_gcg(gcg_dir: &str, pair_idx: u64, game_in_pair: u8, gcg_writer: &gcg::GcgWriter) {
+    let path = format!("{gcg_dir}/pair-{pair_idx}-game-{game_in_pair}.gcg");
+    if let Err(e) = std::fs::write(&path, gcg_writer.as_str()) {
+        eprintln!("gcg: writing {path} failed: {e}");
+    }
+}
+
 fn compare_leaves<N: kwg::Node + Sync + Send, L: kwg::Node + Sync + Send>(
     game_config: game_config::GameConfig,
     kwg: kwg::Kwg<N>,
@@ -8819,6 +8879,8 @@ fn compare_leaves<N: kwg::Node + Sync + Send, L: kwg::Node + Sync + Send>(
     let completed_pairs = std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0));
     let reported_secs = std::sync::atomic::AtomicU64::new(0);
     let t0 = std::time::Instant::now();
+    let gcg_dir = wolges_gcg_dir()?;
+    let gcg_dir = gcg_dir.as_deref();
 
     // Dynamic leaves A/B knob. Off (the default) => byte-identical to before.
     // When on, only the klv0 side's midgame turns reweight their leaves by the
@@ -8915,6 +8977,13 @@ fn compare_leaves<N: kwg::Node + Sync + Send, L: kwg::Node + Sync + Send>(
                         if !klv_swapped {
                             first_game_moves.clear();
                         }
+                        let mut gcg_writer = gcg_dir.map(|_| {
+                            gcg::GcgWriter::new(if klv_swapped {
+                                &["klv1", "klv0"]
+                            } else {
+                                &["klv0", "klv1"]
+                            })
+                        });
 
                         let end_reason = loop {
                             // The klv0 player uses arc_klv0 (swap-corrected across the
@@ -8948,9 +9017,20 @@ fn compare_leaves<N: kwg::Node + Sync + Send, L: kwg::Node + Sync + Send>(
                             } else {
                                 first_game_moves.push(play.clone());
                             }
+                            if let Some(gcg_writer) = &mut gcg_writer {
+                                gcg_writer.record_play(
+                                    &game_config,
+                                    game_state.turn,
+                                    &game_state.current_player().rack,
+                                    play,
+                                );
+                            }
                             game_state.play(&game_config, &mut rng, play).unwrap();
                             num_turns += 1;
                             let end = game_state.check_game_ended(&game_config, &mut final_scores);
+                            if let Some(gcg_writer) = &mut gcg_writer {
+                                gcg_writer.record_game_end(&game_config, &game_state, end);
+                            }
                             match end {
                                 game_state::CheckGameEnded::PlayedOut
                                 | game_state::CheckGameEnded::ZeroScores => break end,
@@ -8966,6 +9046,9 @@ fn compare_leaves<N: kwg::Node + Sync + Send, L: kwg::Node + Sync + Send>(
                         };
                         pair_results[game_in_pair as usize] =
                             (klv0_score, klv1_score, num_turns, end_reason);
+                        if let (Some(gcg_dir), Some(gcg_writer)) = (gcg_dir, &gcg_writer) {
+                            write_compare_gcg(gcg_dir, pair_idx, game_in_pair, gcg_writer);
+                        }
                     }
                     // also check if game 1 ended at a different turn
                     if !pair_diverged && pair_results[0].2 != pair_results[1].2 {
@@ -9101,6 +9184,8 @@ fn sim_compare<N: kwg::Node + Sync + Send, L: kwg::Node + Sync + Send>(
     let completed_pairs = std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0));
     let reported_secs = std::sync::atomic::AtomicU64::new(0);
     let t0 = std::time::Instant::now();
+    let gcg_dir = wolges_gcg_dir()?;
+    let gcg_dir = gcg_dir.as_deref();
 
     // Fixed per-move rollout budget (no wall clock), shared by both seats so a
     // config difference is the only asymmetry. Each seat's objective and
@@ -9230,6 +9315,13 @@ fn sim_compare<N: kwg::Node + Sync + Send, L: kwg::Node + Sync + Send>(
                         if !seat_swapped {
                             first_game_moves.clear();
                         }
+                        let mut gcg_writer = gcg_dir.map(|_| {
+                            gcg::GcgWriter::new(if seat_swapped {
+                                &["P1", "P0"]
+                            } else {
+                                &["P0", "P1"]
+                            })
+                        });
 
                         let end_reason = loop {
                             // The player-0 seat uses config_p0 (swap-corrected
@@ -9278,9 +9370,20 @@ fn sim_compare<N: kwg::Node + Sync + Send, L: kwg::Node + Sync + Send>(
                             } else {
                                 first_game_moves.push(play.clone());
                             }
+                            if let Some(gcg_writer) = &mut gcg_writer {
+                                gcg_writer.record_play(
+                                    &game_config,
+                                    game_state.turn,
+                                    &game_state.current_player().rack,
+                                    play,
+                                );
+                            }
                             game_state.play(&game_config, &mut rng, play).unwrap();
                             num_turns += 1;
                             let end = game_state.check_game_ended(&game_config, &mut final_scores);
+                            if let Some(gcg_writer) = &mut gcg_writer {
+                                gcg_writer.record_game_end(&game_config, &game_state, end);
+                            }
                             match end {
                                 game_state::CheckGameEnded::PlayedOut
                                 | game_state::CheckGameEnded::ZeroScores => break end,
@@ -9296,6 +9399,9 @@ fn sim_compare<N: kwg::Node + Sync + Send, L: kwg::Node + Sync + Send>(
                         };
                         pair_results[game_in_pair as usize] =
                             (p0_score, p1_score, num_turns, end_reason);
+                        if let (Some(gcg_dir), Some(gcg_writer)) = (gcg_dir, &gcg_writer) {
+                            write_compare_gcg(gcg_dir, pair_idx, game_in_pair, gcg_writer);
+                        }
                     }
                     if !pair_diverged && pair_results[0].2 != pair_results[1].2 {
                         pair_diverged = true;
@@ -6,7 +6,7 @@ use wolges::{alphabet, bites, error, fash, game_config, kwg};
 fn print_dawg<N: kwg::Node>(a: &alphabet::Alphabet, g: &kwg::Kwg<N>) {
     struct Env<'a, N: kwg::Node> {
         a: &'a alphabet::Alphabet,
-        g: &'a kwg::Kwg<N>,
+        g: &'a kwg::Kwg<'a, N>,
         s: &'a mut String,
     }
     fn iter<N: kwg::Node>(env: &mut Env<'_, N>, mut p: i32) {
@@ -98,7 +98,7 @@ struct EmbeddedWordsFinder {
 
 struct FindEmbeddedWordParams<'a, M: Fn(usize) -> i8, F: FnMut(&[u8], i8), N: kwg::Node> {
     board: &'a [u8],
-    kwg: &'a kwg::Kwg<N>,
+    kwg: &'a kwg::Kwg<'a, N>,
     get_multiplier_at: &'a M,
     record_finding: &'a mut F,
 }
@@ -308,16 +308,28 @@ fn test_find_embedded_words<'a, N: kwg::Node>(
 }
 
 fn main() -> error::Returns<()> {
+    // opt-in rules file (WOLGES_RULES_FILE=<file.json>) in place of the
+    // built-in game config.
+    let rules_config = std::env::var("WOLGES_RULES_FILE")
+        .ok()
+        .filter(|x| !x.is_empty())
+        .map(|path| game_config::load_game_config_from_rules_file(&path))
+        .transpose()?;
+    let game_config = &rules_config.unwrap_or_else(game_config::make_english_game_config);
     if false {
-        let kwg = kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/CSW24.kwg")?);
-        print_dawg(&alphabet::make_english_alphabet(), &kwg);
+        let kwg = kwg::Kwg::<kwg::Node22>::from_bytes(
+            kwg::AlignedBytes::read("lexbin/CSW24.kwg")?.leak(),
+        );
+        print_dawg(game_config.alphabet(), &kwg);
         return Ok(());
     }
-    let kwg = kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/CSW24.kwg")?);
+    let kwg =
+        kwg::Kwg::<kwg::Node22>::from_bytes(kwg::AlignedBytes::read("lexbin/CSW24.kwg")?.leak());
     if true {
-        let alphabet = alphabet::make_english_alphabet();
-        let nwl23_kwg =
-            kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/NWL23.kwg")?);
+        let alphabet = game_config.alphabet();
+        let nwl23_kwg = kwg::Kwg::<kwg::Node22>::from_bytes(
+            kwg::AlignedBytes::read("lexbin/NWL23.kwg")?.leak(),
+        );
         let known_boards = [
             &[
                 1, 1, 1, 1, //
@@ -351,14 +363,14 @@ fn main() -> error::Returns<()> {
             ][..],
         ];
         test_find_embedded_words(
-            &alphabet,
+            alphabet,
             &nwl23_kwg, // actually nwl18
             ["LIASERTAPGADID##KEMA##IRAIVZQAEFEGSY"],
             Some(known_boards[3]),
         )?;
-        test_find_embedded_words(&alphabet, &nwl23_kwg, ["LIASERTAIDKEMAIR"], None)?; // actually twl14
+        test_find_embedded_words(alphabet, &nwl23_kwg, ["LIASERTAIDKEMAIR"], None)?; // actually twl14
         test_find_embedded_words(
-            &alphabet,
+            alphabet,
             &kwg,
             "
 NQALBRYUDAMEWPAI
@@ -400,8 +412,6 @@ TABCEEAWNREEOSRLLDGN####F#UN#OIAESTYNKACEPIHUEIST#AZRET#IDTYMUOE
         )?;
         return Ok(());
     }
-    let game_config = &game_config::make_english_game_config();
-
     print_dawg(game_config.alphabet(), &kwg);
     let t0 = std::time::Instant::now();
     let word_counts = kwg.count_words_alloc();
@@ -1,8 +1,9 @@
 // Copyright (C) 2020-2026 Andy Kurnia.
 
-use wolges::{alphabet, bites, error, fash, kwg, prob, stats};
+use wolges::{alphabet, bites, error, fash, game_config, kwg, lexicon, prob, stats};
 
 use kwg::{read_le_u16, read_le_u32};
+use lexicon::AnagramMode;
 use std::fmt::Write;
 use std::str::FromStr;
 
@@ -566,11 +567,20 @@ fn do_wg_check<R: WgReader>(
     Ok(())
 }
 
-#[derive(Clone, Copy)]
-enum AnagramMode {
-    Sub,
-    Exact,
-    Super,
+fn parse_rack_tally(alphabet: &alphabet::Alphabet, rack_str: &str) -> error::Returns<Vec<u8>> {
+    let alphabet_reader = &alphabet::AlphabetReader::new_for_racks(alphabet);
+    let mut rack = vec![0; alphabet.len().into()];
+    let sb = rack_str.as_bytes();
+    let mut ix = 0;
+    while ix < sb.len() {
+        if let Some((tile, end_ix)) = alphabet_reader.next_tile(sb, ix) {
+            rack[tile as usize] += 1;
+            ix = end_ix;
+        } else {
+            return Err("invalid tile".into());
+        }
+    }
+    Ok(rack)
 }
 
 fn do_wg_anagram<R: WgReader>(
@@ -579,24 +589,15 @@ fn do_wg_anagram<R: WgReader>(
     reader: &R,
     mode: AnagramMode,
 ) -> error::Returns<()> {
-    let alphabet_reader = &alphabet::AlphabetReader::new_for_racks(alphabet);
+    if args.len() < 5 {
+        return Err("need more argument".into());
+    }
     let kwg_bytes = &read_to_end(&mut make_reader(&args[2])?)?;
     if 0 == reader.len(kwg_bytes) {
         return Err("out of bounds".into());
     }
-    let mut rack = vec![0; alphabet.len().into()];
-    let mut given_num_tiles = 0usize;
-    let sb = &args[4].as_bytes();
-    let mut ix = 0;
-    while ix < sb.len() {
-        if let Some((tile, end_ix)) = alphabet_reader.next_tile(sb, ix) {
-            rack[tile as usize] += 1;
-            given_num_tiles += 1;
-            ix = end_ix;
-        } else {
-            return Err("invalid tile".into());
-        }
-    }
+    let rack = parse_rack_tally(alphabet, &args[4])?;
+    let given_num_tiles = rack.iter().map(|&count| count as usize).sum::<usize>();
     let rack_cell = std::cell::RefCell::new(rack);
     let num_tiles = std::sync::atomic::AtomicUsize::new(0);
     let mut ret = String::new();
@@ -663,6 +664,105 @@ fn do_wg_anagram<R: WgReader>(
     Ok(())
 }
 
+fn do_kwg_pattern<N: kwg::Node>(
+    args: &[String],
+    alphabet: &alphabet::Alphabet,
+) -> error::Returns<()> {
+    if args.len() < 5 {
+        return Err("need more argument".into());
+    }
+    let kwg = kwg::Kwg::<N>::from_bytes_alloc(&read_to_end(&mut make_reader(&args[2])?)?);
+    if kwg.0.is_empty() {
+        return Err("out of bounds".into());
+    }
+    let mut pattern = kwg::Pattern::parse(alphabet, &args[4])?;
+    // after the pattern: a rack, and/or "len" with a length or min-max range.
+    let mut rest = args[5..].iter();
+    while let Some(arg) = rest.next() {
+        if arg == "len" {
+            let len_str = rest.next().ok_or("need length after len")?;
+            let (min_len, max_len) = len_str.split_once('-').unwrap_or((len_str, len_str));
+            pattern.min_len = usize::from_str(min_len).map_err(|_| "invalid length")?;
+            pattern.max_len = usize::from_str(max_len).map_err(|_| "invalid length")?;
+        } else {
+            pattern.rack = Some(parse_rack_tally(alphabet, arg)?);
+        }
+    }
+    let mut ret = String::new();
+    for word in kwg.find_pattern(&pattern) {
+        for &tile in word.iter() {
+            ret.push_str(alphabet.of_board(tile).ok_or("invalid tile")?);
+        }
+        ret.push('\n');
+    }
+    make_writer(&args[3])?.write_all(ret.as_bytes())?;
+    Ok(())
+}
+
+// output format not guaranteed to be stable.
+fn do_kwg_diff<N: kwg::Node>(args: &[String], alphabet: &alphabet::Alphabet) -> error::Returns<()> {
+    if args.len() < 5 {
+        return Err("need more argument".into());
+    }
+    let old_kwg = kwg::Kwg::<N>::from_bytes_alloc(&read_to_end(&mut make_reader(&args[2])?)?);
+    let new_kwg = kwg::Kwg::<N>::from_bytes_alloc(&read_to_end(&mut make_reader(&args[3])?)?);
+    if old_kwg.0.is_empty() || new_kwg.0.is_empty() {
+        return Err("out of bounds".into());
+    }
+    let with_hooks = match args.get(5).map(|s| s.as_str()) {
+        None => false,
+        Some("hooks") => true,
+        Some(_) => return Err("expected hooks".into()),
+    };
+    let mut word_prob = prob::WordProbability::new(alphabet);
+    let mut changes = Vec::new();
+    lexicon::diff(&old_kwg, &new_kwg, |kind, word| {
+        changes.push((
+            word.len(),
+            word_prob.word_draw_ways(word),
+            kind,
+            Box::<[u8]>::from(word),
+        ));
+    });
+    // by length, then by probability, most probable first.
+    changes.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| b.1.cmp(&a.1)));
+    let mut csv_out = csv::Writer::from_writer(make_writer(&args[4])?);
+    let mut word_str = String::new();
+    let mut hooked_str = String::new();
+    for (len, draw_ways, kind, word) in changes {
+        word_str.clear();
+        for &tile in word.iter() {
+            word_str.push_str(alphabet.of_board(tile).ok_or("invalid tile")?);
+        }
+        let change = match kind {
+            lexicon::DiffKind::Added => "+",
+            lexicon::DiffKind::Removed => "-",
+        };
+        if with_hooks {
+            // an added word makes hooks on the words it extends, in the new
+            // lexicon; a removed word took them away from the old one.
+            let kwg_is_word = |w: &[u8]| match kind {
+                lexicon::DiffKind::Added => lexicon::is_word(&new_kwg, w),
+                lexicon::DiffKind::Removed => lexicon::is_word(&old_kwg, w),
+            };
+            let mut hooks = [String::new(), String::new()];
+            for (hook, w) in hooks.iter_mut().zip([&word[1..], &word[..len - 1]]) {
+                if kwg_is_word(w) {
+                    hooked_str.clear();
+                    for &tile in w.iter() {
+                        hooked_str.push_str(alphabet.of_board(tile).ok_or("invalid tile")?);
+                    }
+                    hook.clone_from(&hooked_str);
+                }
+            }
+            csv_out.serialize((change, &word_str, len, draw_ways, &hooks[0], &hooks[1]))?;
+        } else {
+            csv_out.serialize((change, &word_str, len, draw_ways))?;
+        }
+    }
+    Ok(())
+}
+
 fn dump_dawg<R: WgReader, A: AlphabetLabel>(
     args: &[String],
     label: &A,
@@ -1119,6 +1219,24 @@ fn do_lang<AlphabetMaker: Fn() -> alphabet::Alphabet>(
                 }
                 Ok(true)
             }
+            "-kwg-pattern" | "-kbwg-pattern" => {
+                let alphabet = make_alphabet();
+                if args1_suffix == "-kbwg-pattern" {
+                    do_kwg_pattern::<kwg::Node24>(args, &alphabet)?;
+                } else {
+                    do_kwg_pattern::<kwg::Node22>(args, &alphabet)?;
+                }
+                Ok(true)
+            }
+            "-kwg-diff" | "-kbwg-diff" => {
+                let alphabet = make_alphabet();
+                if args1_suffix == "-kbwg-diff" {
+                    do_kwg_diff::<kwg::Node24>(args, &alphabet)?;
+                } else {
+                    do_kwg_diff::<kwg::Node22>(args, &alphabet)?;
+                }
+                Ok(true)
+            }
             "-kwg-check" | "-kbwg-check" => {
                 let alphabet = make_alphabet();
                 if args1_suffix == "-kbwg-check" {
@@ -2707,6 +2825,20 @@ fn main() -> error::Returns<()> {
   english-kbwg-anagram CSW24.kbwg - A?AC
   english-kbwg-anagram+ CSW24.kbwg - A?AC
     list all words with subanagram, anagram, or superanagram (using dawg)
+  english-kwg-pattern CSW24.kwg - 'C?T*'
+  english-kwg-pattern CSW24.kwg - '[AEIOU]*S' A?AC
+  english-kwg-pattern CSW24.kwg - '*QU*' len 8
+  english-kwg-pattern CSW24.kwg - '*S' A?AC len 4-6
+  english-kbwg-pattern CSW24.kbwg - 'C?T*'
+    list all words matching the pattern, optionally formable from the rack
+    and of the given length (or min-max range)
+    (letters are given, ? or . is any tile, [AEIOU] or [^AEIOU] is a class,
+    * is any run of tiles) (using dawg)
+  english-kwg-diff CSW21.kwg CSW24.kwg -
+  english-kwg-diff CSW21.kwg CSW24.kwg - hooks
+  english-kbwg-diff CSW21.kbwg CSW24.kbwg -
+    list words added (+) and removed (-), by length then probability,
+    optionally with the words they front-hook and back-hook (using dawg)
   english-kwg-check CSW24.kwg word [word...]
   english-kbwg-check CSW24.kbwg word [word...]
     checks if all words are valid (using dawg)
@@ -2730,6 +2862,8 @@ fn main() -> error::Returns<()> {
     slovene, spanish, swedish, decimal, hex)
   (english can also be custom, with an extra alphabet file argument:
     custom-kwg alphabet.txt CSW24.kwg CSW24.txt)
+  (english can also be rules, with an extra .json rules file argument
+    whose alphabet is used: rules-kwg club.json CSW24.kwg CSW24.txt)
   klv-kwg-extract CSW24.klv2 racks.kwg
     just copy out the kwg for further analysis.
   kwg-hitcheck CSW24.kwg cls csa ncs outfile
@@ -2802,6 +2936,17 @@ input/output files can be \"-\" (not advisable for binary files)"
             })? {
                 return Err("invalid argument".into());
             }
+        } else if args[1].starts_with("rules-") {
+            if args.len() < 3 {
+                return Err("need rules file".into());
+            }
+            // only the alphabet of the rules file matters for reading.
+            let alphabet = game_config::load_game_config_from_rules_file(&args[2])?.into_alphabet();
+            let mut shifted_args = vec![args[0].clone(), args[1].clone()];
+            shifted_args.extend_from_slice(&args[3..]);
+            if !do_lang(&shifted_args, "rules", || alphabet.clone())? {
+                return Err("invalid argument".into());
+            }
         } else if args[1] == "klv-kwg-extract" {
             let klv_bytes = &read_to_end(&mut make_reader(&args[2])?)?;
             let parts = parse_klv(klv_bytes)?;
@@ -0,0 +1,581 @@
+// Copyright (C) 2020-2026 Andy Kurnia.
+
+use rand::prelude::*;
+use std::io::{BufRead, Read, Write};
+use std::sync::Arc;
+use wolges::{
+    bundle, equity, error, fash, game_config, game_state, kibitzer, klv, kwg, move_picker, movegen,
+    play_scorer,
+};
+
+// a small local HTTP server for analysis. Every endpoint takes a POST with the
+// same JSON shape the json binary parses (tile numbering follows alphabet
+// order; rack: 0 for blank, 1 for A; board: 0 for empty, 1 for A, -1 for
+// blank-as-A) and answers with a JSON list of kibitzer::JsonPlayWithEquity.
+//
+//   /movegen   the top "count" plays by static equity.
+//   /score     "plays" with their scores recounted and equity computed.
+//   /validate  "plays", each canonicalized and recounted, or an error naming
+//              the first invalid play and why.
+//   /sim       the top "count" of "candidates" (default 100) plays after
+//              "iters" sim iterations, equity being the mean sim equity.
+//   /endgame   the principal variation, equity being each play's valuation.
+//              With one tile in the bag, the committed PEG move instead
+//              (empty if the config only allows a clairvoyant bound).
+//
+// errors are answered with status 400 and {"error": "..."}. Word graphs and
+// leaves are loaded once at startup and shared by every connection.
+
+const USAGE: &str = "\
+usage: server <address> <lexicon>:<config>:<kwg-file>[:<klv-file>]...
+  address:  where to listen, e.g. 127.0.0.1:4500
+  lexicon:  the name requests use in \"lexicon\", e.g. CSW24
+  config:   english, catalan, dutch, ... (see endgame), optionally
+            prefixed jumbled- and/or super-, or a .json rules file, and
+            suffixed -big for a .kbwg
+  kwg-file: path to a word graph built for that config, or a lexicon
+            bundle (which needs no -big suffix, and is checked against
+            the config)
+  klv-file: path to a .klv2 leave file (default: the bundle's, or no
+            leaves)";
+
+const DEFAULT_COUNT: usize = 15;
+const DEFAULT_SIM_PLAYS: usize = 100;
+const DEFAULT_SIM_ITERS: u64 = 1000;
+
+// the largest request body accepted, to bound what a client can make us buffer.
+const MAX_BODY_LEN: usize = 1 << 20;
+
+// how long a read may wait, so a client that stops sending does not hold its
+// connection's worker forever.
+const READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+#[derive(serde::Deserialize)]
+struct Question {
+    lexicon: String,
+    rack: Vec<u8>,
+    #[serde(rename = "board")]
+    board_tiles: Vec<Vec<i8>>,
+    #[serde(default)]
+    count: Option<usize>,
+    // for /score and /validate.
+    #[serde(default)]
+    plays: Vec<kibitzer::JsonPlay>,
+    // scores in whole points in turn order, starting with the mover's.
+    #[serde(default)]
+    scores: Vec<i32>,
+    // for /sim: rollouts, rng seed, and how many candidates to sim.
+    #[serde(default)]
+    iters: Option<u64>,
+    #[serde(default)]
+    seed: Option<u64>,
+    #[serde(default)]
+    candidates: Option<usize>,
+}
+
+struct Lexicon<N: kwg::Node> {
+    game_config: game_config::GameConfig,
+    kwg: kwg::Kwg<'static, N>,
+    klv: klv::Klv<'static, kwg::Node22>,
+}
+
+// a lexicon by the node type of its word graph: .kwg or .kbwg.
+enum AnyLexicon {
+    Node22(Lexicon<kwg::Node22>),
+    Node24(Lexicon<kwg::Node24>),
+}
+
+struct Server {
+    lexicons: fash::MyHashMap<String, Arc<AnyLexicon>>,
+}
+
+// one request's worth of analysis state over a shared lexicon.
+struct Analysis<'a, N: kwg::Node> {
+    lexicon: &'a Lexicon<N>,
+    question: &'a Question,
+    kibitzer: kibitzer::Kibitzer,
+    move_generator: movegen::KurniaMoveGenerator,
+}
+
+impl<'a, N: kwg::Node + Sync> Analysis<'a, N> {
+    fn new(lexicon: &'a Lexicon<N>, question: &'a Question) -> error::Returns<Self> {
+        let mut kibitzer = kibitzer::Kibitzer::new();
+        kibitzer.prepare(&lexicon.game_config, &question.rack, &question.board_tiles)?;
+        Ok(Self {
+            lexicon,
+            question,
+            kibitzer,
+            move_generator: movegen::KurniaMoveGenerator::new(&lexicon.game_config),
+        })
+    }
+
+    fn board_snapshot(&self) -> movegen::BoardSnapshot<'_, N, kwg::Node22> {
+        movegen::BoardSnapshot {
+            board_tiles: &self.kibitzer.board_tiles,
+            game_config: &self.lexicon.game_config,
+            kwg: &self.lexicon.kwg,
+            klv: &self.lexicon.klv,
+        }
+    }
+
+    fn game_state(&self, rng: &mut dyn Rng) -> game_state::GameState {
+        self.kibitzer.make_game_state(
+            &self.lexicon.game_config,
+            &self.question.rack,
+            &self.question.scores,
+            rng,
+        )
+    }
+
+    fn gen_moves(&mut self, max_gen: usize) {
+        let board_snapshot = &movegen::BoardSnapshot {
+            board_tiles: &self.kibitzer.board_tiles,
+            game_config: &self.lexicon.game_config,
+            kwg: &self.lexicon.kwg,
+            klv: &self.lexicon.klv,
+        };
+        self.move_generator
+            .gen_moves_unfiltered(&movegen::GenMovesParams {
+                board_snapshot,
+                rack: &self.question.rack,
+                max_gen,
+                num_exchanges_by_this_player: 0,
+                always_include_pass: false,
+                dynamic_leaves: None,
+            });
+    }
+
+    fn movegen(&mut self) -> Vec<kibitzer::JsonPlayWithEquity> {
+        self.gen_moves(self.question.count.unwrap_or(DEFAULT_COUNT));
+        self.move_generator.plays.iter().map(|x| x.into()).collect()
+    }
+
+    // validate one play for the mover, returning it canonicalized with its
+    // recounted score and equity. Word validity is not checked here, so a
+    // phony still gets its score; /validate looks for invalid words after.
+    fn recount(
+        &self,
+        play_scorer: &mut play_scorer::PlayScorer,
+        game_state: &game_state::GameState,
+        play: &kibitzer::JsonPlay,
+    ) -> error::Returns<movegen::ValuedMove> {
+        let board_snapshot = &self.board_snapshot();
+        let play = movegen::Play::from(play);
+        let mut play = play_scorer
+            .validate_play(board_snapshot, game_state, &play)?
+            .unwrap_or(play);
+        let score = play_scorer.compute_score(board_snapshot, &play);
+        if let movegen::Play::Place { score: s, .. } = &mut play {
+            *s = score;
+        }
+        let equity = play_scorer.compute_equity(
+            board_snapshot,
+            game_state,
+            &play,
+            wolges::move_filter::LEAVE_SCALE_DENOM,
+            score,
+        );
+        Ok(movegen::ValuedMove {
+            equity: equity::Equity::new(equity),
+            play,
+        })
+    }
+
+    fn score(&self) -> error::Returns<Vec<kibitzer::JsonPlayWithEquity>> {
+        // the bag order does not matter to scoring, only its size.
+        let game_state = self.game_state(&mut rand::rngs::ChaCha20Rng::seed_from_u64(0));
+        let mut play_scorer = play_scorer::PlayScorer::new();
+        let mut ret = Vec::with_capacity(self.question.plays.len());
+        for play in &self.question.plays {
+            ret.push((&self.recount(&mut play_scorer, &game_state, play)?).into());
+        }
+        Ok(ret)
+    }
+
+    fn validate(&self) -> error::Returns<Vec<kibitzer::JsonPlayWithEquity>> {
+        let game_state = self.game_state(&mut rand::rngs::ChaCha20Rng::seed_from_u64(0));
+        let board_snapshot = &self.board_snapshot();
+        let alphabet = self.lexicon.game_config.alphabet();
+        let mut play_scorer = play_scorer::PlayScorer::new();
+        let mut ret = Vec::with_capacity(self.question.plays.len());
+        for (play_idx, play) in self.question.plays.iter().enumerate() {
+            let valued_move = self
+                .recount(&mut play_scorer, &game_state, play)
+                .map_err(|e| error::new(format!("play {play_idx}: {e}")))?;
+            let mut invalid_words = Vec::new();
+            play_scorer.find_invalid_words(board_snapshot, &valued_move.play, |word: &[u8]| {
+                invalid_words.push(
+                    word.iter()
+                        .map(|&tile| alphabet.of_board(tile).unwrap_or("?"))
+                        .collect::<String>(),
+                )
+            });
+            if !invalid_words.is_empty() {
+                wolges::return_error!(format!(
+                    "play {play_idx}: invalid words {}",
+                    invalid_words.join(", "),
+                ));
+            }
+            ret.push((&valued_move).into());
+        }
+        Ok(ret)
+    }
+
+    fn sim(&mut self) -> error::Returns<Vec<kibitzer::JsonPlayWithEquity>> {
+        let mut rng = match self.question.seed {
+            Some(seed) => rand::rngs::ChaCha20Rng::seed_from_u64(seed),
+            None => rand::rngs::ChaCha20Rng::try_from_rng(&mut rand::rngs::SysRng)?,
+        };
+        let game_state = self.game_state(&mut rng);
+        self.gen_moves(self.question.candidates.unwrap_or(DEFAULT_SIM_PLAYS));
+        if self.move_generator.plays.is_empty() {
+            wolges::return_error!("no candidate plays".into());
+        }
+        let lexicon = self.lexicon;
+        let mut simmer = move_picker::Simmer::new(&lexicon.game_config, &lexicon.kwg, &lexicon.klv);
+        let iters = self.question.iters.unwrap_or(DEFAULT_SIM_ITERS);
+        simmer.set_verbose(false);
+        simmer.set_observe(true);
+        simmer.set_num_sim_iters(iters);
+        simmer.reseed(rng.next_u64());
+        simmer.begin_decision(&self.move_generator, &game_state, iters);
+        let plays = &self.move_generator.plays;
+        Ok(simmer
+            .leaderboard(self.question.count.unwrap_or(DEFAULT_COUNT))
+            .into_iter()
+            .map(
+                |(play_index, _, sim_equity, _)| kibitzer::JsonPlayWithEquity {
+                    equity: sim_equity as f32,
+                    play: (&plays[play_index].play).into(),
+                },
+            )
+            .collect())
+    }
+
+    fn endgame(&mut self) -> error::Returns<Vec<kibitzer::JsonPlayWithEquity>> {
+        Ok(
+            match self.kibitzer.solve_endgame(
+                &mut self.move_generator,
+                &self.lexicon.game_config,
+                &self.lexicon.kwg,
+                &self.question.rack,
+                &self.question.scores,
+            )? {
+                kibitzer::EndgameAnswer::Peg(result) => result
+                    .best_move
+                    .as_ref()
+                    .map(|best_move| kibitzer::JsonPlayWithEquity {
+                        equity: result.expected_margin / equity::SCALE as f32,
+                        play: best_move.into(),
+                    })
+                    .into_iter()
+                    .collect(),
+                kibitzer::EndgameAnswer::Endgame { pv, .. } => pv
+                    .iter()
+                    .map(|(value, play)| kibitzer::JsonPlayWithEquity {
+                        equity: value / equity::SCALE as f32,
+                        play: play.into(),
+                    })
+                    .collect(),
+            },
+        )
+    }
+}
+
+impl Server {
+    // the JSON response body for a request, or the error message.
+    fn answer(&self, path: &str, body: &[u8]) -> error::Returns<String> {
+        let question = serde_json::from_slice::<Question>(body)?;
+        let Some(lexicon) = self.lexicons.get(&question.lexicon) else {
+            wolges::return_error!(format!("invalid lexicon {:?}", question.lexicon));
+        };
+        let lexicon = Arc::clone(lexicon);
+        match lexicon.as_ref() {
+            AnyLexicon::Node22(lexicon) => respond(lexicon, path, &question),
+            AnyLexicon::Node24(lexicon) => respond(lexicon, path, &question),
+        }
+    }
+
+    // read one HTTP/1.1 request, answer it, and close.
+    fn handle_connection(&self, stream: std::net::TcpStream) -> error::Returns<()> {
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let mut reader = std::io::BufReader::new(&stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_string();
+        let path = parts.next().unwrap_or_default().to_string();
+        let mut content_length = 0;
+        loop {
+            let mut header = String::new();
+            if reader.read_line(&mut header)? == 0 {
+                break;
+            }
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':')
+                && name.eq_ignore_ascii_case("content-length")
+            {
+                content_length = value.trim().parse::<usize>()?;
+            }
+        }
+        let (status, response) = if method != "POST" {
+            ("405 Method Not Allowed", error_body("expected POST"))
+        } else if content_length > MAX_BODY_LEN {
+            ("413 Payload Too Large", error_body("request too large"))
+        } else {
+            let mut body = vec![0u8; content_length];
+            reader.read_exact(&mut body)?;
+            match self.answer(&path, &body) {
+                Ok(response) => ("200 OK", response),
+                Err(err) => ("400 Bad Request", error_body(&err.to_string())),
+            }
+        };
+        let mut stream = &stream;
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+            response.len(),
+        )?;
+        stream.flush()?;
+        Ok(())
+    }
+}
+
+// the JSON response body for a question over a lexicon of either node type.
+fn respond<N: kwg::Node + Sync>(
+    lexicon: &Lexicon<N>,
+    path: &str,
+    question: &Question,
+) -> error::Returns<String> {
+    let mut analysis = Analysis::new(lexicon, question)?;
+    Ok(match path {
+        "/movegen" => serde_json::to_string(&analysis.movegen())?,
+        "/score" => serde_json::to_string(&analysis.score()?)?,
+        "/validate" => serde_json::to_string(&analysis.validate()?)?,
+        "/sim" => serde_json::to_string(&analysis.sim()?)?,
+        "/endgame" => serde_json::to_string(&analysis.endgame()?)?,
+        _ => {
+            wolges::return_error!(format!("unknown path {path:?}"));
+        }
+    })
+}
+
+fn error_body(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+// a fixed pool of workers, one per cpu, each answering one connection at a
+// time. Accepted connections wait in a queue as long as the pool; when it is
+// full, accepting waits for a worker to free up.
+fn serve(listener: std::net::TcpListener, server: Arc<Server>) {
+    let num_workers = num_cpus::get();
+    let (sender, receiver) = std::sync::mpsc::sync_channel::<std::net::TcpStream>(num_workers);
+    let receiver = Arc::new(std::sync::Mutex::new(receiver));
+    for _ in 0..num_workers {
+        let server = Arc::clone(&server);
+        let receiver = Arc::clone(&receiver);
+        std::thread::spawn(move || {
+            loop {
+                let stream = receiver.lock().unwrap().recv();
+                let Ok(stream) = stream else { break };
+                if let Err(err) = server.handle_connection(stream) {
+                    eprintln!("connection error: {err}");
+                }
+            }
+        });
+    }
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        if sender.send(stream).is_err() {
+            break;
+        }
+    }
+}
+
+fn main() -> error::Returns<()> {
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.len() < 3 {
+        wolges::return_error!(format!(
+            "expected at least 2 arguments, got {}\n{USAGE}",
+            args.len() - 1,
+        ));
+    }
+    let mut lexicons = fash::MyHashMap::default();
+    for spec in &args[2..] {
+        let parts = spec.splitn(4, ':').collect::<Vec<_>>();
+        let [name, config_name, kwg_path, klv_path @ ..] = parts.as_slice() else {
+            wolges::return_error!(format!("invalid lexicon {spec:?}\n{USAGE}"));
+        };
+        let (base_name, big) = match config_name.strip_suffix("-big") {
+            Some(base) => (base, true),
+            None => (*config_name, false),
+        };
+        let game_config = game_config::load_game_config(base_name)
+            .map_err(|e| error::new(format!("{e}\n{USAGE}")))?;
+        // lexicons are kept until the server exits, so their bytes are too.
+        let file_bytes = kwg::AlignedBytes::read(kwg_path)?.leak();
+        let (kwg_bytes, big, bundled_klv_bytes) = match bundle::detect(file_bytes, &game_config)? {
+            Some(bundle) => (
+                bundle.kwg,
+                bundle.metadata.node_width == bundle::NodeWidth::Node24,
+                bundle.klv,
+            ),
+            None => (file_bytes, big, None),
+        };
+        let klv = match klv_path.first() {
+            Some(klv_path) => {
+                klv::Klv::<kwg::Node22>::from_bytes(kwg::AlignedBytes::read(klv_path)?.leak())
+            }
+            None => klv::Klv::<kwg::Node22>::from_bytes(
+                bundled_klv_bytes.unwrap_or(klv::EMPTY_KLV_BYTES),
+            ),
+        };
+        let lexicon = if big {
+            AnyLexicon::Node24(Lexicon {
+                game_config,
+                kwg: kwg::Kwg::<kwg::Node24>::from_bytes(kwg_bytes),
+                klv,
+            })
+        } else {
+            AnyLexicon::Node22(Lexicon {
+                game_config,
+                kwg: kwg::Kwg::<kwg::Node22>::from_bytes(kwg_bytes),
+                klv,
+            })
+        };
+        lexicons.insert(name.to_string(), Arc::new(lexicon));
+    }
+    let listener = std::net::TcpListener::bind(&args[1])?;
+    println!("listening on {}", listener.local_addr()?);
+    serve(listener, Arc::new(Server { lexicons }));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wolges::{alphabet, bites, build};
+
+    fn start_server() -> std::net::SocketAddr {
+        let game_config = game_config::make_english_game_config();
+        let reader = alphabet::AlphabetReader::new_for_words(game_config.alphabet());
+        let mut words = ["AB", "BA", "CAB", "DAB", "BAD", "CAD", "ABA"]
+            .iter()
+            .map(|w| {
+                let mut buf = Vec::new();
+                reader.set_word(w, &mut buf).unwrap();
+                bites::Bites::from(&buf[..])
+            })
+            .collect::<Vec<_>>();
+        words.sort_unstable();
+        let kwg = kwg::Kwg::from_bytes_alloc(
+            &build::build(
+                build::BuildContent::Gaddawg,
+                build::BuildLayout::Wolges,
+                &words,
+            )
+            .unwrap(),
+        );
+        let klv = klv::Klv::from_bytes_alloc(klv::EMPTY_KLV_BYTES);
+        let mut lexicons = fash::MyHashMap::default();
+        lexicons.insert(
+            "TINY".to_string(),
+            Arc::new(AnyLexicon::Node22(Lexicon {
+                game_config,
+                kwg,
+                klv,
+            })),
+        );
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = Arc::new(Server { lexicons });
+        std::thread::spawn(move || serve(listener, server));
+        addr
+    }
+
+    fn post(addr: std::net::SocketAddr, path: &str, body: &str) -> (String, serde_json::Value) {
+        let mut stream = std::net::TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "POST {path} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len(),
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.lines().next().unwrap().to_string();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    fn question(extra: &str) -> String {
+        let mut board = vec![vec![0i8; 15]; 15];
+        // CAB across at 8G, with the C a blank.
+        board[7][6] = -3;
+        board[7][7] = 1;
+        board[7][8] = 2;
+        format!(
+            r#"{{"lexicon":"TINY","rack":[1,2,4,4],"board":{}{extra}}}"#,
+            serde_json::to_string(&board).unwrap(),
+        )
+    }
+
+    // every endpoint answers over one shared lexicon, and errors are 400s.
+    #[test]
+    fn endpoints_answer_over_localhost() {
+        let addr = start_server();
+
+        let (status, plays) = post(addr, "/movegen", &question(r#","count":3"#));
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert_eq!(plays.as_array().unwrap().len(), 3);
+        assert!(plays[0]["equity"].is_number());
+
+        // DAB down from H7 through the A of CAB: 2 + 1 + 3 = 6.
+        let dab = r#"{"action":"play","down":true,"lane":7,"idx":6,"word":[4,0,2],"score":0}"#;
+        let dad = r#"{"action":"play","down":true,"lane":7,"idx":6,"word":[4,0,4],"score":0}"#;
+        let (status, scored) = post(addr, "/score", &question(&format!(r#","plays":[{dab}]"#)));
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert_eq!(scored[0]["score"], 6);
+
+        let (status, validated) = post(
+            addr,
+            "/validate",
+            &question(&format!(r#","plays":[{dab}]"#)),
+        );
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert_eq!(validated[0]["score"], 6);
+        assert!(validated[0]["equity"].is_number());
+        let (status, err) = post(
+            addr,
+            "/validate",
+            &question(&format!(r#","plays":[{dab},{dad}]"#)),
+        );
+        assert_eq!(status, "HTTP/1.1 400 Bad Request");
+        assert!(
+            err["error"]
+                .as_str()
+                .unwrap()
+                .contains("play 1: invalid words DAD")
+        );
+
+        let (status, simmed) = post(
+            addr,
+            "/sim",
+            &question(r#","count":2,"iters":20,"seed":1,"candidates":5"#),
+        );
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert_eq!(simmed.as_array().unwrap().len(), 2);
+
+        let (status, err) = post(addr, "/endgame", &question(""));
+        assert_eq!(status, "HTTP/1.1 400 Bad Request");
+        assert!(err["error"].as_str().unwrap().contains("not endgame"));
+
+        let (status, _) = post(addr, "/nope", &question(""));
+        assert_eq!(status, "HTTP/1.1 400 Bad Request");
+        let (status, _) = post(addr, "/movegen", r#"{"lexicon":"BOGUS"}"#);
+        assert_eq!(status, "HTTP/1.1 400 Bad Request");
+    }
+}
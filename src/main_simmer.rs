@@ -66,10 +66,20 @@ impl Question {
 }
 
 fn main() -> error::Returns<()> {
+    // opt-in rules file (WOLGES_RULES_FILE=<file.json>) in place of the
+    // built-in game config.
+    let rules_config = std::env::var("WOLGES_RULES_FILE")
+        .ok()
+        .filter(|x| !x.is_empty())
+        .map(|path| game_config::load_game_config_from_rules_file(&path))
+        .transpose()?;
+
     // https://github.com/domino14/macondo/issues/43
     let scores = [336000, 298000];
     let question = Question::from_fen(
-        &game_config::make_english_game_config(),
+        &rules_config
+            .clone()
+            .unwrap_or_else(game_config::make_english_game_config),
         "NWL23", // actually "NWL20",
         "C14/O2TOY9/mIRADOR8/F4DAB2PUGH1/I5GOOEY3V/T4XI2MALTHA/14N/6GUM3OWN/7PEW2DOE/9EF1DOR/2KUNA1J1BEVELS/3TURRETs2S2/7A4T2/7N7/7S7",
         "EEEIILZ",
@@ -78,24 +88,35 @@ fn main() -> error::Returns<()> {
     // of course this should be cached
     let (kwg, klv, game_config) = match question.lexicon.as_str() {
         "CSW24" => (
-            kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/CSW24.kwg")?),
-            klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/CSW24.klv2")?),
+            kwg::Kwg::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/CSW24.kwg")?.leak(),
+            ),
+            klv::Klv::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/CSW24.klv2")?.leak(),
+            ),
             game_config::make_english_game_config(),
         ),
         "NWL23" => (
-            kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/NWL23.kwg")?),
-            klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/NWL23.klv2")?),
+            kwg::Kwg::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/NWL23.kwg")?.leak(),
+            ),
+            klv::Klv::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/NWL23.klv2")?.leak(),
+            ),
             game_config::make_english_game_config(),
         ),
         "ECWL" => (
-            kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/ECWL.kwg")?),
-            klv::Klv::<kwg::Node22>::from_bytes_alloc(&std::fs::read("lexbin/ECWL.klv2")?),
+            kwg::Kwg::<kwg::Node22>::from_bytes(kwg::AlignedBytes::read("lexbin/ECWL.kwg")?.leak()),
+            klv::Klv::<kwg::Node22>::from_bytes(
+                kwg::AlignedBytes::read("lexbin/ECWL.klv2")?.leak(),
+            ),
             game_config::make_english_game_config(),
         ),
         _ => {
             wolges::return_error!(format!("invalid lexicon {:?}", question.lexicon));
         }
     };
+    let game_config = rules_config.unwrap_or(game_config);
 
     let alphabet = game_config.alphabet();
     let alphabet_len_without_blank = alphabet.len() - 1;
@@ -234,6 +255,32 @@ fn main() -> error::Returns<()> {
         kwg: &smaller_kwg,
         klv: &klv,
     };
+
+    // "merge run1.json run2.json ..." pools checkpoints of this position saved
+    // by independent runs (say, on other machines) into one leaderboard.
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.get(1).map(String::as_str) == Some("merge") {
+        let checkpoints = args[2..]
+            .iter()
+            .map(|path| move_picker::Checkpoint::load(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        let merged = move_picker::merge_checkpoints(&checkpoints)?;
+        let total_iters = checkpoints.iter().map(|c| c.iters_done()).sum::<u64>();
+        println!("\nmerged {} runs, {total_iters} iters", checkpoints.len());
+        for (i, candidate) in (1..).zip(merged.iter().take(10)) {
+            println!(
+                "{:3} {:6.2} +-{:5.2} {:6.2} {:6} {}",
+                i,
+                candidate.stats.mean(),
+                candidate.ci_half_width(1.96),
+                100.0 * candidate.win_rate_stats.mean(),
+                candidate.stats.count(),
+                candidate.play.fmt(board_snapshot)
+            );
+        }
+        return Ok(());
+    }
+
     move_generator.gen_moves_unfiltered(&movegen::GenMovesParams {
         board_snapshot,
         rack: &game_state.current_player().rack,
@@ -248,12 +295,28 @@ fn main() -> error::Returns<()> {
     driver.set_verbose(false);
     driver.set_num_sim_iters(10000);
     // run in chunks so the leaderboard can be shown as it converges, exercising
-    // the resumable accumulator.
+    // the resumable accumulator. with a checkpoint path argument, pick up where
+    // an earlier run saved and save again after every chunk.
+    let checkpoint_path = args.get(1);
     let chunk = 50u64;
-    let mut done = 0u64;
-    driver.begin_decision(&move_generator, &game_state, chunk);
-    done += chunk;
+    let mut done = match &checkpoint_path {
+        Some(path) if std::path::Path::new(path).exists() => {
+            let checkpoint = move_picker::Checkpoint::load(path)?;
+            driver.restore(&checkpoint, &mut move_generator)?;
+            println!("resuming {path} at iter {}", checkpoint.iters_done());
+            checkpoint.iters_done()
+        }
+        _ => {
+            // a fresh seed per run, so runs on several machines can be merged.
+            driver.reseed(rand::random());
+            driver.begin_decision(&move_generator, &game_state, chunk);
+            chunk
+        }
+    };
     loop {
+        if let Some(path) = &checkpoint_path {
+            driver.checkpoint(&move_generator).save(path)?;
+        }
         println!("\niter {done}");
         for (i, (play_index, _obj, equity_mean, win_rate)) in (1..).zip(driver.leaderboard(10)) {
             println!(
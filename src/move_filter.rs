@@ -184,6 +184,7 @@ impl<'a> Tilt<'a> {
     }
 }
 
+#[derive(Clone)]
 pub enum GenMoves<'a> {
     Unfiltered,
     Tilt { tilt: Tilt<'a>, bot_level: i8 },
@@ -1,6 +1,9 @@
 // Copyright (C) 2020-2026 Andy Kurnia.
 
-use super::{game_config, game_state, klv, kwg, move_filter, movegen, simmer, stats, win_pct};
+use super::{
+    equity, error, fash, game_config, game_state, game_timers, inference, kibitzer, klv, kwg,
+    move_filter, movegen, simmer, stats, win_pct,
+};
 
 struct Candidate {
     play_index: usize,
@@ -17,6 +20,9 @@ struct Candidate {
 // Default per-decision rollout budget (see Simmer::num_sim_iters).
 const DEFAULT_NUM_SIM_ITERS: u64 = 1000;
 
+// Default rollout depth: the candidate, the opponent's reply, and our next move.
+const DEFAULT_NUM_SIM_PLIES: usize = 2;
+
 // splitmix64 finalizer of a decision seed combined with an iteration index.
 // The parallel move picker reseeds each iteration's draw from this, so the draw
 // is reproducible and depends only on (decision seed, iteration index) -- never
@@ -42,7 +48,8 @@ const PRUNE_CADENCE: u64 = 16;
 const DEFAULT_STOP_DELTA: f64 = 0.05;
 
 // How each iteration's rollouts are allocated across the surviving candidates.
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Allocator {
     // Simulate every surviving candidate on every iteration's shared draw.
     // Maximum common-random-number pairing, but spends as many rollouts on
@@ -84,7 +91,8 @@ fn rollout_objective<N: kwg::Node, L: kwg::Node>(
 }
 
 // The rule that decides when a move decision is finished.
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum StopRule {
     // Always run the whole fixed iteration budget (minus pruning to one arm).
     FixedCap,
@@ -93,6 +101,17 @@ pub enum StopRule {
     Confidence,
 }
 
+// Which plays a decision sims, picked from the generated plays instead of just
+// their top by static equity: the best placements by static equity, the best
+// exchanges by leave value, and pass. Good exchanges and strategic passes near
+// the end would otherwise rarely make the cut.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CandidatePolicy {
+    pub num_placements: usize,
+    pub num_exchanges: usize,
+    pub include_pass: bool,
+}
+
 // The z threshold for the confidence stop, corrected for multiple comparisons.
 // The stop compares the leader against each of the num_survivors - 1 other
 // survivors; a union bound over those comparisons at overall error `delta`,
@@ -184,8 +203,8 @@ fn limit_surviving_candidates(
 // This is not enforced.
 pub struct Simmer<'a, N: kwg::Node, L: kwg::Node> {
     game_config: &'a game_config::GameConfig,
-    kwg: &'a kwg::Kwg<N>,
-    klv: &'a klv::Klv<L>,
+    kwg: &'a kwg::Kwg<'a, N>,
+    klv: &'a klv::Klv<'a, L>,
     candidates: Vec<Candidate>,
     simmer: simmer::Simmer,
     // Number of Monte-Carlo rollout iterations spent on each move decision.
@@ -195,6 +214,8 @@ pub struct Simmer<'a, N: kwg::Node, L: kwg::Node> {
     // wants a more accurate (slower) or quicker decision overrides it via
     // set_num_sim_iters.
     num_sim_iters: u64,
+    // Plies rolled out after each candidate, or simmer::PLAY_TO_END.
+    num_sim_plies: usize,
     // When false, pick_a_move skips its per-decision debug print. The batch
     // sim-vs-sim harness turns this off; the interactive picker leaves it on.
     verbose: bool,
@@ -204,6 +225,14 @@ pub struct Simmer<'a, N: kwg::Node, L: kwg::Node> {
     // statistically separated, and the target error for that stop.
     stop_rule: StopRule,
     stop_delta: f64,
+    // Optional wall-clock limit on each decision, on top of the stop rule and
+    // the iteration budget, timed afresh from each begin_decision or resume.
+    // None (default) never reads the clock.
+    time_budget: Option<game_timers::MoveBudget>,
+    decision_start: Option<std::time::Instant>,
+    // How pick_a_move narrows the generated plays to candidates. None (default)
+    // sims every generated play.
+    candidate_policy: Option<CandidatePolicy>,
     // Candidates dropped by the prune, kept with their statistics instead of
     // discarded, so a study session can resume and inspect them later.
     retired: Vec<Candidate>,
@@ -221,6 +250,10 @@ pub struct Simmer<'a, N: kwg::Node, L: kwg::Node> {
     // terminal evaluation. None (default) = the simmer uses its sigmoid, batch
     // play byte-identical. Borrowed, never owned.
     win_pct_table: Option<&'a win_pct::WinPctTable>,
+    // Per player, the leave they are inferred to have kept, dealt to them in
+    // every rollout. Kept across decisions until changed; the inner simmer
+    // forgets it on every prepare, so it is handed over again each time.
+    opponent_leaves: Box<[Option<inference::LeaveInference>]>,
     // How many threads a single decision's rollouts are divided across on native
     // builds. 1 (default) keeps the single-threaded stream, so the
     // default path is byte-identical to before; > 1 opts into the parallel path.
@@ -236,8 +269,8 @@ pub struct Simmer<'a, N: kwg::Node, L: kwg::Node> {
 impl<'a, N: kwg::Node, L: kwg::Node> Simmer<'a, N, L> {
     pub fn new(
         game_config: &'a game_config::GameConfig,
-        kwg: &'a kwg::Kwg<N>,
-        klv: &'a klv::Klv<L>,
+        kwg: &'a kwg::Kwg<'a, N>,
+        klv: &'a klv::Klv<'a, L>,
     ) -> Self {
         Self {
             game_config,
@@ -246,15 +279,20 @@ impl<'a, N: kwg::Node, L: kwg::Node> Simmer<'a, N, L> {
             candidates: Vec::new(),
             simmer: simmer::Simmer::new(game_config),
             num_sim_iters: DEFAULT_NUM_SIM_ITERS,
+            num_sim_plies: DEFAULT_NUM_SIM_PLIES,
             verbose: true,
             allocator: Allocator::RoundRobin,
             stop_rule: StopRule::FixedCap,
             stop_delta: DEFAULT_STOP_DELTA,
+            time_budget: None,
+            decision_start: None,
+            candidate_policy: None,
             retired: Vec::new(),
             iters_done: 0,
             next_stream_id: 0,
             observe: false,
             win_pct_table: None,
+            opponent_leaves: vec![None; game_config.num_players() as usize].into_boxed_slice(),
             sim_threads: 1,
             decision_seed: 0,
         }
@@ -265,6 +303,18 @@ impl<'a, N: kwg::Node, L: kwg::Node> Simmer<'a, N, L> {
         self.num_sim_iters = num_sim_iters;
     }
 
+    // takes effect from the next begin_decision.
+    #[inline(always)]
+    pub fn set_num_sim_plies(&mut self, num_sim_plies: usize) {
+        self.num_sim_plies = num_sim_plies;
+    }
+
+    /// How each rollout ply picks its move (see simmer::Simmer::set_ply_policies).
+    #[inline(always)]
+    pub fn set_ply_policies(&mut self, ply_policies: Vec<simmer::PlyPolicy>) {
+        self.simmer.set_ply_policies(ply_policies);
+    }
+
     #[inline(always)]
     pub fn set_verbose(&mut self, verbose: bool) {
         self.verbose = verbose;
@@ -287,6 +337,90 @@ impl<'a, N: kwg::Node, L: kwg::Node> Simmer<'a, N, L> {
         self.stop_delta = stop_delta.clamp(f64::MIN_POSITIVE, 1.0 - f64::EPSILON);
     }
 
+    // Each decision then stops at the hard limit, or at a prune after the soft
+    // one once the leader is separated, whichever the stop rule does not reach
+    // first. At least one iteration always runs. See game_timers::TimePolicy.
+    #[inline(always)]
+    pub fn set_time_budget(&mut self, time_budget: Option<game_timers::MoveBudget>) {
+        self.time_budget = time_budget;
+    }
+
+    #[inline(always)]
+    pub fn set_candidate_policy(&mut self, candidate_policy: Option<CandidatePolicy>) {
+        self.candidate_policy = candidate_policy;
+    }
+
+    // Narrow move_generator.plays to the candidates the policy picks, best
+    // static equity first, for the next begin_decision. Exchanges are generated
+    // afresh from the rack, so they need not have survived movegen's cut, and
+    // only when the rules allow one. No policy leaves the plays alone.
+    pub fn select_candidates(
+        &self,
+        move_generator: &mut movegen::KurniaMoveGenerator,
+        game_state: &game_state::GameState,
+    ) {
+        let Some(policy) = self.candidate_policy else {
+            return;
+        };
+        let player = game_state.current_player();
+        let mut plays = std::mem::take(&mut move_generator.plays);
+        plays.retain(|valued_move| matches!(valued_move.play, movegen::Play::Place { .. }));
+        plays.sort_by_key(|valued_move| std::cmp::Reverse(valued_move.equity));
+        plays.truncate(policy.num_placements);
+        let mut rack_tally = vec![0u8; self.game_config.alphabet().len() as usize];
+        for &tile in player.rack.iter() {
+            rack_tally[tile as usize] += 1;
+        }
+        let num_tiles_in_bag = game_state.bag.len();
+        if policy.num_exchanges > 0
+            && num_tiles_in_bag >= self.game_config.exchange_tile_limit() as usize
+            && player.num_exchanges < self.game_config.exchanges_allowed_per_player()
+        {
+            let mut exchanges = Vec::new();
+            klv::MultiLeaves::gen_exchange_moves_via_klv(
+                self.klv,
+                |exchanged_tiles: &[u8], leave_value: i32| {
+                    exchanges.push(movegen::ValuedMove {
+                        equity: equity::Equity::new(leave_value),
+                        play: movegen::Play::Exchange {
+                            tiles: exchanged_tiles.into(),
+                        },
+                    });
+                },
+                &mut rack_tally,
+                &mut Vec::new(),
+                num_tiles_in_bag,
+            );
+            exchanges.sort_by_key(|valued_move| std::cmp::Reverse(valued_move.equity));
+            exchanges.truncate(policy.num_exchanges);
+            plays.extend(exchanges);
+        }
+        if policy.include_pass {
+            plays.push(movegen::ValuedMove {
+                equity: equity::Equity::new(self.klv.leave_value_from_tally(&rack_tally)),
+                play: movegen::Play::Exchange {
+                    tiles: [][..].into(),
+                },
+            });
+        }
+        plays.sort_by_key(|valued_move| std::cmp::Reverse(valued_move.equity));
+        move_generator.plays = plays;
+    }
+
+    // Whether the time budget ends the decision after this iteration.
+    #[inline(always)]
+    fn out_of_time(&self, candidates: &[Candidate], at_prune: bool) -> bool {
+        let (Some(time_budget), Some(decision_start)) = (self.time_budget, self.decision_start)
+        else {
+            return false;
+        };
+        let elapsed_ms = decision_start.elapsed().as_millis() as i64;
+        elapsed_ms >= time_budget.hard_ms
+            || (at_prune
+                && elapsed_ms >= time_budget.soft_ms
+                && leader_is_separated(candidates, self.stop_delta))
+    }
+
     #[inline(always)]
     pub fn set_observe(&mut self, observe: bool) {
         self.observe = observe;
@@ -308,6 +442,30 @@ impl<'a, N: kwg::Node, L: kwg::Node> Simmer<'a, N, L> {
         self.win_pct_table = table;
     }
 
+    // Deal this opponent a leave drawn from the inference (such as from
+    // inference::infer_leaves on their last play) in every rollout of the
+    // following decisions, instead of a uniformly drawn rack. None goes back
+    // to uniform.
+    #[inline(always)]
+    pub fn set_opponent_leaves(
+        &mut self,
+        player_idx: usize,
+        leaves: Option<inference::LeaveInference>,
+    ) {
+        self.opponent_leaves[player_idx] = leaves;
+    }
+
+    // prepare the inner simmer, then hand it the inferred leaves prepare cleared.
+    fn prepare_simmer(&mut self, game_state: &game_state::GameState) {
+        self.simmer
+            .prepare(self.game_config, game_state, self.num_sim_plies);
+        for (player_idx, leaves) in self.opponent_leaves.iter().enumerate() {
+            if let Some(leaves) = leaves {
+                self.simmer.set_opponent_leaves(player_idx, leaves.clone());
+            }
+        }
+    }
+
     /// Reseed the inner rollout RNG so a decision replays identically. The
     /// sim-vs-sim harness reseeds every move from a (seed, pair, game, turn)
     /// mix, making its results independent of the thread count.
@@ -390,6 +548,49 @@ impl<'a, N: kwg::Node, L: kwg::Node> Simmer<'a, N, L> {
         stream_id
     }
 
+    // Bring a play the user wants looked at into the current decision as a
+    // candidate of its own, adding it to move_generator.plays (valued at
+    // `equity`) if it was not generated. A play already being simmed keeps its
+    // candidate, and a retired one comes back with its statistics. Returns its
+    // stream id.
+    pub fn force_play(
+        &mut self,
+        move_generator: &mut movegen::KurniaMoveGenerator,
+        play: movegen::Play,
+        equity: equity::Equity,
+    ) -> u64 {
+        let play_index = match move_generator
+            .plays
+            .iter()
+            .position(|valued_move| valued_move.play == play)
+        {
+            Some(play_index) => play_index,
+            None => {
+                move_generator
+                    .plays
+                    .push(movegen::ValuedMove { equity, play });
+                move_generator.plays.len() - 1
+            }
+        };
+        if let Some(candidate) = self
+            .candidates
+            .iter()
+            .find(|candidate| candidate.play_index == play_index)
+        {
+            return candidate.stream_id;
+        }
+        if let Some(stream_id) = self
+            .retired
+            .iter()
+            .find(|candidate| candidate.play_index == play_index)
+            .map(|candidate| candidate.stream_id)
+        {
+            self.readmit_with_history(stream_id);
+            return stream_id;
+        }
+        self.add_play(play_index)
+    }
+
     // Readmit a retired candidate to the working set WITH its accumulated
     // statistics, named by its stream id. Returns true if it was found.
     pub fn readmit_with_history(&mut self, stream_id: u64) -> bool {
@@ -448,6 +649,89 @@ impl<'a, N: kwg::Node, L: kwg::Node> Simmer<'a, N, L> {
     pub fn is_decided(&self) -> bool {
         leader_is_separated(&self.candidates, self.stop_delta)
     }
+
+    // The current decision, to be continued by restore. move_generator.plays
+    // must be the plays the decision began with.
+    pub fn checkpoint(&self, move_generator: &movegen::KurniaMoveGenerator) -> Checkpoint {
+        Checkpoint {
+            version: CHECKPOINT_VERSION,
+            num_sim_iters: self.num_sim_iters,
+            num_sim_plies: self.num_sim_plies,
+            allocator: self.allocator,
+            stop_rule: self.stop_rule,
+            stop_delta: self.stop_delta,
+            observe: self.observe,
+            sim_threads: self.sim_threads,
+            decision_seed: self.decision_seed,
+            config: *self.simmer.config(),
+            rng_state: self.simmer.rng_state().to_vec(),
+            iters_done: self.iters_done,
+            next_stream_id: self.next_stream_id,
+            position: self.simmer.prepared_state().into(),
+            plays: move_generator
+                .plays
+                .iter()
+                .map(|valued_move| CheckpointPlay {
+                    equity: valued_move.equity.raw(),
+                    play: (&valued_move.play).into(),
+                })
+                .collect(),
+            candidates: self.candidates.iter().map(|c| c.into()).collect(),
+            retired: self.retired.iter().map(|c| c.into()).collect(),
+        }
+    }
+
+    // Takes up a checkpointed decision where it left off, with its plays put
+    // back into move_generator, so resume continues exactly as it would have
+    // in the process that saved it. The time budget, if any, starts afresh.
+    pub fn restore(
+        &mut self,
+        checkpoint: &Checkpoint,
+        move_generator: &mut movegen::KurniaMoveGenerator,
+    ) -> error::Returns<()> {
+        if checkpoint.version != CHECKPOINT_VERSION {
+            return_error!(format!(
+                "unsupported checkpoint version {}",
+                checkpoint.version
+            ));
+        }
+        let Ok(rng_state) = <&[u8; 49]>::try_from(&checkpoint.rng_state[..]) else {
+            return_error!("invalid checkpoint rng_state".into());
+        };
+        if checkpoint
+            .candidates
+            .iter()
+            .chain(checkpoint.retired.iter())
+            .any(|c| c.play_index >= checkpoint.plays.len())
+        {
+            return_error!("checkpoint candidate has no play".into());
+        }
+        let game_state = checkpoint.position.to_game_state(self.game_config)?;
+        self.num_sim_iters = checkpoint.num_sim_iters;
+        self.num_sim_plies = checkpoint.num_sim_plies;
+        self.allocator = checkpoint.allocator;
+        self.stop_rule = checkpoint.stop_rule;
+        self.stop_delta = checkpoint.stop_delta;
+        self.observe = checkpoint.observe;
+        self.sim_threads = checkpoint.sim_threads;
+        self.decision_seed = checkpoint.decision_seed;
+        self.simmer.set_config(checkpoint.config);
+        self.prepare_simmer(&game_state);
+        self.simmer.set_rng_state(rng_state);
+        self.iters_done = checkpoint.iters_done;
+        self.next_stream_id = checkpoint.next_stream_id;
+        self.candidates = checkpoint.candidates.iter().map(|c| c.into()).collect();
+        self.retired = checkpoint.retired.iter().map(|c| c.into()).collect();
+        self.decision_start = self.time_budget.map(|_| std::time::Instant::now());
+        move_generator.plays.clear();
+        move_generator
+            .plays
+            .extend(checkpoint.plays.iter().map(|play| movegen::ValuedMove {
+                equity: equity::Equity::new(play.equity),
+                play: (&play.play).into(),
+            }));
+        Ok(())
+    }
 }
 
 #[inline(always)]
@@ -459,6 +743,295 @@ fn top_candidate_play_index_by_mean(candidates: &[Candidate]) -> usize {
         .play_index
 }
 
+// Bumped on any incompatible change to Checkpoint.
+const CHECKPOINT_VERSION: u32 = 1;
+
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+struct CheckpointCandidate {
+    play_index: usize,
+    stream_id: u64,
+    stats: [u64; 3],
+    equity_stats: [u64; 3],
+    win_rate_stats: [u64; 3],
+}
+
+impl From<&Candidate> for CheckpointCandidate {
+    fn from(candidate: &Candidate) -> Self {
+        Self {
+            play_index: candidate.play_index,
+            stream_id: candidate.stream_id,
+            stats: candidate.stats.to_bits(),
+            equity_stats: candidate.equity_stats.to_bits(),
+            win_rate_stats: candidate.win_rate_stats.to_bits(),
+        }
+    }
+}
+
+impl From<&CheckpointCandidate> for Candidate {
+    fn from(candidate: &CheckpointCandidate) -> Self {
+        Self {
+            play_index: candidate.play_index,
+            stats: stats::Stats::from_bits(candidate.stats),
+            stream_id: candidate.stream_id,
+            equity_stats: stats::Stats::from_bits(candidate.equity_stats),
+            win_rate_stats: stats::Stats::from_bits(candidate.win_rate_stats),
+        }
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+struct CheckpointPlay {
+    equity: i32, // raw
+    play: kibitzer::JsonPlay,
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+struct CheckpointPlayer {
+    score: i32,
+    rack: Vec<u8>,
+    num_exchanges: i16,
+    known_present: Vec<u8>,
+    known_absent: Vec<u8>,
+}
+
+// the prepared position, whose opponent racks and bag change as each
+// iteration draws.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+struct CheckpointPosition {
+    board_tiles: Vec<u8>,
+    bag: Vec<u8>,
+    players: Vec<CheckpointPlayer>,
+    turn: u8,
+    zero_turns: u16,
+    pass_turns: u16,
+}
+
+impl From<&game_state::GameState> for CheckpointPosition {
+    fn from(game_state: &game_state::GameState) -> Self {
+        Self {
+            board_tiles: game_state.board_tiles.to_vec(),
+            bag: game_state.bag.as_slice().to_vec(),
+            players: game_state
+                .players
+                .iter()
+                .map(|player| CheckpointPlayer {
+                    score: player.score,
+                    rack: player.rack.clone(),
+                    num_exchanges: player.num_exchanges,
+                    known_present: player.known.present.clone(),
+                    known_absent: player.known.absent.clone(),
+                })
+                .collect(),
+            turn: game_state.turn,
+            zero_turns: game_state.zero_turns,
+            pass_turns: game_state.pass_turns,
+        }
+    }
+}
+
+impl CheckpointPosition {
+    fn to_game_state(
+        &self,
+        game_config: &game_config::GameConfig,
+    ) -> error::Returns<game_state::GameState> {
+        let mut game_state = game_state::GameState::new(game_config);
+        if self.board_tiles.len() != game_state.board_tiles.len()
+            || self.players.len() != game_state.players.len()
+            || self.turn as usize >= self.players.len()
+        {
+            return_error!("checkpoint position does not fit the game config".into());
+        }
+        game_state.board_tiles.copy_from_slice(&self.board_tiles);
+        game_state.bag.set_from_iter(self.bag.iter().copied());
+        for (player, saved) in game_state.players.iter_mut().zip(self.players.iter()) {
+            player.score = saved.score;
+            player.rack.clone_from(&saved.rack);
+            player.num_exchanges = saved.num_exchanges;
+            player.known.present.clone_from(&saved.known_present);
+            player.known.absent.clone_from(&saved.known_absent);
+        }
+        game_state.turn = self.turn;
+        game_state.zero_turns = self.zero_turns;
+        game_state.pass_turns = self.pass_turns;
+        Ok(game_state)
+    }
+}
+
+// A decision's whole state, written by Simmer::checkpoint and continued by
+// Simmer::restore, possibly in another process: the position as drawn so far,
+// the RNG stream position, the candidate plays with every arm's statistics
+// (retired ones too), and the settings. The ply policies, inferred opponent
+// leaves (see Simmer::set_opponent_leaves) and win-pct table are not saved;
+// set them again before restoring.
+// Stored as JSON.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Checkpoint {
+    version: u32,
+    num_sim_iters: u64,
+    num_sim_plies: usize,
+    allocator: Allocator,
+    stop_rule: StopRule,
+    stop_delta: f64,
+    observe: bool,
+    sim_threads: usize,
+    decision_seed: u64,
+    config: simmer::SimmerConfig,
+    rng_state: Vec<u8>,
+    iters_done: u64,
+    next_stream_id: u64,
+    position: CheckpointPosition,
+    plays: Vec<CheckpointPlay>,
+    candidates: Vec<CheckpointCandidate>,
+    retired: Vec<CheckpointCandidate>,
+}
+
+impl Checkpoint {
+    #[inline(always)]
+    pub fn iters_done(&self) -> u64 {
+        self.iters_done
+    }
+
+    // written to a temporary file first, so an interrupted save leaves the
+    // previous checkpoint intact.
+    pub fn save(&self, path: &str) -> error::Returns<()> {
+        let tmp_path = format!("{path}.tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec(self)?)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    pub fn load(path: &str) -> error::Returns<Self> {
+        let checkpoint = serde_json::from_slice::<Self>(&std::fs::read(path)?)?;
+        if checkpoint.version != CHECKPOINT_VERSION {
+            return_error!(format!(
+                "unsupported checkpoint version {}",
+                checkpoint.version
+            ));
+        }
+        Ok(checkpoint)
+    }
+}
+
+// One play's samples pooled across every merged run.
+pub struct MergedCandidate {
+    pub play: movegen::Play,
+    pub stats: stats::Stats,
+    pub equity_stats: stats::Stats,
+    pub win_rate_stats: stats::Stats,
+    // how many of the runs sampled this play.
+    pub num_runs: usize,
+}
+
+impl MergedCandidate {
+    // half-width of the confidence interval around stats.mean().
+    #[inline(always)]
+    pub fn ci_half_width(&self, z: f64) -> f64 {
+        if self.stats.count() > 0.0 {
+            self.stats.ci_max(z) - self.stats.mean()
+        } else {
+            f64::INFINITY
+        }
+    }
+}
+
+impl CheckpointPosition {
+    // what every run of one position agrees on, whatever each has since drawn.
+    fn same_position_as(&self, other: &Self) -> bool {
+        let unseen = |position: &Self| {
+            let mut tiles = position.bag.clone();
+            for (i, player) in position.players.iter().enumerate() {
+                if i != position.turn as usize {
+                    tiles.extend_from_slice(&player.rack);
+                }
+            }
+            tiles.sort_unstable();
+            tiles
+        };
+        self.board_tiles == other.board_tiles
+            && self.turn == other.turn
+            && self.players.len() == other.players.len()
+            && self
+                .players
+                .iter()
+                .zip(other.players.iter())
+                .all(|(a, b)| a.score == b.score)
+            && self.players[self.turn as usize].rack == other.players[other.turn as usize].rack
+            && unseen(self) == unseen(other)
+    }
+}
+
+// Pools the samples of runs of the same position (checkpoints from separate
+// processes or machines) into one leaderboard, best mean first. A play is
+// matched across runs by the play itself, so the runs need not have generated
+// the same candidates in the same order. The runs must be independent: two
+// runs reseeded with the same seed share their first samples, serial or
+// parallel, so they are refused rather than double counted. Reseed each run
+// with its own seed.
+pub fn merge_checkpoints(checkpoints: &[Checkpoint]) -> error::Returns<Vec<MergedCandidate>> {
+    let Some(first) = checkpoints.first() else {
+        return_error!("no runs to merge".into());
+    };
+    for (i, checkpoint) in checkpoints.iter().enumerate() {
+        if checkpoint.version != CHECKPOINT_VERSION {
+            return_error!(format!(
+                "run {i}: unsupported checkpoint version {}",
+                checkpoint.version
+            ));
+        }
+        if !checkpoint.position.same_position_as(&first.position) {
+            return_error!(format!("run {i} is of a different position"));
+        }
+        if let Some(j) = checkpoints[..i].iter().position(|other| {
+            other.rng_state == checkpoint.rng_state
+                || other.decision_seed == checkpoint.decision_seed
+        }) {
+            return_error!(format!("runs {j} and {i} are not independent"));
+        }
+    }
+    let mut merged = Vec::<MergedCandidate>::new();
+    let mut index_of_play = fash::MyHashMap::<movegen::Play, usize>::default();
+    for checkpoint in checkpoints {
+        for candidate in checkpoint
+            .candidates
+            .iter()
+            .chain(checkpoint.retired.iter())
+        {
+            let Some(saved_play) = checkpoint.plays.get(candidate.play_index) else {
+                return_error!("checkpoint candidate has no play".into());
+            };
+            let play = movegen::Play::from(&saved_play.play);
+            let idx = *index_of_play.entry(play).or_insert_with_key(|play| {
+                merged.push(MergedCandidate {
+                    play: play.clone(),
+                    stats: stats::Stats::new(),
+                    equity_stats: stats::Stats::new(),
+                    win_rate_stats: stats::Stats::new(),
+                    num_runs: 0,
+                });
+                merged.len() - 1
+            });
+            let entry = &mut merged[idx];
+            entry
+                .stats
+                .update_bulk(&stats::Stats::from_bits(candidate.stats));
+            entry
+                .equity_stats
+                .update_bulk(&stats::Stats::from_bits(candidate.equity_stats));
+            entry
+                .win_rate_stats
+                .update_bulk(&stats::Stats::from_bits(candidate.win_rate_stats));
+            entry.num_runs += 1;
+        }
+    }
+    merged.sort_by(|a, b| b.stats.mean().total_cmp(&a.stats.mean()));
+    Ok(merged)
+}
+
 pub struct Periods(pub u64);
 
 impl Periods {
@@ -651,6 +1224,9 @@ impl<'a, N: kwg::Node + Sync, L: kwg::Node + Sync> Simmer<'a, N, L> {
                     break;
                 }
             }
+            if self.out_of_time(&candidates, sim_iter % PRUNE_CADENCE == 0) {
+                break;
+            }
         }
         self.candidates = candidates;
         self.retired = retired;
@@ -691,6 +1267,14 @@ impl<'a, N: kwg::Node + Sync, L: kwg::Node + Sync> Simmer<'a, N, L> {
         let klv = self.klv;
         let win_pct_table = self.win_pct_table;
         let base_simmer = &self.simmer;
+        // every worker checks the hard limit after each of its iterations.
+        let hard_deadline =
+            self.time_budget
+                .zip(self.decision_start)
+                .map(|(time_budget, decision_start)| {
+                    decision_start
+                        + std::time::Duration::from_millis(time_budget.hard_ms.max(0) as u64)
+                });
         let end = self.iters_done + count;
         // One prune-cadence period at a time: sample it in parallel, merge, then
         // prune on the merged set exactly as the single-thread path does.
@@ -710,10 +1294,11 @@ impl<'a, N: kwg::Node + Sync, L: kwg::Node + Sync> Simmer<'a, N, L> {
             // returns their per-iteration values laid out iteration-major (one
             // row of num_candidates values per iteration, in ascending iteration
             // order). Contiguous slices in thread order let the rows concatenate
-            // back into ascending iteration order for any thread count. The third
-            // and fourth vecs (equity, win rate) stay empty unless observing.
-            let mut thread_rows: Vec<(Vec<f64>, Vec<f64>, Vec<f64>)> =
-                Vec::with_capacity(num_threads);
+            // back into ascending iteration order for any thread count. The
+            // equity and win rate vecs stay empty unless observing. A thread past the hard deadline stops early, so the
+            // first two fields count the iterations it ran out of its slice.
+            type ThreadRows = (usize, usize, Vec<f64>, Vec<f64>, Vec<f64>);
+            let mut thread_rows: Vec<ThreadRows> = Vec::with_capacity(num_threads);
             std::thread::scope(|scope| {
                 let mut handles = Vec::with_capacity(num_threads);
                 for thread_index in 0..num_threads {
@@ -762,8 +1347,14 @@ impl<'a, N: kwg::Node + Sync, L: kwg::Node + Sync> Simmer<'a, N, L> {
                                     win_rate.push(win_prob);
                                 }
                             }
+                            if offset + 1 < hi
+                                && hard_deadline
+                                    .is_some_and(|deadline| std::time::Instant::now() >= deadline)
+                            {
+                                return (offset + 1 - lo, span, objective, equity, win_rate);
+                            }
                         }
-                        (objective, equity, win_rate)
+                        (span, span, objective, equity, win_rate)
                     }));
                 }
                 for handle in handles {
@@ -773,6 +1364,8 @@ impl<'a, N: kwg::Node + Sync, L: kwg::Node + Sync> Simmer<'a, N, L> {
             // Concatenate the threads' rows in thread (= ascending iteration)
             // order, then reduce each candidate's values in that fixed order. The
             // buffer is identical for any thread count, so the reduction is too.
+            // Past the deadline, keep only the rows up to the first thread that
+            // stopped early, so iters_done still counts a gapless prefix.
             let mut block_objective: Vec<f64> = Vec::with_capacity(block_len * num_candidates);
             let mut block_equity: Vec<f64> = Vec::new();
             let mut block_win_rate: Vec<f64> = Vec::new();
@@ -780,13 +1373,21 @@ impl<'a, N: kwg::Node + Sync, L: kwg::Node + Sync> Simmer<'a, N, L> {
                 block_equity.reserve(block_len * num_candidates);
                 block_win_rate.reserve(block_len * num_candidates);
             }
-            for (objective, equity, win_rate) in thread_rows {
+            let mut timed_out = false;
+            let mut block_len = 0;
+            for (iterations_run, span, objective, equity, win_rate) in thread_rows {
+                block_len += iterations_run;
                 block_objective.extend(objective);
                 if observe {
                     block_equity.extend(equity);
                     block_win_rate.extend(win_rate);
                 }
+                if iterations_run < span {
+                    timed_out = true;
+                    break;
+                }
             }
+            let block_end = block_start + block_len as u64;
             for (candidate_index, candidate) in candidates.iter_mut().enumerate() {
                 for iteration in 0..block_len {
                     let k = iteration * num_candidates + candidate_index;
@@ -798,6 +1399,9 @@ impl<'a, N: kwg::Node + Sync, L: kwg::Node + Sync> Simmer<'a, N, L> {
                 }
             }
             self.iters_done = block_end;
+            if timed_out {
+                break;
+            }
             if block_end.is_multiple_of(PRUNE_CADENCE) {
                 let low_bar = candidates
                     .iter()
@@ -821,6 +1425,9 @@ impl<'a, N: kwg::Node + Sync, L: kwg::Node + Sync> Simmer<'a, N, L> {
                     break;
                 }
             }
+            if self.out_of_time(&candidates, block_end.is_multiple_of(PRUNE_CADENCE)) {
+                break;
+            }
         }
         self.candidates = candidates;
         self.retired = retired;
@@ -836,11 +1443,12 @@ impl<'a, N: kwg::Node + Sync, L: kwg::Node + Sync> Simmer<'a, N, L> {
         game_state: &game_state::GameState,
         iters: u64,
     ) {
-        self.simmer.prepare(self.game_config, game_state, 2);
+        self.prepare_simmer(game_state);
         self.candidates = self.take_candidates(move_generator.plays.len());
         self.next_stream_id = self.candidates.len() as u64;
         self.retired.clear();
         self.iters_done = 0;
+        self.decision_start = self.time_budget.map(|_| std::time::Instant::now());
         let budget = self.num_sim_iters;
         self.run_iterations(move_generator, budget, iters);
     }
@@ -848,8 +1456,10 @@ impl<'a, N: kwg::Node + Sync, L: kwg::Node + Sync> Simmer<'a, N, L> {
     // Study continuation: run `extra_iters` more rollouts on the retained
     // candidate set, continuing the same rollout stream (no reseed) so the
     // result matches having run the larger budget in one begin_decision call.
-    // Does not commit a winner.
+    // Does not commit a winner. The time budget, if any, applies to this call
+    // alone, however long the pause before it.
     pub fn resume(&mut self, move_generator: &movegen::KurniaMoveGenerator, extra_iters: u64) {
+        self.decision_start = self.time_budget.map(|_| std::time::Instant::now());
         let budget = self.num_sim_iters;
         self.run_iterations(move_generator, budget, extra_iters);
     }
@@ -886,6 +1496,7 @@ impl<N: kwg::Node, L: kwg::Node> MovePicker<'_, N, L> {
                     game_state.current_player().num_exchanges,
                     100,
                 );
+                simmer.select_candidates(move_generator, game_state);
                 let budget = simmer.num_sim_iters;
                 simmer.begin_decision(move_generator, game_state, budget);
                 let winner_play_index = top_candidate_play_index_by_mean(&simmer.candidates);
@@ -915,6 +1526,8 @@ impl<N: kwg::Node, L: kwg::Node> MovePicker<'_, N, L> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::{TWO_LETTER_WORDS, make_tiny_kwg};
+    use rand::SeedableRng;
 
     fn stats_from(values: &[f64]) -> stats::Stats {
         let mut s = stats::Stats::new();
@@ -924,6 +1537,33 @@ mod tests {
         s
     }
 
+    // the opening rack dealt from seed 1, and up to max_gen plays for it.
+    fn deal_opening(
+        game_config: &game_config::GameConfig,
+        kwg: &kwg::Kwg<kwg::Node22>,
+        klv: &klv::Klv<kwg::Node22>,
+        max_gen: usize,
+    ) -> (game_state::GameState, movegen::KurniaMoveGenerator) {
+        let mut game_state = game_state::GameState::new(game_config);
+        let mut deal_rng = rand::rngs::ChaCha20Rng::seed_from_u64(1);
+        game_state.reset_and_draw_tiles(game_config, &mut deal_rng);
+        let mut move_generator = movegen::KurniaMoveGenerator::new(game_config);
+        move_generator.gen_moves_unfiltered(&movegen::GenMovesParams {
+            board_snapshot: &movegen::BoardSnapshot {
+                board_tiles: &game_state.board_tiles,
+                game_config,
+                kwg,
+                klv,
+            },
+            rack: &game_state.current_player().rack,
+            max_gen,
+            num_exchanges_by_this_player: 0,
+            always_include_pass: false,
+            dynamic_leaves: None,
+        });
+        (game_state, move_generator)
+    }
+
     fn candidate_from(play_index: usize, values: &[f64]) -> Candidate {
         Candidate {
             play_index,
@@ -980,4 +1620,261 @@ mod tests {
         assert_eq!(retired.len(), 1);
         assert_eq!(retired[0].play_index, 1);
     }
+
+    // a spent time budget stops a decision after its first iteration.
+    #[test]
+    fn time_budget_stops_the_decision() {
+        let game_config = game_config::make_english_game_config();
+        let kwg = make_tiny_kwg(&[]);
+        let klv = klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES);
+        let (game_state, move_generator) = deal_opening(&game_config, &kwg, &klv, 5);
+        assert!(move_generator.plays.len() > 1);
+        let mut simmer = Simmer::new(&game_config, &kwg, &klv);
+        simmer.reseed(3);
+        simmer.set_time_budget(Some(game_timers::MoveBudget {
+            soft_ms: 0,
+            hard_ms: 0,
+        }));
+        simmer.begin_decision(&move_generator, &game_state, 100);
+        assert_eq!(simmer.iters_done, 1);
+        // the parallel workers stop mid-block too, keeping the gapless prefix.
+        simmer.set_sim_threads(2);
+        simmer.begin_decision(&move_generator, &game_state, 100);
+        assert_eq!(simmer.iters_done, 1);
+        simmer.set_sim_threads(1);
+        // a resume after a pause gets its own budget.
+        simmer.set_time_budget(Some(game_timers::MoveBudget {
+            soft_ms: 60000,
+            hard_ms: 60000,
+        }));
+        simmer.decision_start =
+            std::time::Instant::now().checked_sub(std::time::Duration::from_secs(120));
+        simmer.resume(&move_generator, 5);
+        assert_eq!(simmer.iters_done, 6);
+        simmer.set_time_budget(None);
+        simmer.begin_decision(&move_generator, &game_state, 10);
+        assert_eq!(simmer.iters_done, 10);
+        assert!(simmer.decision_start.is_none());
+    }
+
+    // an inferred leave set on the picker survives the prepare of every
+    // decision, so each rollout deals it to the opponent.
+    #[test]
+    fn opponent_leaves_reach_every_decision() {
+        let game_config = game_config::make_english_game_config();
+        let kwg = make_tiny_kwg(&[]);
+        let klv = klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES);
+        let (game_state, move_generator) = deal_opening(&game_config, &kwg, &klv, 5);
+        // the tile the mover cannot see least of, so a uniform deal often
+        // misses it.
+        let unseen = game_state
+            .bag
+            .as_slice()
+            .iter()
+            .chain(game_state.players[1].rack.iter())
+            .copied()
+            .collect::<Vec<_>>();
+        let kept = *unseen
+            .iter()
+            .min_by_key(|&&tile| unseen.iter().filter(|&&t| t == tile).count())
+            .unwrap();
+        let mut simmer = Simmer::new(&game_config, &kwg, &klv);
+        simmer.reseed(3);
+        simmer.set_opponent_leaves(
+            1,
+            Some(inference::LeaveInference::from_leaves(vec![
+                inference::InferredLeave {
+                    leave: [kept][..].into(),
+                    weight: 1.0,
+                },
+            ])),
+        );
+        for _ in 0..10 {
+            simmer.begin_decision(&move_generator, &game_state, 1);
+            assert!(
+                simmer.simmer.prepared_state().players[1]
+                    .rack
+                    .contains(&kept)
+            );
+        }
+        // and uniform again once cleared: some deal misses the tile.
+        simmer.set_opponent_leaves(1, None);
+        assert!((0..50).any(|_| {
+            simmer.begin_decision(&move_generator, &game_state, 1);
+            !simmer.simmer.prepared_state().players[1]
+                .rack
+                .contains(&kept)
+        }));
+    }
+
+    // a decision checkpointed through JSON and restored elsewhere resumes
+    // exactly as the original does.
+    #[test]
+    fn checkpoint_resumes_the_same_decision() {
+        let game_config = game_config::make_english_game_config();
+        let kwg = make_tiny_kwg(&[]);
+        let klv = klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES);
+        let (game_state, move_generator) = deal_opening(&game_config, &kwg, &klv, 5);
+        let mut simmer = Simmer::new(&game_config, &kwg, &klv);
+        simmer.reseed(3);
+        simmer.begin_decision(&move_generator, &game_state, 20);
+        let json = serde_json::to_string(&simmer.checkpoint(&move_generator)).unwrap();
+        let checkpoint = serde_json::from_str::<Checkpoint>(&json).unwrap();
+        assert_eq!(checkpoint.iters_done(), 20);
+
+        let mut restored_move_generator = movegen::KurniaMoveGenerator::new(&game_config);
+        let mut restored = Simmer::new(&game_config, &kwg, &klv);
+        restored
+            .restore(&checkpoint, &mut restored_move_generator)
+            .unwrap();
+        assert_eq!(
+            restored_move_generator.plays.len(),
+            move_generator.plays.len()
+        );
+        simmer.resume(&move_generator, 30);
+        restored.resume(&restored_move_generator, 30);
+        assert_eq!(restored.iters_done, simmer.iters_done);
+        let leaderboard = |s: &Simmer<'_, kwg::Node22, kwg::Node22>| {
+            s.candidates
+                .iter()
+                .chain(s.retired.iter())
+                .map(|c| (c.play_index, c.stats.to_bits()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(leaderboard(&restored), leaderboard(&simmer));
+
+        let mut bad = serde_json::from_str::<Checkpoint>(&json).unwrap();
+        bad.rng_state.pop();
+        assert!(
+            restored
+                .restore(&bad, &mut restored_move_generator)
+                .is_err()
+        );
+    }
+
+    // runs from different seeds pool every sample of each play; a run cannot be
+    // merged with itself.
+    #[test]
+    fn merge_pools_independent_runs() {
+        let game_config = game_config::make_english_game_config();
+        let kwg = make_tiny_kwg(&[]);
+        let klv = klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES);
+        let (game_state, move_generator) = deal_opening(&game_config, &kwg, &klv, 5);
+        let run = |seed: u64, iters: u64| {
+            let mut simmer = Simmer::new(&game_config, &kwg, &klv);
+            simmer.reseed(seed);
+            simmer.begin_decision(&move_generator, &game_state, iters);
+            simmer.checkpoint(&move_generator)
+        };
+        let runs = [run(3, 20), run(4, 30)];
+        let count_of = |checkpoint: &Checkpoint| {
+            checkpoint
+                .candidates
+                .iter()
+                .chain(checkpoint.retired.iter())
+                .map(|c| stats::Stats::from_bits(c.stats).count())
+                .sum::<f64>()
+        };
+        let merged = merge_checkpoints(&runs).unwrap();
+        assert_eq!(merged.len(), move_generator.plays.len());
+        assert!(merged.iter().all(|c| c.num_runs == 2));
+        assert_eq!(
+            merged.iter().map(|c| c.stats.count()).sum::<f64>(),
+            count_of(&runs[0]) + count_of(&runs[1])
+        );
+        assert!(
+            merged
+                .windows(2)
+                .all(|w| w[0].stats.mean() >= w[1].stats.mean())
+        );
+        assert!(merged[0].ci_half_width(1.96).is_finite());
+
+        let again = [run(3, 20), run(3, 20)];
+        assert!(merge_checkpoints(&again).is_err());
+        let longer = [run(3, 20), run(3, 30)];
+        assert!(merge_checkpoints(&longer).is_err());
+        assert!(merge_checkpoints(&[]).is_err());
+    }
+
+    // the policy keeps the best placements, then exchanges and pass in
+    // contention whatever movegen kept, and a forced play joins the decision
+    // under its own stream id.
+    #[test]
+    fn candidate_policy_includes_exchanges_and_pass() {
+        let game_config = game_config::make_english_game_config();
+        let kwg = make_tiny_kwg(TWO_LETTER_WORDS);
+        let klv = klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES);
+        let (mut game_state, mut move_generator) = deal_opening(&game_config, &kwg, &klv, 100);
+        let is_place = |play: &movegen::Play| matches!(play, movegen::Play::Place { .. });
+        let is_pass = |play: &movegen::Play| matches!(play, movegen::Play::Exchange { tiles } if tiles.is_empty());
+        let mut placements = move_generator
+            .plays
+            .iter()
+            .filter(|valued_move| is_place(&valued_move.play))
+            .map(|valued_move| valued_move.equity)
+            .collect::<Vec<_>>();
+        placements.sort_unstable_by(|a, b| b.cmp(a));
+        assert!(placements.len() > 2);
+        let num_plays = move_generator.plays.len();
+        let mut simmer = Simmer::new(&game_config, &kwg, &klv);
+        simmer.select_candidates(&mut move_generator, &game_state);
+        assert_eq!(move_generator.plays.len(), num_plays);
+
+        simmer.set_candidate_policy(Some(CandidatePolicy {
+            num_placements: 2,
+            num_exchanges: 3,
+            include_pass: true,
+        }));
+        simmer.select_candidates(&mut move_generator, &game_state);
+        let plays = &move_generator.plays;
+        assert_eq!(plays.len(), 6);
+        // the top two placements, which outscore the zero-valued exchanges and
+        // pass of the empty leaves, come first.
+        assert!(
+            plays[..2]
+                .iter()
+                .all(|valued_move| is_place(&valued_move.play))
+        );
+        assert_eq!(
+            plays[..2]
+                .iter()
+                .map(|valued_move| valued_move.equity)
+                .collect::<Vec<_>>(),
+            placements[..2]
+        );
+        assert!(plays.windows(2).all(|w| w[0].equity >= w[1].equity));
+        assert!(
+            plays[2..]
+                .iter()
+                .all(|valued_move| !is_place(&valued_move.play))
+        );
+        assert_eq!(
+            plays
+                .iter()
+                .filter(|valued_move| is_pass(&valued_move.play))
+                .count(),
+            1
+        );
+
+        simmer.reseed(3);
+        simmer.begin_decision(&move_generator, &game_state, 4);
+        let forced = movegen::Play::Exchange {
+            tiles: game_state.current_player().rack[..].into(),
+        };
+        let stream_id =
+            simmer.force_play(&mut move_generator, forced.clone(), equity::Equity::new(0));
+        assert_eq!(stream_id, 6);
+        assert!(move_generator.plays[6].play == forced);
+        assert_eq!(
+            simmer.force_play(&mut move_generator, forced, equity::Equity::new(0)),
+            stream_id
+        );
+        assert_eq!(simmer.candidates.len() + simmer.retired.len(), 7);
+
+        // too few tiles in the bag to exchange leaves only the pass.
+        game_state.bag.set_from_iter(std::iter::empty());
+        simmer.select_candidates(&mut move_generator, &game_state);
+        assert_eq!(move_generator.plays.len(), 3);
+        assert!(is_pass(&move_generator.plays[2].play));
+    }
 }
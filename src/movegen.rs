@@ -618,8 +618,8 @@ impl WorkingBuffer {
 pub struct BoardSnapshot<'a, N: kwg::Node, L: kwg::Node> {
     pub board_tiles: &'a [u8],
     pub game_config: &'a game_config::GameConfig,
-    pub kwg: &'a kwg::Kwg<N>,
-    pub klv: &'a klv::Klv<L>,
+    pub kwg: &'a kwg::Kwg<'a, N>,
+    pub klv: &'a klv::Klv<'a, L>,
 }
 
 // cached_cross_sets is just one strip, so it is transposed from cross_sets
@@ -3315,7 +3315,7 @@ struct GenRemainingConnectedWordsParams<'a, N: kwg::Node> {
     board_strip: &'a [u8],
     rack_tally: &'a mut [u8],
     word_strip_buffer: &'a mut [u8],
-    kwg: &'a kwg::Kwg<N>,
+    kwg: &'a kwg::Kwg<'a, N>,
 }
 
 fn gen_remaining_connected_words<
@@ -3512,7 +3512,7 @@ fn gen_remaining_connected_words<
 struct GenRemainingUnconnectedWordsParams<'a, N: kwg::Node> {
     rack_tally: &'a mut [u8],
     word_vec: &'a mut Vec<u8>,
-    kwg: &'a kwg::Kwg<N>,
+    kwg: &'a kwg::Kwg<'a, N>,
     max_len: usize,
 }
 
@@ -3525,7 +3525,7 @@ fn gen_remaining_unconnected_words<'a, FoundWord: 'a + FnMut(&[u8]), N: kwg::Nod
     struct Env<'a, FoundWord: 'a + FnMut(&[u8]), N: kwg::Node> {
         rack_tally: &'a mut [u8],
         word_vec: &'a mut Vec<u8>,
-        kwg: &'a kwg::Kwg<N>,
+        kwg: &'a kwg::Kwg<'a, N>,
         max_len: usize,
         found_word: FoundWord,
     }
@@ -3653,7 +3653,7 @@ fn gen_remaining_words<'a, FoundWord: 'a + FnMut(&[u8]), N: kwg::Node, L: kwg::N
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{alphabet, bites, build, game_config, klv, kwg};
+    use crate::{alphabet, bites, build, game_config, klv, kwg, play_scorer};
 
     // A move generator's cross-set score cache keys the reuse check on the
     // exact board tile (letter plus the 0x80 blank bit). Only the endgame
@@ -3748,4 +3748,97 @@ mod tests {
         assert_eq!(cross_sets[1].score, 0);
         assert_eq!(cross_sets[3].score, 0);
     }
+
+    // movegen, play_scorer::compute_score and the census's
+    // score_and_blank_deltas all take the play bonus from the config's table,
+    // so on a table that rewards five-, six- and seven-tile plays they agree
+    // with each other, and differ from the same play on the default table by
+    // exactly the change in bonus.
+    #[test]
+    fn num_played_bonus_table_is_shared() {
+        let english = game_config::make_english_game_config();
+        // a bonus for every play of five or more tiles, not just the bingo.
+        let gc = game_config::make_game_config_from_rules(
+            r#"{ "num_played_bonuses": [0, 0, 0, 0, 0, 10, 20, 35] }"#,
+        )
+        .unwrap();
+        let reader = alphabet::AlphabetReader::new_for_words(gc.alphabet());
+        let mut words = ["AB", "ABC", "ABCD", "ABCDE", "ABCDEF", "ABCDEFG"]
+            .iter()
+            .map(|w| {
+                let mut buf = Vec::new();
+                reader.set_word(w, &mut buf).unwrap();
+                bites::Bites::from(&buf[..])
+            })
+            .collect::<Vec<_>>();
+        words.sort_unstable();
+        let kwg = kwg::Kwg::<kwg::Node22>::from_bytes_alloc(
+            &build::build(
+                build::BuildContent::Gaddawg,
+                build::BuildLayout::Wolges,
+                &words,
+            )
+            .unwrap(),
+        );
+        let klv = klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES);
+        let dim = gc.board_layout().dim();
+        let board_tiles = vec![0u8; dim.rows as usize * dim.cols as usize];
+        let board_snapshot = &BoardSnapshot {
+            board_tiles: &board_tiles,
+            game_config: &gc,
+            kwg: &kwg,
+            klv: &klv,
+        };
+        let english_snapshot = &BoardSnapshot {
+            game_config: &english,
+            ..*board_snapshot
+        };
+        let rack = [1, 2, 3, 4, 5, 6, 7]; // ABCDEFG
+        let mut move_generator = KurniaMoveGenerator::new(&gc);
+        move_generator.gen_moves_unfiltered(&GenMovesParams {
+            board_snapshot,
+            rack: &rack,
+            max_gen: usize::MAX,
+            num_exchanges_by_this_player: 0,
+            always_include_pass: false,
+            dynamic_leaves: None,
+        });
+        let mut ps = play_scorer::PlayScorer::new();
+        let mut deltas = Vec::new();
+        let mut seen_lengths = [false; 8];
+        for valued_move in move_generator.plays.iter() {
+            let Play::Place {
+                down,
+                lane,
+                idx,
+                word,
+                score,
+            } = &valued_move.play
+            else {
+                continue;
+            };
+            let num_played = word.iter().filter(|&&t| t != 0).count();
+            seen_lengths[num_played] = true;
+            assert_eq!(ps.compute_score(board_snapshot, &valued_move.play), *score);
+            assert_eq!(
+                play_scorer::score_and_blank_deltas(
+                    board_snapshot,
+                    *down,
+                    *lane,
+                    *idx,
+                    word,
+                    &mut deltas
+                ),
+                *score
+            );
+            let default_score = ps.compute_score(english_snapshot, &valued_move.play);
+            assert_eq!(
+                *score - default_score,
+                (gc.num_played_bonus(num_played as u8) - english.num_played_bonus(num_played as u8))
+                    as i32
+                    * equity::SCALE
+            );
+        }
+        assert!(seen_lengths[2..].iter().all(|&x| x));
+    }
 }
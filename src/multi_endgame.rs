@@ -0,0 +1,283 @@
+// Copyright (C) 2020-2026 Andy Kurnia.
+
+// endgames for more than two players, which EndgameSolver (built around two
+// racks) cannot solve. the bag must be empty and every rack known. this is a
+// plain depth-limited search over whole game states, so it suits short lines
+// and small racks rather than exhaustive solves. MaxN cannot prune, so the
+// default caps keep it to one round over the best few plays of each player.
+
+use super::{error, game_config, game_state, klv, kwg, movegen};
+use rand::SeedableRng;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MultiEndgameStrategy {
+    // every player maximizes their own margin over the best other player.
+    MaxN,
+    // the other players jointly minimize the root player's margin.
+    Paranoid,
+}
+
+pub struct MultiEndgameResult {
+    // None only when no ply was searched (max_depth 0).
+    pub best_play: Option<movegen::Play>,
+    // scores at the end of the principal line, in millipoints. lines cut off
+    // by the depth limit are scored as if every player were stuck with their
+    // rack, as when the game ends on scoreless turns.
+    pub final_scores: Box<[i32]>,
+}
+
+// a player's margin over the best other player.
+#[inline(always)]
+pub fn margin(final_scores: &[i32], player_idx: usize) -> i32 {
+    final_scores[player_idx]
+        - (0..final_scores.len())
+            .filter(|&i| i != player_idx)
+            .map(|i| final_scores[i])
+            .max()
+            .unwrap_or(0)
+}
+
+// the default for max_plays. max_depth defaults to one turn per player.
+pub const DEFAULT_MAX_PLAYS: usize = 12;
+
+// MultiEndgameSolver can only be reused for the same game_config and kwg.
+// (Refer to note at KurniaMoveGenerator.)
+// This is not enforced.
+pub struct MultiEndgameSolver<'a, N: kwg::Node> {
+    game_config: &'a game_config::GameConfig,
+    kwg: &'a kwg::Kwg<'a, N>,
+    klv: klv::Klv<'static, kwg::Node22>,
+    move_generator: movegen::KurniaMoveGenerator,
+    // the bag is empty, so plays never draw from this.
+    rng: rand::rngs::ChaCha20Rng,
+    pub max_depth: u8,
+    // the highest-scoring plays searched per position.
+    pub max_plays: usize,
+}
+
+impl<'a, N: kwg::Node> MultiEndgameSolver<'a, N> {
+    pub fn new(game_config: &'a game_config::GameConfig, kwg: &'a kwg::Kwg<'a, N>) -> Self {
+        Self {
+            game_config,
+            kwg,
+            klv: klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES),
+            move_generator: movegen::KurniaMoveGenerator::new(game_config),
+            rng: rand::rngs::ChaCha20Rng::seed_from_u64(0),
+            max_depth: game_config.num_players(),
+            max_plays: DEFAULT_MAX_PLAYS,
+        }
+    }
+
+    pub fn solve(
+        &mut self,
+        game_state: &game_state::GameState,
+        strategy: MultiEndgameStrategy,
+    ) -> error::Returns<MultiEndgameResult> {
+        if !game_state.bag.is_empty() {
+            return_error!(format!(
+                "not endgame yet as there are {} tiles in the bag",
+                game_state.bag.len()
+            ));
+        }
+        // plays are made and taken back on this one copy.
+        let mut game_state = game_state.clone();
+        game_state.set_keep_history(true);
+        game_state.clear_history();
+        let mut final_scores = vec![0; game_state.players.len()].into_boxed_slice();
+        let root_idx = game_state.turn as usize;
+        let best_play = self.search(
+            &mut game_state,
+            strategy,
+            root_idx,
+            self.max_depth,
+            i32::MIN,
+            i32::MAX,
+            &mut final_scores,
+        )?;
+        Ok(MultiEndgameResult {
+            best_play,
+            final_scores,
+        })
+    }
+
+    // alpha and beta bound the root player's margin, and only prune when
+    // strategy is Paranoid. game_state keeps history, and is left as it was.
+    #[expect(clippy::too_many_arguments)]
+    fn search(
+        &mut self,
+        game_state: &mut game_state::GameState,
+        strategy: MultiEndgameStrategy,
+        root_idx: usize,
+        depth: u8,
+        mut alpha: i32,
+        mut beta: i32,
+        final_scores: &mut [i32],
+    ) -> error::Returns<Option<movegen::Play>> {
+        if depth == 0 {
+            let alphabet = self.game_config.alphabet();
+            for (final_score, player) in final_scores.iter_mut().zip(game_state.players.iter()) {
+                *final_score = player.score - alphabet.scaled_rack_score(&player.rack);
+            }
+            return Ok(None);
+        }
+        self.move_generator
+            .gen_moves_unfiltered(&movegen::GenMovesParams {
+                board_snapshot: &movegen::BoardSnapshot {
+                    board_tiles: &game_state.board_tiles,
+                    game_config: self.game_config,
+                    kwg: self.kwg,
+                    klv: &self.klv,
+                },
+                rack: &game_state.current_player().rack,
+                max_gen: self.max_plays,
+                num_exchanges_by_this_player: game_state.current_player().num_exchanges,
+                always_include_pass: true,
+                dynamic_leaves: None,
+            });
+        // the move generator is reused deeper down. it infers the bag from the
+        // board, so it may offer exchanges the empty bag cannot honor.
+        let plays = self
+            .move_generator
+            .plays
+            .iter()
+            .filter(|valued_move| {
+                !matches!(&valued_move.play, movegen::Play::Exchange { tiles } if !tiles.is_empty())
+            })
+            .map(|valued_move| valued_move.play.clone())
+            .collect::<Vec<_>>();
+        let mover_idx = game_state.turn as usize;
+        // the mover picks by this; Paranoid opponents minimize the root margin.
+        let (value_idx, maximizing) = match strategy {
+            MultiEndgameStrategy::MaxN => (mover_idx, true),
+            MultiEndgameStrategy::Paranoid => (root_idx, mover_idx == root_idx),
+        };
+        let mut best_value = if maximizing { i32::MIN } else { i32::MAX };
+        let mut best_play_idx = 0;
+        let mut child_scores = vec![0; final_scores.len()];
+        for (play_idx, play) in plays.iter().enumerate() {
+            game_state.play(self.game_config, &mut self.rng, play)?;
+            let searched = match game_state.check_game_ended(self.game_config, &mut child_scores) {
+                game_state::CheckGameEnded::NotEnded => {
+                    game_state.next_turn();
+                    self.search(
+                        game_state,
+                        strategy,
+                        root_idx,
+                        depth - 1,
+                        alpha,
+                        beta,
+                        &mut child_scores,
+                    )
+                    .map(|_| ())
+                }
+                _ => Ok(()),
+            };
+            game_state.undo(self.game_config);
+            searched?;
+            let value = margin(&child_scores, value_idx);
+            if (maximizing && value > best_value) || (!maximizing && value < best_value) {
+                best_value = value;
+                best_play_idx = play_idx;
+                final_scores.copy_from_slice(&child_scores);
+            }
+            if strategy == MultiEndgameStrategy::Paranoid {
+                if maximizing {
+                    alpha = alpha.max(value);
+                } else {
+                    beta = beta.min(value);
+                }
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+        Ok(plays.into_iter().nth(best_play_idx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equity;
+    use crate::test_util::make_tiny_kwg;
+
+    fn make_position(
+        game_config: &game_config::GameConfig,
+        racks: &[&[u8]],
+    ) -> game_state::GameState {
+        let mut game_state = game_state::GameState::new(game_config);
+        game_state.bag.set_from_iter(std::iter::empty());
+        for (player, rack) in game_state.players.iter_mut().zip(racks.iter()) {
+            player.rack.clear();
+            player.rack.extend_from_slice(rack);
+        }
+        game_state
+    }
+
+    // going out collects every other rack, by either strategy; with the bag
+    // still full there is no endgame to solve.
+    #[test]
+    fn three_player_endgame_goes_out() {
+        let game_config =
+            game_config::make_game_config_from_rules(r#"{ "num_players": 3 }"#).unwrap();
+        let kwg = make_tiny_kwg(&["AT", "TA", "ZA"]);
+        // AT, QV, QV: only the first player can ever play.
+        let game_state = make_position(&game_config, &[&[1, 20], &[17, 22], &[17, 22]]);
+        let mut solver = MultiEndgameSolver::new(&game_config, &kwg);
+        for strategy in [MultiEndgameStrategy::MaxN, MultiEndgameStrategy::Paranoid] {
+            let result = solver.solve(&game_state, strategy).unwrap();
+            assert!(matches!(
+                result.best_play,
+                Some(movegen::Play::Place { score, .. }) if score == equity::scale_score(4)
+            ));
+            // AT doubled, plus both QV racks (14 each).
+            assert_eq!(
+                &result.final_scores[..],
+                [
+                    equity::scale_score(32),
+                    equity::scale_score(-14),
+                    equity::scale_score(-14),
+                ]
+            );
+            assert_eq!(margin(&result.final_scores, 0), equity::scale_score(46));
+        }
+
+        let mut game_state = game_state::GameState::new(&game_config);
+        game_state
+            .reset_and_draw_tiles(&game_config, &mut rand::rngs::ChaCha20Rng::seed_from_u64(0));
+        assert!(
+            solver
+                .solve(&game_state, MultiEndgameStrategy::MaxN)
+                .is_err()
+        );
+    }
+
+    // the first player can only pass, and the search sees the second player
+    // go out with ZA after that.
+    #[test]
+    fn three_player_endgame_looks_ahead() {
+        let game_config =
+            game_config::make_game_config_from_rules(r#"{ "num_players": 3 }"#).unwrap();
+        let kwg = make_tiny_kwg(&["AT", "TA", "ZA"]);
+        let game_state = make_position(&game_config, &[&[17], &[26, 1], &[22]]);
+        let mut solver = MultiEndgameSolver::new(&game_config, &kwg);
+        for strategy in [MultiEndgameStrategy::MaxN, MultiEndgameStrategy::Paranoid] {
+            let result = solver.solve(&game_state, strategy).unwrap();
+            // Q cannot be played, so the first player passes.
+            assert!(matches!(
+                &result.best_play,
+                Some(movegen::Play::Exchange { tiles }) if tiles.is_empty()
+            ));
+            // ZA doubled (22), plus Q (10) and V (4).
+            assert_eq!(
+                &result.final_scores[..],
+                [
+                    equity::scale_score(-10),
+                    equity::scale_score(36),
+                    equity::scale_score(-4),
+                ]
+            );
+            assert_eq!(margin(&result.final_scores, 1), equity::scale_score(40));
+        }
+    }
+}
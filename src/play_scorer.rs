@@ -2,6 +2,18 @@
 
 use super::{equity, error, game_config, game_state, kwg, move_filter, movegen};
 
+// One word in a row or column of the board, as found by audit_board.
+pub struct AuditedWord {
+    pub down: bool,
+    pub lane: i8,
+    pub idx: i8,
+    pub word: Box<[u8]>, // as on the board, so blanks have 0x80
+    pub valid: bool,
+    // the index in the history of the play that completed this word, or None
+    // if some of its tiles were not placed by the history.
+    pub introduced_by: Option<usize>,
+}
+
 pub struct PlayScorer {
     rack_tally: Vec<u8>,
     word_iter: move_filter::LimitedVocabChecker,
@@ -334,6 +346,88 @@ impl PlayScorer {
         };
     }
 
+    // Every word of two or more tiles in every row and column, in row then
+    // column order. history is the plays that led to the board, oldest first,
+    // and may be empty or start from a position already on the board.
+    pub fn audit_board<N: kwg::Node, L: kwg::Node>(
+        &mut self,
+        board_snapshot: &movegen::BoardSnapshot<'_, N, L>,
+        history: &[movegen::Play],
+    ) -> error::Returns<Vec<AuditedWord>> {
+        let dim = board_snapshot.game_config.board_layout().dim();
+        let board_tiles = board_snapshot.board_tiles;
+        // the history index of the play that placed each square.
+        let mut placed_by = vec![None; board_tiles.len()];
+        for (turn, play) in history.iter().enumerate() {
+            if let movegen::Play::Place {
+                down,
+                lane,
+                idx,
+                word,
+                ..
+            } = play
+            {
+                let strider = dim.lane(*down, *lane);
+                if *lane < 0
+                    || *lane >= (if *down { dim.cols } else { dim.rows })
+                    || *idx < 0
+                    || *idx as usize + word.len() > strider.len() as usize
+                {
+                    return_error!(format!("play {turn} is off the board"));
+                }
+                for (i, &tile) in (*idx..).zip(word.iter()) {
+                    if tile != 0 {
+                        let at = strider.at(i);
+                        if placed_by[at].is_some() || board_tiles[at] != tile {
+                            return_error!(format!("play {turn} does not match the board"));
+                        }
+                        placed_by[at] = Some(turn);
+                    }
+                }
+            }
+        }
+
+        let mut audited_words = Vec::new();
+        for down in [false, true] {
+            for lane in 0..(if down { dim.cols } else { dim.rows }) {
+                let strider = dim.lane(down, lane);
+                let mut i = 0;
+                while i < strider.len() {
+                    if board_tiles[strider.at(i)] == 0 {
+                        i += 1;
+                        continue;
+                    }
+                    let start = i;
+                    while i < strider.len() && board_tiles[strider.at(i)] != 0 {
+                        i += 1;
+                    }
+                    if i - start < 2 {
+                        continue;
+                    }
+                    // placing nothing but play-throughs checks just this word.
+                    let play = movegen::Play::Place {
+                        down,
+                        lane,
+                        idx: start,
+                        word: vec![0u8; (i - start) as usize][..].into(),
+                        score: 0,
+                    };
+                    audited_words.push(AuditedWord {
+                        down,
+                        lane,
+                        idx: start,
+                        word: (start..i).map(|j| board_tiles[strider.at(j)]).collect(),
+                        valid: self.words_are_valid(board_snapshot, &play),
+                        // the word took its final extent with its last tile.
+                        introduced_by: (start..i)
+                            .try_fold(0, |acc, j| Some(acc.max(placed_by[strider.at(j)]?))),
+                    });
+                }
+            }
+        }
+        Ok(audited_words)
+    }
+
     // Unused &mut self for future-proofing.
     // Assume play is valid.
     pub fn compute_score<N: kwg::Node, L: kwg::Node>(
@@ -702,3 +796,77 @@ pub fn score_and_blank_deltas<N: kwg::Node, L: kwg::Node>(
 
     recounted_score
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::klv;
+    use crate::test_util::make_tiny_kwg;
+
+    // CAT across, then AT down through its A. QI is not a word here, and no
+    // play in the history placed it.
+    #[test]
+    fn audit_board_finds_every_word() {
+        let game_config = game_config::make_english_game_config();
+        let kwg = make_tiny_kwg(&["AT", "CAT"]);
+        let klv = klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES);
+        let dim = game_config.board_layout().dim();
+        let mut board_tiles = vec![0u8; 15 * 15];
+        // CAT at 8G, AT (with a blank T) at H8, and QI at 1A.
+        board_tiles[dim.at_row_col(7, 6)] = 3;
+        board_tiles[dim.at_row_col(7, 7)] = 1;
+        board_tiles[dim.at_row_col(7, 8)] = 20;
+        board_tiles[dim.at_row_col(8, 7)] = 0x94;
+        board_tiles[dim.at_row_col(0, 0)] = 17;
+        board_tiles[dim.at_row_col(0, 1)] = 9;
+        let history = [
+            movegen::Play::Place {
+                down: false,
+                lane: 7,
+                idx: 6,
+                word: [3, 1, 20][..].into(),
+                score: 0,
+            },
+            movegen::Play::Exchange {
+                tiles: [][..].into(),
+            },
+            movegen::Play::Place {
+                down: true,
+                lane: 7,
+                idx: 7,
+                word: [0, 0x94][..].into(),
+                score: 0,
+            },
+        ];
+        let board_snapshot = movegen::BoardSnapshot {
+            board_tiles: &board_tiles,
+            game_config: &game_config,
+            kwg: &kwg,
+            klv: &klv,
+        };
+        let mut play_scorer = PlayScorer::new();
+        let audited_words = play_scorer.audit_board(&board_snapshot, &history).unwrap();
+        let summary = audited_words
+            .iter()
+            .map(|w| (w.down, w.lane, w.idx, &w.word[..], w.valid, w.introduced_by))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                (false, 0, 0, &[17, 9][..], false, None),
+                (false, 7, 6, &[3, 1, 20][..], true, Some(0)),
+                (true, 7, 7, &[1, 0x94][..], true, Some(2)),
+            ]
+        );
+
+        // the history must agree with the board.
+        board_tiles[dim.at_row_col(8, 7)] = 20;
+        let board_snapshot = movegen::BoardSnapshot {
+            board_tiles: &board_tiles,
+            game_config: &game_config,
+            kwg: &kwg,
+            klv: &klv,
+        };
+        assert!(play_scorer.audit_board(&board_snapshot, &history).is_err());
+    }
+}
@@ -1,7 +1,7 @@
 // Copyright (C) 2020-2026 Andy Kurnia.
 
-use super::{equity, game_config, game_state, klv, kwg, movegen, win_pct};
-use rand::SeedableRng;
+use super::{equity, game_config, game_state, inference, klv, kwg, move_filter, movegen, win_pct};
+use rand::prelude::*;
 
 /// Whole-point spread from a millipoint spread. Player scores and klv leave
 /// values are accumulated in millipoints (equity::SCALE = 1000), but the
@@ -18,7 +18,8 @@ pub fn spread_points(millipoints: i32) -> f64 {
 /// position. Sigmoid is the shipped default: a hand-tuned sigmoid of the score
 /// margin. Table looks the position up in an empirical WinPctTable and falls
 /// back to the sigmoid for any count-and-margin the table never sampled.
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum WinProbSource {
     Sigmoid,
     Table,
@@ -28,7 +29,8 @@ pub enum WinProbSource {
 /// is the shipped configuration. `descale: false` reproduces the pre-descale
 /// units mismatch on purpose; it exists only as an A/B baseline and is never
 /// shipped.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct SimmerConfig {
     /// Feed the spread to the sigmoid and objective in points (true) instead of
     /// raw millipoints (false, the pre-descale baseline).
@@ -90,6 +92,39 @@ pub fn sim_objective(sim_spread: i32, win_prob: f64, weightage: f64, descale: bo
     spread + win_prob * weightage
 }
 
+/// num_sim_plies that rolls out until the game ends.
+pub const PLAY_TO_END: usize = usize::MAX;
+
+/// How each simulated ply after the candidate chooses its move. TopEquity is the
+/// shipped default. Filtered takes the top move a filtered generator finds, such
+/// as a weaker bot's move_filter::GenMoves::Tilt, tilted as its caller left it.
+/// Softmax draws from the top_n moves by static equity, weighting each by
+/// exp(equity / temperature) with equity in points, so a higher temperature
+/// plays more like a fallible opponent.
+#[derive(Clone)]
+pub enum PlyPolicy {
+    TopEquity,
+    Filtered(move_filter::GenMoves<'static>),
+    Softmax { top_n: usize, temperature: f64 },
+}
+
+// the index of a play drawn by softmax of equity, plays being best first.
+fn softmax_index(plays: &[movegen::ValuedMove], temperature: f64, rng: &mut dyn Rng) -> usize {
+    if plays.len() < 2 || temperature <= 0.0 {
+        return 0;
+    }
+    let best = plays[0].equity.as_f64();
+    let weight = |play: &movegen::ValuedMove| ((play.equity.as_f64() - best) / temperature).exp();
+    let mut r = rng.random::<f64>() * plays.iter().map(weight).sum::<f64>();
+    for (i, play) in plays.iter().enumerate() {
+        r -= weight(play);
+        if r < 0.0 {
+            return i;
+        }
+    }
+    plays.len() - 1
+}
+
 fn set_rack_tally_from_leave(rack_tally: &mut [u8], rack: &[u8], play: &movegen::Play) {
     rack_tally.iter_mut().for_each(|m| *m = 0);
     rack.iter().for_each(|&tile| rack_tally[tile as usize] += 1);
@@ -119,8 +154,14 @@ pub struct Simmer {
     initial_game_state: game_state::GameState,
     pub initial_score_spread: i32,
     num_sim_plies: usize,
+    num_rollout_plies: usize,
     num_tiles_that_matter: usize,
     win_prob_weightage: f64,
+    // per player, what they are inferred to have kept (see set_opponent_leaves).
+    opponent_leaves: Box<[Option<inference::LeaveInference>]>,
+    // per rollout ply after the candidate, the last one repeating. empty means
+    // TopEquity throughout. kept across prepare() (see set_ply_policies).
+    ply_policies: Box<[PlyPolicy]>,
 
     // simulate() simulates a single iteration and sets these
     game_state: game_state::GameState,
@@ -143,8 +184,11 @@ impl Simmer {
             initial_game_state: game_state::GameState::new(game_config),
             initial_score_spread: 0,
             num_sim_plies: 0,
+            num_rollout_plies: 0,
             num_tiles_that_matter: 0,
             win_prob_weightage: 0.0,
+            opponent_leaves: vec![None; game_config.num_players() as usize].into_boxed_slice(),
+            ply_policies: Box::new([]),
 
             game_state: game_state::GameState::new(game_config),
             last_seen_leave_values: vec![0i32; game_config.num_players() as usize]
@@ -175,6 +219,14 @@ impl Simmer {
         self.config = config;
     }
 
+    /// Choose each rollout ply's move by these policies: the first for the ply
+    /// after the candidate, and so on, the last repeating to the end of the
+    /// rollout. Empty keeps the top static equity move throughout.
+    #[inline(always)]
+    pub fn set_ply_policies(&mut self, ply_policies: Vec<PlyPolicy>) {
+        self.ply_policies = ply_policies.into_boxed_slice();
+    }
+
     /// Build a fresh simmer already prepared to this one's current position and
     /// config, for a worker thread that runs its own rollouts. The ChaCha20 RNG
     /// is not Clone, so this rebuilds from the retained initial game state rather
@@ -186,10 +238,23 @@ impl Simmer {
     pub fn prepared_clone(&self, game_config: &game_config::GameConfig) -> Self {
         let mut clone = Simmer::new(game_config);
         clone.config = self.config;
+        clone.ply_policies.clone_from(&self.ply_policies);
         clone.prepare(game_config, &self.initial_game_state, self.num_sim_plies);
+        clone.opponent_leaves.clone_from(&self.opponent_leaves);
         clone
     }
 
+    /// The RNG stream position, so a checkpoint can resume the same stream.
+    #[inline(always)]
+    pub fn rng_state(&self) -> [u8; 49] {
+        self.rng.serialize_state()
+    }
+
+    #[inline(always)]
+    pub fn set_rng_state(&mut self, rng_state: &[u8; 49]) {
+        self.rng = rand::rngs::ChaCha20Rng::deserialize_state(rng_state);
+    }
+
     #[inline(always)]
     pub fn config(&self) -> &SimmerConfig {
         &self.config
@@ -212,7 +277,20 @@ impl Simmer {
                 .max()
                 .unwrap_or(0);
         self.num_sim_plies = num_sim_plies;
-        self.num_tiles_that_matter = num_sim_plies * game_config.rack_size() as usize;
+        self.opponent_leaves.iter_mut().for_each(|m| *m = None);
+        // num_sim_plies counts two-player plies. With more players, each of
+        // the two-player game's opponent turns becomes a turn for every
+        // opponent, so each opponent's reply is modeled. PLAY_TO_END saturates.
+        self.num_rollout_plies = num_sim_plies.saturating_add(
+            game_state
+                .players
+                .len()
+                .saturating_sub(2)
+                .saturating_mul(num_sim_plies.div_ceil(2)),
+        );
+        self.num_tiles_that_matter = self
+            .num_rollout_plies
+            .saturating_mul(game_config.rack_size() as usize);
         let mut num_unseen_tiles = self.initial_game_state.bag.len();
         let initial_turn = self.initial_game_state.turn as usize;
         for (i, player) in self.initial_game_state.players.iter_mut().enumerate() {
@@ -225,7 +303,7 @@ impl Simmer {
         self.win_prob_weightage = if num_unseen_tiles <= self.num_tiles_that_matter {
             // possible to play out
             w_out
-        } else if num_unseen_tiles < 2 * self.num_tiles_that_matter {
+        } else if num_unseen_tiles < self.num_tiles_that_matter.saturating_mul(2) {
             w_out
                 + ((num_unseen_tiles - self.num_tiles_that_matter) as f64
                     / self.num_tiles_that_matter as f64)
@@ -255,6 +333,15 @@ impl Simmer {
         self.initial_game_state.clone_from(pristine);
     }
 
+    /// After prepare(), have prepare_iteration() deal this opponent a leave
+    /// drawn from the inference (such as from inference::infer_leaves on their
+    /// last play) before filling the rest of their rack from the unseen tiles,
+    /// instead of dealing the whole rack uniformly.
+    #[inline(always)]
+    pub fn set_opponent_leaves(&mut self, player_idx: usize, leaves: inference::LeaveInference) {
+        self.opponent_leaves[player_idx] = Some(leaves);
+    }
+
     #[inline(always)]
     pub fn prepare_iteration(&mut self) {
         let initial_turn = self.initial_game_state.turn as usize;
@@ -263,6 +350,33 @@ impl Simmer {
                 self.final_scores[i] = player.rack.len() as i32;
                 self.initial_game_state.bag.return_tiles(&player.rack);
                 player.rack.clear();
+                // tiles known to be on the rack stay there.
+                for &tile in player.known.present.iter() {
+                    if player.rack.len() < self.final_scores[i] as usize
+                        && self.initial_game_state.bag.remove_tile(tile).is_some()
+                    {
+                        player.rack.push(tile);
+                    }
+                }
+            }
+        }
+        for (i, opponent_leaves) in self.opponent_leaves.iter().enumerate() {
+            if let Some(opponent_leaves) = opponent_leaves
+                && let Some(leave) = opponent_leaves.sample(&mut self.rng)
+            {
+                let player = &mut self.initial_game_state.players[i];
+                for (j, &tile) in leave.iter().enumerate() {
+                    // known tiles are already on the rack, and another
+                    // opponent's leave may have taken the tile already.
+                    if player.rack.len() < self.final_scores[i] as usize
+                        && !player.known.absent.contains(&tile)
+                        && leave[..=j].iter().filter(|&&t| t == tile).count()
+                            > player.known.present.iter().filter(|&&t| t == tile).count()
+                        && self.initial_game_state.bag.remove_tile(tile).is_some()
+                    {
+                        player.rack.push(tile);
+                    }
+                }
             }
         }
         let num_tiles_that_matter = self.num_tiles_that_matter;
@@ -271,11 +385,20 @@ impl Simmer {
             .shuffle_n(&mut self.rng, num_tiles_that_matter);
         for (i, player) in self.initial_game_state.players.iter_mut().enumerate() {
             if i != initial_turn {
-                self.initial_game_state.bag.replenish(
-                    &mut player.rack,
-                    self.final_scores[i] as usize,
-                    i,
-                );
+                if player.known.absent.is_empty() {
+                    self.initial_game_state.bag.replenish(
+                        &mut player.rack,
+                        self.final_scores[i] as usize,
+                        i,
+                    );
+                } else {
+                    self.initial_game_state.bag.replenish_excluding(
+                        &mut player.rack,
+                        self.final_scores[i] as usize,
+                        i,
+                        &player.known.absent,
+                    );
+                }
             }
         }
     }
@@ -307,29 +430,56 @@ impl Simmer {
         let mut next_play = movegen::Play::Exchange {
             tiles: [][..].into(),
         };
-        for ply in 0..=self.num_sim_plies {
-            next_play.clone_from(if ply == 0 {
-                candidate_play
-            } else {
-                self.move_generator
-                    .gen_moves_unfiltered(&movegen::GenMovesParams {
-                        board_snapshot: &movegen::BoardSnapshot {
-                            board_tiles: &self.game_state.board_tiles,
-                            game_config,
-                            kwg,
-                            klv,
-                        },
-                        rack: &self.game_state.current_player().rack,
-                        max_gen: 1,
-                        num_exchanges_by_this_player: self
-                            .game_state
-                            .current_player()
-                            .num_exchanges,
-                        always_include_pass: false,
-                        dynamic_leaves: None,
-                    });
-                &self.move_generator.plays[0].play
+        for ply in 0..=self.num_rollout_plies {
+            let rng = rollout_rng.get_or_insert_with(|| {
+                rand::rngs::ChaCha20Rng::deserialize_state(&self.rng.serialize_state())
             });
+            if ply == 0 {
+                next_play.clone_from(candidate_play);
+            } else {
+                let board_snapshot = &movegen::BoardSnapshot {
+                    board_tiles: &self.game_state.board_tiles,
+                    game_config,
+                    kwg,
+                    klv,
+                };
+                let current_player = self.game_state.current_player();
+                let num_policies = self.ply_policies.len();
+                let policy = (num_policies > 0)
+                    .then(|| &mut self.ply_policies[(ply - 1).min(num_policies - 1)]);
+                let mut softmax_temperature = None;
+                let (filtered_movegen, max_gen) = match policy {
+                    None | Some(PlyPolicy::TopEquity) => (None, 1),
+                    Some(PlyPolicy::Filtered(filtered_movegen)) => (Some(filtered_movegen), 1),
+                    Some(PlyPolicy::Softmax { top_n, temperature }) => {
+                        softmax_temperature = Some(*temperature);
+                        (None, (*top_n).max(1))
+                    }
+                };
+                match filtered_movegen {
+                    Some(filtered_movegen) => filtered_movegen.gen_moves(
+                        &mut self.move_generator,
+                        board_snapshot,
+                        &current_player.rack,
+                        current_player.num_exchanges,
+                        max_gen,
+                    ),
+                    None => self
+                        .move_generator
+                        .gen_moves_unfiltered(&movegen::GenMovesParams {
+                            board_snapshot,
+                            rack: &current_player.rack,
+                            max_gen,
+                            num_exchanges_by_this_player: current_player.num_exchanges,
+                            always_include_pass: false,
+                            dynamic_leaves: None,
+                        }),
+                }
+                let idx = softmax_temperature.map_or(0, |temperature| {
+                    softmax_index(&self.move_generator.plays, temperature, rng)
+                });
+                next_play.clone_from(&self.move_generator.plays[idx].play);
+            }
             set_rack_tally_from_leave(
                 &mut self.rack_tally,
                 &self.game_state.current_player().rack,
@@ -337,9 +487,6 @@ impl Simmer {
             );
             self.last_seen_leave_values[self.game_state.turn as usize] =
                 klv.leave_value_from_tally(&self.rack_tally);
-            let rng = rollout_rng.get_or_insert_with(|| {
-                rand::rngs::ChaCha20Rng::deserialize_state(&self.rng.serialize_state())
-            });
             self.game_state.play(game_config, rng, &next_play).unwrap();
             match self
                 .game_state
@@ -404,6 +551,7 @@ impl Simmer {
             // count-state and margin, use it; otherwise fall through to the
             // sigmoid. The default (Sigmoid, or no table) runs neither branch
             // and is byte-identical to the pre-table path.
+            let num_players = self.game_state.players.len();
             if self.config.win_prob_source == WinProbSource::Table
                 && let Some(table) = table
             {
@@ -411,13 +559,21 @@ impl Simmer {
                     .rack
                     .len();
                 let opp = racks_total - my;
-                if let Some(win_prob) =
-                    table.get_opt(equity::descale_score(final_spread), bag, my, opp)
-                {
+                if let Some(win_prob) = table.get_opt_n_player(
+                    num_players,
+                    equity::descale_score(final_spread),
+                    bag,
+                    my,
+                    opp,
+                ) {
                     return win_prob as f64;
                 }
             }
+            // like the table, key on the lead over the best opponent. Winning
+            // means beating every opponent, so with more than one the sigmoid
+            // treats that lead as the margin over each of them independently.
             win_prob_unfinished(final_spread, bag + racks_total, &self.config)
+                .powi(num_players as i32 - 1)
         }
     }
 
@@ -430,6 +586,7 @@ impl Simmer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::{TWO_LETTER_WORDS, make_tiny_kwg};
 
     // A rollout that exchanges tiles must draw from a copy of self.rng, not
     // self.rng itself, so the shared post-prepare_iteration state stays put for
@@ -631,4 +788,220 @@ mod tests {
         let got = simmer.compute_win_prob(false, final_spread, Some(&table));
         assert_eq!(got, win_prob_unfinished(final_spread, bag + my + opp, &cfg));
     }
+
+    // with three players, num_sim_plies 2 rolls out both opponents' replies and
+    // the mover's next turn, and an unfinished rollout's win probability
+    // requires beating both opponents.
+    #[test]
+    fn three_player_rollout_models_each_opponent() {
+        let game_config =
+            game_config::make_game_config_from_rules(r#"{ "num_players": 3 }"#).unwrap();
+        let mut game_state = game_state::GameState::new(&game_config);
+        let mut deal_rng = rand::rngs::ChaCha20Rng::seed_from_u64(1);
+        game_state.reset_and_draw_tiles(&game_config, &mut deal_rng);
+        let kwg = make_tiny_kwg(&[]);
+        let klv = klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES);
+        let mut simmer = Simmer::new(&game_config);
+        simmer.reseed(5);
+        simmer.prepare(&game_config, &game_state, 2);
+        assert_eq!(simmer.num_rollout_plies, 3);
+        simmer.prepare_iteration();
+        let pass = movegen::Play::Exchange {
+            tiles: [][..].into(),
+        };
+        // nothing can be placed with an empty kwg, so every turn scores zero.
+        assert!(!simmer.simulate(&game_config, &kwg, &klv, &pass));
+        assert_eq!(simmer.game_state.zero_turns, 4);
+        assert_eq!(simmer.final_equity_spread(), 0);
+
+        // level with both opponents: two coin flips, not one.
+        let win_prob = simmer.compute_win_prob(false, 0, None);
+        assert!((win_prob - 0.25).abs() < 1e-9, "{win_prob}");
+        // the lead that counts is the one over the best opponent.
+        simmer.game_state.players[0].score += 40 * equity::SCALE;
+        simmer.game_state.players[1].score += 30 * equity::SCALE;
+        let spread = simmer.final_equity_spread();
+        assert_eq!(spread, 10 * equity::SCALE);
+        let ahead = simmer.compute_win_prob(false, spread, None);
+        let unseen = simmer.game_state.bag.len() + 3 * game_config.rack_size() as usize;
+        let one = win_prob_unfinished(spread, unseen, simmer.config());
+        assert!((ahead - one * one).abs() < 1e-9, "{ahead}");
+
+        // a table row for this count-state answers instead, on the same key.
+        let mut acc = win_pct::WinPctAccumulator::new();
+        let my = simmer.game_state.players[0].rack.len();
+        let opp = unseen - simmer.game_state.bag.len() - my;
+        acc.record_n_player(3, simmer.game_state.bag.len(), my, opp, 0, -20);
+        let table = acc.finalize();
+        simmer.config.win_prob_source = WinProbSource::Table;
+        let from_table = simmer.compute_win_prob(false, spread, Some(&table));
+        let expected = table
+            .get_opt_n_player(
+                3,
+                equity::descale_score(spread),
+                simmer.game_state.bag.len(),
+                my,
+                opp,
+            )
+            .unwrap();
+        assert_eq!(from_table, expected as f64);
+    }
+
+    // an inferred leave is always kept, and the rest of the rack is drawn.
+    #[test]
+    fn prepare_iteration_deals_inferred_leaves() {
+        let game_config = game_config::make_english_game_config();
+        let mut game_state = game_state::GameState::new(&game_config);
+        let mut deal_rng = rand::rngs::ChaCha20Rng::seed_from_u64(1);
+        game_state.reset_and_draw_tiles(&game_config, &mut deal_rng);
+        // a tile the player on turn cannot see.
+        let kept = game_state.players[1].rack[0];
+        let mut simmer = Simmer::new(&game_config);
+        simmer.reseed(7);
+        simmer.prepare(&game_config, &game_state, 2);
+        simmer.set_opponent_leaves(
+            1,
+            inference::LeaveInference::from_leaves(vec![inference::InferredLeave {
+                leave: [kept][..].into(),
+                weight: 1.0,
+            }]),
+        );
+        let pristine = simmer.prepared_state().clone();
+        for _ in 0..10 {
+            simmer.restore_prepared(&pristine);
+            simmer.prepare_iteration();
+            let rack = &simmer.initial_game_state.players[1].rack;
+            assert_eq!(rack.len(), 7);
+            assert!(rack.contains(&kept));
+        }
+        // prepare() forgets the inference.
+        simmer.prepare(&game_config, &game_state, 2);
+        assert!(simmer.opponent_leaves.iter().all(|m| m.is_none()));
+    }
+
+    // known tiles stay on the rack and known-absent tiles are never drawn.
+    #[test]
+    fn prepare_iteration_keeps_known_tiles() {
+        let game_config = game_config::make_english_game_config();
+        let mut game_state = game_state::GameState::new(&game_config);
+        let mut deal_rng = rand::rngs::ChaCha20Rng::seed_from_u64(1);
+        game_state.reset_and_draw_tiles(&game_config, &mut deal_rng);
+        let known = game_state.players[1].rack[..2].to_vec();
+        game_state.players[1]
+            .known
+            .present
+            .extend_from_slice(&known);
+        // vowels are common enough to show up without the constraint.
+        let absent = [1, 5, 9, 15]
+            .into_iter()
+            .filter(|tile| !known.contains(tile))
+            .collect::<Vec<u8>>();
+        game_state.players[1]
+            .known
+            .absent
+            .extend_from_slice(&absent);
+        let mut simmer = Simmer::new(&game_config);
+        simmer.reseed(7);
+        simmer.prepare(&game_config, &game_state, 2);
+        let pristine = simmer.prepared_state().clone();
+        for _ in 0..20 {
+            simmer.restore_prepared(&pristine);
+            simmer.prepare_iteration();
+            let rack = &simmer.initial_game_state.players[1].rack;
+            assert_eq!(rack.len(), 7);
+            assert_eq!(rack[..2], known[..]);
+            assert!(!rack[2..].iter().any(|tile| absent.contains(tile)));
+        }
+    }
+
+    // with nothing to place, both sides pass until the game ends, which only a
+    // rollout to the end gets to.
+    #[test]
+    fn play_to_end_rolls_out_until_the_game_ends() {
+        let game_config = game_config::make_english_game_config();
+        let mut game_state = game_state::GameState::new(&game_config);
+        let mut deal_rng = rand::rngs::ChaCha20Rng::seed_from_u64(1);
+        game_state.reset_and_draw_tiles(&game_config, &mut deal_rng);
+        let kwg = make_tiny_kwg(&[]);
+        let klv = klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES);
+        let pass = movegen::Play::Exchange {
+            tiles: [][..].into(),
+        };
+        let mut simmer = Simmer::new(&game_config);
+        simmer.reseed(5);
+        simmer.prepare(&game_config, &game_state, 2);
+        simmer.prepare_iteration();
+        assert!(!simmer.simulate(&game_config, &kwg, &klv, &pass));
+        simmer.prepare(&game_config, &game_state, PLAY_TO_END);
+        assert_eq!(simmer.win_prob_weightage(), simmer.config().w_out);
+        simmer.prepare_iteration();
+        assert!(simmer.simulate(&game_config, &kwg, &klv, &pass));
+        assert_eq!(
+            simmer.game_state.pass_turns,
+            game_config.num_passes_to_end() as u16
+        );
+    }
+
+    // with more than two players, playing to the end still saturates.
+    #[test]
+    fn play_to_end_saturates_with_four_players() {
+        let game_config =
+            game_config::make_game_config_from_rules(r#"{ "num_players": 4 }"#).unwrap();
+        let mut game_state = game_state::GameState::new(&game_config);
+        let mut deal_rng = rand::rngs::ChaCha20Rng::seed_from_u64(1);
+        game_state.reset_and_draw_tiles(&game_config, &mut deal_rng);
+        let mut simmer = Simmer::new(&game_config);
+        simmer.prepare(&game_config, &game_state, PLAY_TO_END);
+        assert_eq!(simmer.num_rollout_plies, usize::MAX);
+        assert_eq!(simmer.win_prob_weightage(), simmer.config().w_out);
+    }
+
+    // an unfiltered generator and a softmax over one move both reply with the
+    // top move, while a hot softmax over many does not always.
+    #[test]
+    fn ply_policies_choose_the_reply() {
+        let game_config = game_config::make_english_game_config();
+        let kwg = make_tiny_kwg(TWO_LETTER_WORDS);
+        let klv = klv::Klv::<kwg::Node22>::from_bytes_alloc(klv::EMPTY_KLV_BYTES);
+        let mut game_state = game_state::GameState::new(&game_config);
+        let mut deal_rng = rand::rngs::ChaCha20Rng::seed_from_u64(1);
+        game_state.reset_and_draw_tiles(&game_config, &mut deal_rng);
+        let pass = movegen::Play::Exchange {
+            tiles: [][..].into(),
+        };
+        let replies = |ply_policies: Vec<PlyPolicy>| {
+            let mut simmer = Simmer::new(&game_config);
+            simmer.reseed(5);
+            simmer.set_ply_policies(ply_policies);
+            simmer.prepare(&game_config, &game_state, 1);
+            (0..10)
+                .map(|_| {
+                    simmer.prepare_iteration();
+                    simmer.simulate(&game_config, &kwg, &klv, &pass);
+                    simmer.game_state.board_tiles.clone()
+                })
+                .collect::<Vec<_>>()
+        };
+        let top = replies(Vec::new());
+        assert!(top.iter().any(|board| board.iter().any(|&t| t != 0)));
+        assert_eq!(top, replies(vec![PlyPolicy::TopEquity]));
+        assert_eq!(
+            top,
+            replies(vec![PlyPolicy::Filtered(move_filter::GenMoves::Unfiltered)])
+        );
+        assert_eq!(
+            top,
+            replies(vec![PlyPolicy::Softmax {
+                top_n: 1,
+                temperature: 1000.0
+            }])
+        );
+        assert_ne!(
+            top,
+            replies(vec![PlyPolicy::Softmax {
+                top_n: 20,
+                temperature: 1000.0
+            }])
+        );
+    }
 }
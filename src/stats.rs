@@ -72,6 +72,21 @@ impl Stats {
         self.variance().sqrt()
     }
 
+    // the exact state, as bits since a float may not survive a text format.
+    #[inline(always)]
+    pub fn to_bits(&self) -> [u64; 3] {
+        [self.count.to_bits(), self.mean.to_bits(), self.m2.to_bits()]
+    }
+
+    #[inline(always)]
+    pub fn from_bits(bits: [u64; 3]) -> Self {
+        Self {
+            count: f64::from_bits(bits[0]),
+            mean: f64::from_bits(bits[1]),
+            m2: f64::from_bits(bits[2]),
+        }
+    }
+
     // https://www.mathsisfun.com/data/confidence-interval.html
     #[inline(always)]
     pub fn ci_max(&self, z: f64) -> f64 {
@@ -0,0 +1,40 @@
+// Copyright (C) 2020-2026 Andy Kurnia.
+
+// helpers shared by the library's tests.
+
+use super::{alphabet, bites, build, kwg};
+
+// two-letter words, enough for a few turns of real play.
+pub const TWO_LETTER_WORDS: &[&str] = &[
+    "AA", "AB", "AD", "AE", "AG", "AH", "AI", "AL", "AM", "AN", "AR", "AS", "AT", "AW", "BE", "DE",
+    "DO", "ED", "EH", "EL", "EM", "EN", "ER", "ES", "GO", "HE", "HI", "ID", "IN", "IS", "IT", "LA",
+    "LI", "LO", "ME", "NO", "OD", "OE", "OF", "ON", "OR", "OS", "RE", "SO", "TA", "TI", "TO", "UN",
+    "US", "WE",
+];
+
+// the built word graph of just these (english) words, in any order.
+pub fn make_tiny_kwg_bytes(words: &[&str]) -> bites::Bites {
+    let alphabet = alphabet::make_english_alphabet();
+    let reader = alphabet::AlphabetReader::new_for_words(&alphabet);
+    let mut buf = Vec::new();
+    let mut words = words
+        .iter()
+        .map(|word| {
+            reader.set_word(word, &mut buf).unwrap();
+            bites::Bites::from(&buf[..])
+        })
+        .collect::<Vec<_>>();
+    words.sort_unstable();
+    build::build(
+        build::BuildContent::Gaddawg,
+        build::BuildLayout::Wolges,
+        &words,
+    )
+    .unwrap()
+}
+
+// a word graph of just these (english) words, in any order. no words gives a
+// graph that generates no placements.
+pub fn make_tiny_kwg(words: &[&str]) -> kwg::Kwg<'static, kwg::Node22> {
+    kwg::Kwg::<kwg::Node22>::from_bytes_alloc(&make_tiny_kwg_bytes(words))
+}
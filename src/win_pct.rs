@@ -28,15 +28,24 @@
 // full, since you draw back up; only when the bag empties do racks deplete),
 // so the keyed form is the scalar midgame and exact in the endgame at little
 // extra cost.
+//
+// Games with more than two players key on the number of players too, with opp
+// the opponents' racks combined and spreads taken against the best opponent.
+// Those keys are not symmetrized: with several opponents there is no mirror
+// image of the mover's view, and a tied lead is worse than a coin flip.
 
 use std::collections::{BTreeMap, HashMap};
 
-// (bag, my, opp). bag is u16 to cover larger tile sets; rack sizes fit u8.
-pub type Key = (u16, u8, u8);
+// (num_players, bag, my, opp). bag is u16 to cover larger tile sets; rack
+// sizes fit u8.
+pub type Key = (u8, u16, u8, u8);
 
-// CSV format tag and version on the self-describing header line.
+// CSV format tag and version on the self-describing header line. Version 2 is
+// two-player only; version 3 adds a leading players column, and is only
+// written when some key needs it.
 const CSV_TAG: &str = "winpct";
 const CSV_VERSION: &str = "2";
+const CSV_VERSION_N_PLAYER: &str = "3";
 
 // Accumulates the raw per-key delta histogram. Composable via merge.
 #[derive(Default)]
@@ -55,7 +64,21 @@ impl WinPctAccumulator {
     // of `spread` at count-state (bag, my, opp) and finished at `final_spread`.
     // Tallies the raw future swing delta = final_spread - spread.
     pub fn record(&mut self, bag: usize, my: usize, opp: usize, spread: i32, final_spread: i32) {
-        let key = (bag as u16, my as u8, opp as u8);
+        self.record_n_player(2, bag, my, opp, spread, final_spread);
+    }
+
+    // Like record, for a game of num_players players: opp counts every
+    // opponent's tiles, and spreads are against the best opponent.
+    pub fn record_n_player(
+        &mut self,
+        num_players: usize,
+        bag: usize,
+        my: usize,
+        opp: usize,
+        spread: i32,
+        final_spread: i32,
+    ) {
+        let key = (num_players as u8, bag as u16, my as u8, opp as u8);
         *self
             .rows
             .entry(key)
@@ -76,15 +99,24 @@ impl WinPctAccumulator {
 
     // Raw sparse CSV: a structured header line, then one line per key
     //   bag,my,opp,total,delta:count,delta:count,...
-    // (deltas ascending; total = sum of counts, a redundant checksum).
+    // (deltas ascending; total = sum of counts, a redundant checksum). With
+    // any key for other than two players, every line starts with players.
     pub fn to_csv(&self) -> String {
         use std::fmt::Write as _;
-        let mut out = format!("{CSV_TAG},{CSV_VERSION},bag,my,opp\n");
-        for (&(bag, my, opp), hist) in &self.rows {
+        let n_player = self.rows.keys().any(|&(num_players, ..)| num_players != 2);
+        let mut out = if n_player {
+            format!("{CSV_TAG},{CSV_VERSION_N_PLAYER},players,bag,my,opp\n")
+        } else {
+            format!("{CSV_TAG},{CSV_VERSION},bag,my,opp\n")
+        };
+        for (&(num_players, bag, my, opp), hist) in &self.rows {
             if hist.is_empty() {
                 continue;
             }
             let total: u64 = hist.values().sum();
+            if n_player {
+                let _ = write!(out, "{num_players},");
+            }
             let _ = write!(out, "{bag},{my},{opp},{total}");
             for (&delta, &count) in hist {
                 let _ = write!(out, ",{delta}:{count}");
@@ -100,20 +132,35 @@ impl WinPctAccumulator {
             .next()
             .ok_or_else(|| crate::error::new("win_pct: empty csv".into()))?;
         let mut h = header.trim().split(',');
-        if h.next() != Some(CSV_TAG) || h.next() != Some(CSV_VERSION) {
+        if h.next() != Some(CSV_TAG) {
             return_error!("win_pct: bad csv header tag/version".into());
         }
+        let n_player = match h.next() {
+            Some(CSV_VERSION) => false,
+            Some(CSV_VERSION_N_PLAYER) => true,
+            _ => {
+                return_error!("win_pct: bad csv header tag/version".into());
+            }
+        };
+        if n_player && h.next() != Some("players") {
+            return_error!("win_pct: csv header dims must be players,bag,my,opp".into());
+        }
         if h.next() != Some("bag") || h.next() != Some("my") || h.next() != Some("opp") {
             return_error!("win_pct: csv header dims must be bag,my,opp".into());
         }
         let mut acc = WinPctAccumulator::new();
         for line in lines {
             let mut it = line.trim().split(',');
+            let num_players: u8 = if n_player {
+                it.next().unwrap_or("").parse()?
+            } else {
+                2
+            };
             let bag: u16 = it.next().unwrap_or("").parse()?;
             let my: u8 = it.next().unwrap_or("").parse()?;
             let opp: u8 = it.next().unwrap_or("").parse()?;
             let total: u64 = it.next().unwrap_or("").parse()?;
-            let hist = acc.rows.entry((bag, my, opp)).or_default();
+            let hist = acc.rows.entry((num_players, bag, my, opp)).or_default();
             let mut sum = 0u64;
             for tok in it {
                 let (d, c) = tok
@@ -144,9 +191,12 @@ impl WinPctAccumulator {
             };
             let width = (2 * cap + 1) as usize;
             let mut sym = vec![0u64; width];
+            let symmetrize = key.0 == 2;
             for (&delta, &count) in hist {
                 sym[(delta + cap) as usize] += count;
-                sym[(-delta + cap) as usize] += count;
+                if symmetrize {
+                    sym[(-delta + cap) as usize] += count;
+                }
             }
             let total = sym.iter().sum::<u64>() as f64;
             // For lead s the break-even swing is delta == -s; win iff delta > -s
@@ -188,7 +238,22 @@ impl WinPctTable {
     // a caller can fall back to its own estimator only where the table has no
     // data. A spread past the key's observed range still saturates to 0.0 / 1.0.
     pub fn get_opt(&self, spread: i32, bag: usize, my: usize, opp: usize) -> Option<f32> {
-        match self.rows.get(&(bag as u16, my as u8, opp as u8)) {
+        self.get_opt_n_player(2, spread, bag, my, opp)
+    }
+
+    // Like get_opt, for a game of num_players players (see record_n_player).
+    pub fn get_opt_n_player(
+        &self,
+        num_players: usize,
+        spread: i32,
+        bag: usize,
+        my: usize,
+        opp: usize,
+    ) -> Option<f32> {
+        match self
+            .rows
+            .get(&(num_players as u8, bag as u16, my as u8, opp as u8))
+        {
             None => None,
             Some(row) if spread > row.cap => Some(1.0),
             Some(row) if spread < -row.cap => Some(0.0),
@@ -422,4 +487,32 @@ mod tests {
             );
         }
     }
+
+    // More-than-two-player keys stay apart from two-player ones, are not
+    // symmetrized, and round-trip through the version 3 csv.
+    #[test]
+    fn n_player_keys_are_separate_and_round_trip() {
+        let mut acc = WinPctAccumulator::new();
+        for &v in &[-30, -10, -5] {
+            acc.record_n_player(3, 40, 7, 14, 0, v);
+        }
+        acc.record(40, 7, 14, 0, 20);
+        let t = acc.finalize();
+        // every sampled three-player future swing lost ground, so a tied lead
+        // is a sure loss rather than the symmetrized coin flip.
+        assert_eq!(t.get_opt_n_player(3, 0, 40, 7, 14), Some(0.0));
+        assert!((t.get(0, 40, 7, 14) - 0.5).abs() < EPS);
+        assert_eq!(t.get_opt_n_player(4, 0, 40, 7, 14), None);
+
+        let csv = acc.to_csv();
+        assert_eq!(csv.lines().next().unwrap(), "winpct,3,players,bag,my,opp");
+        let t2 = WinPctTable::from_csv(&csv).unwrap();
+        for s in [-40, -20, -7, 0, 7, 20, 40] {
+            assert_eq!(
+                t.get_opt_n_player(3, s, 40, 7, 14),
+                t2.get_opt_n_player(3, s, 40, 7, 14)
+            );
+            assert_eq!(t.get_opt(s, 40, 7, 14), t2.get_opt(s, 40, 7, 14));
+        }
+    }
 }